@@ -7,11 +7,17 @@
 //! Cargo re-run the build script whenever `memory.x` is changed,
 //! updating `memory.x` ensures a rebuild of the application with the
 //! new memory settings.
+//!
+//! It also generates the fonts in `assets/fonts`,
+//! see `build/fonts.rs`.
 
 use std::env;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+#[path = "build/fonts.rs"]
+mod fonts;
 
 fn main() {
     // Put `memory.x` in our output directory and ensure it's
@@ -23,9 +29,17 @@ fn main() {
         .unwrap();
     println!("cargo:rustc-link-search={}", out.display());
 
+    // Generate the fonts, included by `src/fonts/mod.rs`.
+    File::create(out.join("fonts.rs"))
+        .unwrap()
+        .write_all(fonts::generate(Path::new("assets/fonts")).as_bytes())
+        .unwrap();
+
     // By default, Cargo will re-run a build script whenever
     // any file in the project changes. By specifying `memory.x`
     // here, we ensure the build script is only re-run when
     // `memory.x` is changed.
     println!("cargo:rerun-if-changed=memory.x");
+    println!("cargo:rerun-if-changed=build/fonts.rs");
+    println!("cargo:rerun-if-changed=assets/fonts");
 }
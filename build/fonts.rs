@@ -0,0 +1,657 @@
+//! Generates `ROMFont` and `ProportionalFont` statics from the BDF and PSF
+//! font files in `assets/fonts`.
+//!
+//! Every `.bdf` and `.psf` file there becomes a font, named after the file:
+//! `vga1-8x16.psf` becomes `VGA1_8X16`. By default
+//!
+//! - the font keeps every character from its first to its last one,
+//! - fonts with 1 bit per pixel whose glyphs all advance the same become a
+//!   `ROMFont`, every glyph drawn in a cell as wide as the advance and as
+//!   high as the line,
+//! - other fonts become a `ProportionalFont`, each glyph keeping its own
+//!   bounding box and advance width. BDF 2.3 fonts with 2, 4 or 8 bits per
+//!   pixel keep their coverage levels for anti-aliased rendering,
+//!   `tools/ttf2bdf` makes such fonts,
+//! - PSF fonts without a Unicode table keep their glyphs in font order and
+//!   map characters through `CodePage::Cp437`, like the VGA ROM.
+//!
+//! An optional `fonts.txt` in the same directory changes these for some
+//! files, one file per line followed by what to change:
+//!
+//! ```text
+//! # file          options
+//! ter-116n.psf    name=TERMINUS_8X16 range=20-7e
+//! unifont.bdf     range=0-24f proportional
+//! vga-cp437.bdf   fixed cp437
+//! ```
+//!
+//! - `name=` sets the name of the static.
+//! - `range=` is the first and last character, in hexadecimal, to keep.
+//! - `fixed` and `proportional` choose the kind of font.
+//! - `cp437` keeps the glyphs in font order instead of looking up their
+//!   encoding or Unicode table, and maps characters through
+//!   `CodePage::Cp437`.
+//!
+//! Characters of the range missing from the font use the default character of
+//! the font if there is one, and are blank otherwise.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// A glyph of an imported font.
+#[derive(Clone, Default)]
+struct Glyph {
+    /// the width of the bitmap
+    width: usize,
+    /// the height of the bitmap
+    height: usize,
+    /// the distance from the pen position to the left of the bitmap
+    x_offset: i32,
+    /// the distance from the top of the line to the top of the bitmap
+    y_offset: i32,
+    /// the distance from the pen position to the next one
+    advance: usize,
+    /// the coverage of the pixels, row by row
+    pixels: Vec<u8>,
+}
+
+impl Glyph {
+    fn pixel(&self, x: i32, y: i32) -> u8 {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return 0;
+        }
+        self.pixels[y as usize * self.width + x as usize]
+    }
+}
+
+/// An imported font.
+struct Font {
+    /// the height of a line
+    height: usize,
+    /// the bits of coverage per pixel
+    bpp: usize,
+    /// the glyphs by character code
+    glyphs: BTreeMap<u32, Glyph>,
+    /// the character code used for missing characters
+    default_char: Option<u32>,
+    /// whether the glyphs are in font order, with no encoding of their own
+    by_index: bool,
+}
+
+/// How a font is emitted.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Fixed,
+    Proportional,
+}
+
+/// What `fonts.txt` changes for a file.
+#[derive(Default)]
+struct Overrides {
+    name: Option<String>,
+    range: Option<(u32, u32)>,
+    kind: Option<Kind>,
+    cp437: bool,
+}
+
+/// A font to generate.
+struct Entry {
+    name: String,
+    file: String,
+    first: u32,
+    last: u32,
+    kind: Kind,
+    cp437: bool,
+}
+
+/// Reads the fonts in `dir` and returns the generated Rust code.
+pub fn generate(dir: &Path) -> String {
+    let mut out = String::new();
+    let mut overrides = match fs::read_to_string(dir.join("fonts.txt")) {
+        Ok(text) => parse_overrides(&text),
+        Err(_) => BTreeMap::new(),
+    };
+    let mut files: Vec<String> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|file| file.ends_with(".bdf") || file.ends_with(".psf"))
+            .collect(),
+        Err(_) => return out,
+    };
+    files.sort();
+    for file in files {
+        let options = overrides.remove(&file).unwrap_or_default();
+        let data = fs::read(dir.join(&file)).unwrap_or_else(|e| panic!("{}: {}", file, e));
+        let font = if file.ends_with(".bdf") {
+            parse_bdf(&String::from_utf8_lossy(&data), options.cp437)
+        } else {
+            parse_psf(&data, options.cp437)
+        }
+        .unwrap_or_else(|e| panic!("{}: {}", file, e));
+        let entry = entry(file, options, &font);
+        match entry.kind {
+            Kind::Fixed => emit_fixed(&mut out, &entry, &font),
+            Kind::Proportional => emit_proportional(&mut out, &entry, &font),
+        }
+    }
+    if let Some(file) = overrides.keys().next() {
+        panic!("fonts.txt: no font file {}", file);
+    }
+    out
+}
+
+fn parse_overrides(text: &str) -> BTreeMap<String, Overrides> {
+    let mut overrides = BTreeMap::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let file = fields.next().unwrap().to_string();
+        let options =
+            parse_options(fields).unwrap_or_else(|e| panic!("fonts.txt:{}: {}", n + 1, e));
+        overrides.insert(file, options);
+    }
+    overrides
+}
+
+fn parse_options<'a>(fields: impl Iterator<Item = &'a str>) -> Result<Overrides, String> {
+    let mut options = Overrides::default();
+    for field in fields {
+        match field.split_once('=') {
+            Some(("name", name)) => options.name = Some(name.to_string()),
+            Some(("range", range)) => {
+                let (first, last) = range
+                    .split_once('-')
+                    .ok_or("the range should look like `20-7e`")?;
+                let first = u32::from_str_radix(first, 16).map_err(|e| e.to_string())?;
+                let last = u32::from_str_radix(last, 16).map_err(|e| e.to_string())?;
+                if first > last || char::from_u32(last + 1).is_none() {
+                    return Err(format!("invalid range {}", range));
+                }
+                options.range = Some((first, last));
+            }
+            None if field == "fixed" => options.kind = Some(Kind::Fixed),
+            None if field == "proportional" => options.kind = Some(Kind::Proportional),
+            None if field == "cp437" => options.cp437 = true,
+            _ => return Err(format!("unknown option `{}`", field)),
+        }
+    }
+    Ok(options)
+}
+
+/// Returns what to generate for a font, from the overrides or the defaults.
+fn entry(file: String, options: Overrides, font: &Font) -> Entry {
+    let name = options.name.unwrap_or_else(|| {
+        let stem = file
+            .rsplit_once('.')
+            .map_or(file.as_str(), |(stem, _)| stem);
+        stem.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    });
+    let (first, last) = options.range.unwrap_or_else(|| {
+        let first = font.glyphs.keys().next().copied();
+        let last = font.glyphs.keys().next_back().copied();
+        first
+            .zip(last)
+            .unwrap_or_else(|| panic!("{}: the font has no glyphs", file))
+    });
+    let kind = options.kind.unwrap_or_else(|| {
+        let mut advances = font.glyphs.values().map(|glyph| glyph.advance);
+        let first = advances.next();
+        if font.bpp == 1 && advances.all(|advance| Some(advance) == first) {
+            Kind::Fixed
+        } else {
+            Kind::Proportional
+        }
+    });
+    Entry {
+        name,
+        first,
+        last,
+        kind,
+        cp437: options.cp437 || font.by_index,
+        file,
+    }
+}
+
+fn parse_bdf(text: &str, by_index: bool) -> Result<Font, String> {
+    let mut ascent = None;
+    let mut descent = None;
+    let mut bounding_box = (0, 0, 0, 0);
+    let mut default_char = None;
+    let mut bpp = 1;
+    let mut glyphs = BTreeMap::new();
+    let mut index = 0;
+
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let mut words = line.split_whitespace();
+        let numbers = |words: std::str::SplitWhitespace| -> Vec<i32> {
+            words.filter_map(|w| w.parse().ok()).collect()
+        };
+        match words.next() {
+            Some("FONTBOUNDINGBOX") => {
+                let v = numbers(words);
+                if v.len() == 4 {
+                    bounding_box = (v[0], v[1], v[2], v[3]);
+                }
+            }
+            // BDF 2.3 fonts may have a bits per pixel field.
+            Some("SIZE") => {
+                bpp = numbers(words).get(3).copied().unwrap_or(1) as usize;
+                if ![1, 2, 4, 8].contains(&bpp) {
+                    return Err(format!("unsupported {} bits per pixel", bpp));
+                }
+            }
+            Some("FONT_ASCENT") => ascent = numbers(words).first().copied(),
+            Some("FONT_DESCENT") => descent = numbers(words).first().copied(),
+            Some("DEFAULT_CHAR") => default_char = numbers(words).first().map(|&c| c as u32),
+            Some("STARTCHAR") => {
+                let mut encoding = -1;
+                let mut advance = 0;
+                let mut bbx = bounding_box;
+                let mut pixels = Vec::new();
+                let mut in_bitmap = false;
+                for line in lines.by_ref() {
+                    let mut words = line.split_whitespace();
+                    match words.next() {
+                        Some("ENCODING") => {
+                            encoding = numbers(words).first().copied().unwrap_or(-1)
+                        }
+                        Some("DWIDTH") => advance = numbers(words).first().copied().unwrap_or(0),
+                        Some("BBX") => {
+                            let v = numbers(words);
+                            if v.len() == 4 {
+                                bbx = (v[0], v[1], v[2], v[3]);
+                            }
+                        }
+                        Some("BITMAP") => in_bitmap = true,
+                        Some("ENDCHAR") => break,
+                        Some(row) if in_bitmap => {
+                            let digits: Vec<u32> =
+                                row.chars().filter_map(|c| c.to_digit(16)).collect();
+                            if digits.len() != row.len() {
+                                return Err(format!("invalid bitmap row `{}`", row));
+                            }
+                            for x in 0..bbx.0.max(0) as usize {
+                                let mut level = 0;
+                                for bit in x * bpp..(x + 1) * bpp {
+                                    let digit = digits.get(bit / 4).copied().unwrap_or(0);
+                                    level = level << 1 | (digit >> (3 - bit % 4) & 1) as u8;
+                                }
+                                pixels.push(level);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                pixels.resize((bbx.0 * bbx.1).max(0) as usize, 0);
+                let code = if by_index { index } else { encoding };
+                index += 1;
+                if code >= 0 {
+                    glyphs.insert(
+                        code as u32,
+                        Glyph {
+                            width: bbx.0 as usize,
+                            height: bbx.1 as usize,
+                            x_offset: bbx.2,
+                            // The top of the box above the baseline for now,
+                            // converted once the ascent is known.
+                            y_offset: bbx.1 + bbx.3,
+                            advance: advance.max(0) as usize,
+                            pixels,
+                        },
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let ascent = ascent.unwrap_or(bounding_box.1 + bounding_box.3);
+    let descent = descent.unwrap_or(-bounding_box.3);
+    for glyph in glyphs.values_mut() {
+        glyph.y_offset = ascent - glyph.y_offset;
+    }
+    Ok(Font {
+        height: (ascent + descent).max(0) as usize,
+        bpp,
+        glyphs,
+        default_char,
+        by_index,
+    })
+}
+
+fn parse_psf(data: &[u8], by_index: bool) -> Result<Font, String> {
+    let u32_at = |i: usize| u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+    let (header_size, count, glyph_size, height, width, has_table, utf8) =
+        if data.len() >= 4 && data[0..2] == [0x36, 0x04] {
+            let count = if data[2] & 0x01 != 0 { 512 } else { 256 };
+            let height = data[3] as usize;
+            (4, count, height, height, 8, data[2] & 0x06 != 0, false)
+        } else if data.len() >= 32 && data[0..4] == [0x72, 0xb5, 0x4a, 0x86] {
+            (
+                u32_at(8) as usize,
+                u32_at(16) as usize,
+                u32_at(20) as usize,
+                u32_at(24) as usize,
+                u32_at(28) as usize,
+                u32_at(12) & 0x01 != 0,
+                true,
+            )
+        } else {
+            return Err("not a PSF1 or PSF2 font".into());
+        };
+    let table_start = header_size + count * glyph_size;
+    if data.len() < table_start {
+        return Err("truncated glyph data".into());
+    }
+
+    let stride = width.div_ceil(8);
+    let glyph = |i: usize| {
+        let bitmap = &data[header_size + i * glyph_size..];
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(bitmap[y * stride + x / 8] >> (7 - x % 8) & 1);
+            }
+        }
+        Glyph {
+            width,
+            height,
+            x_offset: 0,
+            y_offset: 0,
+            advance: width,
+            pixels,
+        }
+    };
+
+    let mut glyphs = BTreeMap::new();
+    if has_table && !by_index {
+        // Each glyph lists its characters, then sequences we don't use, then a terminator.
+        let mut pos = table_start;
+        for i in 0..count {
+            let mut in_sequence = false;
+            loop {
+                let c = if utf8 {
+                    let Some(&b) = data.get(pos) else { break };
+                    if b == 0xff {
+                        pos += 1;
+                        break;
+                    }
+                    if b == 0xfe {
+                        in_sequence = true;
+                        pos += 1;
+                        continue;
+                    }
+                    let len = match b {
+                        0x00..=0x7f => 1,
+                        0xc0..=0xdf => 2,
+                        0xe0..=0xef => 3,
+                        _ => 4,
+                    };
+                    let end = (pos + len).min(data.len());
+                    let c = std::str::from_utf8(&data[pos..end])
+                        .ok()
+                        .and_then(|s| s.chars().next())
+                        .map(|c| c as u32);
+                    pos = end;
+                    c
+                } else {
+                    if pos + 2 > data.len() {
+                        break;
+                    }
+                    let c = u16::from_le_bytes([data[pos], data[pos + 1]]);
+                    pos += 2;
+                    match c {
+                        0xffff => break,
+                        0xfffe => {
+                            in_sequence = true;
+                            continue;
+                        }
+                        c => Some(c as u32),
+                    }
+                };
+                if let (Some(c), false) = (c, in_sequence) {
+                    glyphs.entry(c).or_insert_with(|| glyph(i));
+                }
+            }
+        }
+    } else {
+        for i in 0..count {
+            glyphs.insert(i as u32, glyph(i));
+        }
+    }
+
+    Ok(Font {
+        height,
+        bpp: 1,
+        glyphs,
+        default_char: None,
+        by_index: by_index || !has_table,
+    })
+}
+
+/// Returns the glyph of a character, or the default one, or a blank one.
+fn lookup(font: &Font, c: u32) -> Glyph {
+    font.glyphs
+        .get(&c)
+        .or_else(|| font.default_char.and_then(|d| font.glyphs.get(&d)))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Appends `bpp` bit pixels to a bit stream, most significant bit first.
+fn push_bits(data: &mut Vec<u8>, bit: &mut usize, bpp: usize, pixels: impl Iterator<Item = u8>) {
+    for level in pixels {
+        if (*bit).is_multiple_of(8) {
+            data.push(0);
+        }
+        *data.last_mut().unwrap() |= level << (8 - bpp - *bit % 8);
+        *bit += bpp;
+    }
+}
+
+fn emit_bytes(out: &mut String, name: &str, data: &[u8]) {
+    writeln!(out, "const {}_DATA: [u8; {}] = [", name, data.len()).unwrap();
+    for chunk in data.chunks(16) {
+        out.push_str("   ");
+        for b in chunk {
+            write!(out, " 0x{:02x},", b).unwrap();
+        }
+        out.push('\n');
+    }
+    out.push_str("];\n");
+}
+
+fn emit_range(entry: &Entry) -> String {
+    format!("'\\u{{{:x}}}'..'\\u{{{:x}}}'", entry.first, entry.last + 1)
+}
+
+fn emit_code_page(entry: &Entry) -> &'static str {
+    if entry.cp437 {
+        ".with_code_page(CodePage::Cp437)"
+    } else {
+        ""
+    }
+}
+
+fn emit_bpp(font: &Font) -> String {
+    if font.bpp == 1 {
+        String::new()
+    } else {
+        format!(".with_bpp({})", font.bpp)
+    }
+}
+
+fn emit_fixed(out: &mut String, entry: &Entry, font: &Font) {
+    let glyphs: Vec<Glyph> = (entry.first..=entry.last)
+        .map(|c| lookup(font, c))
+        .collect();
+    let width = glyphs.iter().map(|g| g.advance).max().unwrap_or(0);
+    let height = font.height;
+    if font.bpp != 1 {
+        panic!("{}: fixed fonts need 1 bit per pixel", entry.file);
+    }
+    if width == 0 || width > 255 || height == 0 || height > 255 {
+        panic!(
+            "{}: fixed fonts need a cell of 1 to 255 pixels, got {}x{}",
+            entry.file, width, height
+        );
+    }
+
+    // Rows take whole bytes, the layout `ROMFont::new` expects.
+    let mut data = Vec::new();
+    for glyph in &glyphs {
+        for y in 0..height as i32 {
+            let mut bit = 0;
+            let pixels =
+                (0..width as i32).map(|x| glyph.pixel(x - glyph.x_offset, y - glyph.y_offset));
+            push_bits(&mut data, &mut bit, 1, pixels);
+        }
+    }
+
+    writeln!(out, "// Generated from {}.", entry.file).unwrap();
+    emit_bytes(out, &entry.name, &data);
+    writeln!(
+        out,
+        "pub const {}: ROMFont = ROMFont::new(&{}_DATA, {}, {}, {}){};\n",
+        entry.name,
+        entry.name,
+        height,
+        width,
+        emit_range(entry),
+        emit_code_page(entry)
+    )
+    .unwrap();
+}
+
+fn emit_proportional(out: &mut String, entry: &Entry, font: &Font) {
+    if font.height > 255 {
+        panic!("{}: the line height is over 255", entry.file);
+    }
+    let mut data = Vec::new();
+    let mut infos = String::new();
+    for c in entry.first..=entry.last {
+        let glyph = lookup(font, c);
+        let fits = |v: i32| i8::try_from(v).is_ok();
+        if glyph.width > 255 || glyph.height > 255 || glyph.advance > 255 {
+            panic!("{}: glyph {:#x} is over 255 pixels", entry.file, c);
+        }
+        if !fits(glyph.x_offset) || !fits(glyph.y_offset) {
+            panic!(
+                "{}: the offsets of glyph {:#x} are out of range",
+                entry.file, c
+            );
+        }
+        let offset = data.len();
+        let mut bit = 0;
+        push_bits(&mut data, &mut bit, font.bpp, glyph.pixels.iter().copied());
+        writeln!(
+            infos,
+            "    GlyphInfo {{ offset: {}, width: {}, height: {}, x_offset: {}, y_offset: {}, advance: {} }},",
+            offset, glyph.width, glyph.height, glyph.x_offset, glyph.y_offset, glyph.advance
+        )
+        .unwrap();
+    }
+
+    let count = entry.last - entry.first + 1;
+    writeln!(out, "// Generated from {}.", entry.file).unwrap();
+    emit_bytes(out, &entry.name, &data);
+    writeln!(
+        out,
+        "const {}_GLYPHS: [GlyphInfo; {}] = [",
+        entry.name, count
+    )
+    .unwrap();
+    out.push_str(&infos);
+    out.push_str("];\n");
+    writeln!(
+        out,
+        "pub const {}: ProportionalFont = ProportionalFont::new(&{}_DATA, &{}_GLYPHS, {}, {}){}{};\n",
+        entry.name,
+        entry.name,
+        entry.name,
+        font.height,
+        emit_range(entry),
+        emit_code_page(entry),
+        emit_bpp(font)
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generated() -> String {
+        generate(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fonts"))
+    }
+
+    /// Returns the bytes of the `<name>_DATA` array in the generated code.
+    fn data(code: &str, name: &str) -> Vec<u8> {
+        let start = code.find(&format!("const {}_DATA", name)).unwrap();
+        let array = &code[start..];
+        let array = &array[array.find("= [").unwrap() + 3..array.find("];").unwrap()];
+        array
+            .split(',')
+            .map(str::trim)
+            .filter(|byte| !byte.is_empty())
+            .map(|byte| u8::from_str_radix(byte.trim_start_matches("0x"), 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn a_psf_font_with_a_unicode_table_becomes_a_rom_font_of_its_characters() {
+        let code = generated();
+        assert!(code.contains(
+            "pub const TINY_4X6: ROMFont = ROMFont::new(&TINY_4X6_DATA, 6, 4, '\\u{3f}'..'\\u{62}');"
+        ));
+        let data = data(&code, "TINY_4X6");
+        assert_eq!(data.len(), 35 * 6);
+        let glyph = |c: char| &data[(c as usize - 0x3f) * 6..][..6];
+        assert_eq!(glyph('?'), [0x60, 0x90, 0x20, 0x40, 0x00, 0x40]);
+        assert_eq!(glyph('A'), [0x60, 0x90, 0xf0, 0x90, 0x90, 0x00]);
+        // 'a' shares the glyph of 'A', the sequence A + U+0301 is ignored.
+        assert_eq!(glyph('a'), glyph('A'));
+        assert_eq!(glyph('B'), [0xe0, 0x90, 0xe0, 0x90, 0xe0, 0x00]);
+        assert_eq!(glyph('C'), [0; 6]);
+    }
+
+    #[test]
+    fn a_psf_font_without_a_table_keeps_font_order_and_the_overrides_of_fonts_txt() {
+        let code = generated();
+        assert!(code.contains(
+            "pub const CODES: ROMFont = ROMFont::new(&CODES_DATA, 2, 8, '\\u{41}'..'\\u{43}')\
+             .with_code_page(CodePage::Cp437);"
+        ));
+        assert_eq!(data(&code, "CODES"), [0x41, 0x00, 0x42, 0x00]);
+    }
+
+    #[test]
+    fn a_bdf_font_with_different_advances_becomes_a_proportional_font() {
+        let code = generated();
+        assert!(code.contains(
+            "pub const NARROW: ProportionalFont = \
+             ProportionalFont::new(&NARROW_DATA, &NARROW_GLYPHS, 4, '\\u{69}'..'\\u{6e}');"
+        ));
+        assert_eq!(data(&code, "NARROW"), [0xa0, 0xf4]);
+        assert!(code.contains(
+            "GlyphInfo { offset: 0, width: 1, height: 3, x_offset: 0, y_offset: 0, advance: 2 },"
+        ));
+        assert!(code.contains(
+            "GlyphInfo { offset: 1, width: 3, height: 2, x_offset: 0, y_offset: 1, advance: 4 },"
+        ));
+    }
+}
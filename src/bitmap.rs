@@ -0,0 +1,544 @@
+use crate::canvas::{Canvas, Rect};
+use crate::trig::sin_cos;
+
+/// The pixels a blit draws at once.
+const CHUNK: usize = 128;
+
+/// How the pixels of a bitmap are stored, row by row from the top.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// RGB565 big-endian, two bytes a pixel, as the display takes them
+    Rgb565,
+    /// 4 bits each of alpha, red, green and blue big-endian, alpha in the top
+    /// bits
+    Argb4444,
+    /// only an alpha byte a pixel, drawn in the blit's color
+    A8,
+}
+
+impl PixelFormat {
+    /// Returns the bytes a pixel takes.
+    pub const fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgb565 | PixelFormat::Argb4444 => 2,
+            PixelFormat::A8 => 1,
+        }
+    }
+}
+
+/// An image in memory to blit onto the display or a framebuffer.
+///
+/// ```ignore
+/// static SPRITE: &[u8] = include_bytes!("sprite.rgb565");
+/// let sprite = Bitmap::new(SPRITE, 32, 32);
+/// bitmap::blit(&mut display, 100, 100, &sprite, &Blit::new().with_color_key(MAGENTA));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Bitmap<'a> {
+    /// the pixels in `format`
+    pub data: &'a [u8],
+    pub width: u16,
+    pub height: u16,
+    pub format: PixelFormat,
+    /// an alpha byte for each pixel, None if the bitmap is opaque or carries
+    /// its own alpha
+    pub mask: Option<&'a [u8]>,
+}
+
+impl<'a> Bitmap<'a> {
+    /// An opaque RGB565 bitmap.
+    ///
+    /// Debug builds check that `data` holds every pixel; pixels past its end
+    /// or the end of a mask are drawn transparent.
+    pub const fn new(data: &'a [u8], width: u16, height: u16) -> Bitmap<'a> {
+        Bitmap::with_format(data, width, height, PixelFormat::Rgb565)
+    }
+
+    pub const fn with_format(
+        data: &'a [u8],
+        width: u16,
+        height: u16,
+        format: PixelFormat,
+    ) -> Bitmap<'a> {
+        let pixels = width as usize * height as usize;
+        debug_assert!(data.len() >= pixels * format.bytes_per_pixel());
+        Bitmap {
+            data,
+            width,
+            height,
+            format,
+            mask: None,
+        }
+    }
+
+    /// Gives each pixel the opacity of the byte at the same place in an A8 mask.
+    pub const fn with_mask(self, mask: &'a [u8]) -> Bitmap<'a> {
+        debug_assert!(mask.len() >= self.width as usize * self.height as usize);
+        Bitmap {
+            mask: Some(mask),
+            ..self
+        }
+    }
+
+    /// Returns the color and opacity of the pixel at (x, y), drawing an A8
+    /// bitmap in `color`.
+    fn pixel(&self, x: u16, y: u16, color: u16) -> (u16, u8) {
+        let index = y as usize * self.width as usize + x as usize;
+        let size = self.format.bytes_per_pixel();
+        let Some(bytes) = self.data.get(index * size..(index + 1) * size) else {
+            return (0, 0);
+        };
+        let (color, alpha) = match self.format {
+            PixelFormat::Rgb565 => (u16::from_be_bytes([bytes[0], bytes[1]]), 255),
+            PixelFormat::Argb4444 => {
+                let argb = u16::from_be_bytes([bytes[0], bytes[1]]);
+                let (a, r, g, b) = (argb >> 12, (argb >> 8) & 15, (argb >> 4) & 15, argb & 15);
+                // Each component widened by repeating its top bits.
+                let (r, g, b) = (r << 1 | r >> 3, g << 2 | g >> 2, b << 1 | b >> 3);
+                (r << 11 | g << 5 | b, a as u8 * 17)
+            }
+            PixelFormat::A8 => (color, bytes[0]),
+        };
+        match self.mask {
+            Some(mask) => {
+                let opacity = mask.get(index).copied().unwrap_or(0);
+                (color, ((alpha as u16 * opacity as u16 + 127) / 255) as u8)
+            }
+            None => (color, alpha),
+        }
+    }
+}
+
+/// How pixels of a scaled or turned bitmap are picked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    /// the pixel nearest each point, fast and blocky
+    Nearest,
+    /// the four pixels around each point blended by how close they are, smooth
+    /// and with soft edges
+    Bilinear,
+}
+
+/// How a bitmap is blitted: which part of it, mirrored or not, and which
+/// pixels are left out.
+#[derive(Clone, Copy, Debug)]
+pub struct Blit {
+    /// the part of the bitmap drawn, None for all of it
+    pub source: Option<Rect>,
+    /// whether the bitmap is mirrored left to right
+    pub flip_horizontal: bool,
+    /// whether the bitmap is mirrored top to bottom
+    pub flip_vertical: bool,
+    /// the color of the pixels left out, if any
+    pub color_key: Option<u16>,
+    /// the color A8 bitmaps are drawn in
+    pub color: u16,
+    /// how pixels are picked when the bitmap is scaled or turned
+    pub filter: Filter,
+}
+
+impl Blit {
+    /// Draws all of a bitmap as it is.
+    pub const fn new() -> Blit {
+        Blit {
+            source: None,
+            flip_horizontal: false,
+            flip_vertical: false,
+            color_key: None,
+            color: 0xffff,
+            filter: Filter::Nearest,
+        }
+    }
+
+    /// Draws only a rectangle of the bitmap, e.g. one sprite of a sheet.
+    pub const fn with_source(self, x: i32, y: i32, width: i32, height: i32) -> Blit {
+        Blit {
+            source: Some(Rect::new(x, y, width, height)),
+            ..self
+        }
+    }
+
+    pub const fn with_flip(self, horizontal: bool, vertical: bool) -> Blit {
+        Blit {
+            flip_horizontal: horizontal,
+            flip_vertical: vertical,
+            ..self
+        }
+    }
+
+    /// Leaves out the pixels of a color, e.g. magenta around a sprite.
+    pub const fn with_color_key(self, color: u16) -> Blit {
+        Blit {
+            color_key: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn with_color(self, color: u16) -> Blit {
+        Blit { color, ..self }
+    }
+
+    pub const fn with_filter(self, filter: Filter) -> Blit {
+        Blit { filter, ..self }
+    }
+}
+
+impl Default for Blit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the color and opacity of the pixel at (x, y) of the part of a
+/// bitmap a blit draws, flipped and keyed, transparent outside it.
+fn texel(bitmap: &Bitmap, source: &Rect, blit: &Blit, x: i32, y: i32) -> (u16, u8) {
+    if x < 0 || y < 0 || x >= source.width || y >= source.height {
+        return (0, 0);
+    }
+    let x = match blit.flip_horizontal {
+        true => source.right() - 1 - x,
+        false => source.x + x,
+    };
+    let y = match blit.flip_vertical {
+        true => source.bottom() - 1 - y,
+        false => source.y + y,
+    };
+    match bitmap.pixel(x as u16, y as u16, blit.color) {
+        (color, _) if blit.color_key == Some(color) => (color, 0),
+        texel => texel,
+    }
+}
+
+/// Draws pixels of a row of the canvas, blended unless they're all opaque.
+pub(crate) fn draw_pixels<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    x: i32,
+    y: i32,
+    colors: &[u16],
+    alphas: &[u8],
+) {
+    if alphas.iter().all(|&alpha| alpha == 255) {
+        canvas.draw_span(x as u16, y as u16, colors);
+    } else {
+        canvas.blend_span(x as u16, y as u16, colors, alphas);
+    }
+}
+
+/// Draws a bitmap with its top left at local (x, y), clipped to the viewport.
+///
+/// Pixels with some transparency are blended over a framebuffer; the display
+/// can't be read back, so it only draws those at least half opaque.
+pub fn blit<C: Canvas + ?Sized>(canvas: &mut C, x: i32, y: i32, bitmap: &Bitmap, blit: &Blit) {
+    let bounds = Rect::new(0, 0, bitmap.width as i32, bitmap.height as i32);
+    let source = blit.source.unwrap_or(bounds).intersect(&bounds);
+    let (origin_x, origin_y) = canvas.origin();
+    let target = Rect::new(x + origin_x, y + origin_y, source.width, source.height);
+    let visible = target.intersect(&canvas.clip());
+    if visible.is_empty() {
+        return;
+    }
+
+    let mut colors = [0u16; CHUNK];
+    let mut alphas = [0u8; CHUNK];
+    for row in visible.y..visible.bottom() {
+        let mut column = visible.x;
+        while column < visible.right() {
+            let count = ((visible.right() - column) as usize).min(CHUNK);
+            for i in 0..count {
+                let dx = column + i as i32 - target.x;
+                (colors[i], alphas[i]) = texel(bitmap, &source, blit, dx, row - target.y);
+            }
+            draw_pixels(canvas, column, row, &colors[..count], &alphas[..count]);
+            column += count as i32;
+        }
+    }
+}
+
+/// Draws a bitmap scaled and turned `angle` degrees counterclockwise around
+/// its center, which lands on local (cx, cy). The scale has 8 fractional
+/// bits, 256 for the bitmap's own size.
+///
+/// Each pixel drawn is mapped back onto the bitmap in fixed point and picked
+/// by the blit's filter; the bilinear edges blend like other transparency.
+///
+/// ```ignore
+/// let blit = Blit::new().with_filter(Filter::Bilinear);
+/// bitmap::rotozoom(&mut framebuffer, 120, 120, &sprite, angle, 384, &blit);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn rotozoom<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    cx: i32,
+    cy: i32,
+    bitmap: &Bitmap,
+    angle: i32,
+    scale: i32,
+    blit: &Blit,
+) {
+    let bounds = Rect::new(0, 0, bitmap.width as i32, bitmap.height as i32);
+    let source = blit.source.unwrap_or(bounds).intersect(&bounds);
+    if source.is_empty() || scale <= 0 {
+        return;
+    }
+    let (sin, cos) = sin_cos(angle);
+
+    // The box the turned bitmap fits in, a pixel wider all round for rounding
+    // and the bilinear edges.
+    let half_width = source.width as i64 * scale as i64 / 2;
+    let half_height = source.height as i64 * scale as i64 / 2;
+    let reach_x = (half_width * cos.abs() as i64 + half_height * sin.abs() as i64) >> 22;
+    let reach_y = (half_width * sin.abs() as i64 + half_height * cos.abs() as i64) >> 22;
+    let (reach_x, reach_y) = (reach_x as i32 + 2, reach_y as i32 + 2);
+    let (origin_x, origin_y) = canvas.origin();
+    let (cx, cy) = (cx + origin_x, cy + origin_y);
+    let area = Rect::new(cx - reach_x, cy - reach_y, 2 * reach_x, 2 * reach_y);
+    let visible = area.intersect(&canvas.clip());
+    if visible.is_empty() {
+        return;
+    }
+
+    // How far the point on the bitmap moves, with 16 fractional bits, a
+    // pixel right on the canvas and a pixel down.
+    let du_dx = ((cos as i64) << 10) / scale as i64;
+    let dv_dx = ((sin as i64) << 10) / scale as i64;
+    let (du_dy, dv_dy) = (-dv_dx, du_dx);
+    // Back from the center of the first pixel to the bitmap.
+    let dx = ((visible.x - cx) as i64) * 2 + 1;
+    let dy = ((visible.y - cy) as i64) * 2 + 1;
+    let mut row_u = ((source.width as i64) << 15) + (dx * du_dx + dy * du_dy) / 2;
+    let mut row_v = ((source.height as i64) << 15) + (dx * dv_dx + dy * dv_dy) / 2;
+
+    let mut colors = [0u16; CHUNK];
+    let mut alphas = [0u8; CHUNK];
+    for row in visible.y..visible.bottom() {
+        let (mut u, mut v) = (row_u as i32, row_v as i32);
+        let mut start = visible.x;
+        let mut count = 0;
+        for column in visible.x..visible.right() {
+            let (color, alpha) = match blit.filter {
+                Filter::Nearest => texel(bitmap, &source, blit, u >> 16, v >> 16),
+                Filter::Bilinear => bilinear(bitmap, &source, blit, u, v),
+            };
+            if alpha > 0 {
+                if count == 0 {
+                    start = column;
+                }
+                colors[count] = color;
+                alphas[count] = alpha;
+                count += 1;
+            }
+            if count > 0 && (alpha == 0 || count == CHUNK || column == visible.right() - 1) {
+                draw_pixels(canvas, start, row, &colors[..count], &alphas[..count]);
+                count = 0;
+            }
+            u += du_dx as i32;
+            v += dv_dx as i32;
+        }
+        row_u += du_dy;
+        row_v += dv_dy;
+    }
+}
+
+/// Returns the color and opacity at (u, v) on the part of a bitmap a blit
+/// draws, with 16 fractional bits, from the four pixels around it weighted by
+/// how close they are and how opaque.
+fn bilinear(bitmap: &Bitmap, source: &Rect, blit: &Blit, u: i32, v: i32) -> (u16, u8) {
+    // From the centers of the pixels.
+    let (u, v) = (u - (1 << 15), v - (1 << 15));
+    let (x, y) = (u >> 16, v >> 16);
+    if x < -1 || y < -1 || x >= source.width || y >= source.height {
+        return (0, 0);
+    }
+    let (fx, fy) = (((u >> 8) & 255) as u32, ((v >> 8) & 255) as u32);
+    let weights = [
+        (256 - fx) * (256 - fy),
+        fx * (256 - fy),
+        (256 - fx) * fy,
+        fx * fy,
+    ];
+    let (mut r, mut g, mut b, mut total) = (0u32, 0u32, 0u32, 0u32);
+    for (i, weight) in weights.into_iter().enumerate() {
+        let (dx, dy) = ((i & 1) as i32, (i >> 1) as i32);
+        let (color, alpha) = texel(bitmap, source, blit, x + dx, y + dy);
+        // Weights of 16 bits, alphas of 8 and components of up to 6 fit.
+        let weight = (weight * alpha as u32) >> 8;
+        r += weight * (color >> 11) as u32;
+        g += weight * ((color >> 5) & 0x3f) as u32;
+        b += weight * (color & 0x1f) as u32;
+        total += weight;
+    }
+    if total == 0 {
+        return (0, 0);
+    }
+    let color = ((r / total) << 11 | (g / total) << 5 | (b / total)) as u16;
+    (color, (total >> 8).min(255) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::framebuffer::Framebuffer;
+    use crate::testing::{assert_golden, buffer};
+    use std::vec::Vec;
+
+    const RED: u16 = 0xf800;
+    const GREEN: u16 = 0x07e0;
+    const BLUE: u16 = 0x001f;
+    const WHITE: u16 = 0xffff;
+
+    /// An 8x8 RGB565 bitmap with a quarter in each color, red at the top left
+    /// and white at the bottom right.
+    fn quarters() -> Vec<u8> {
+        (0..64)
+            .flat_map(|i| {
+                let (x, y) = (i % 8, i / 8);
+                let color = match (x < 4, y < 4) {
+                    (true, true) => RED,
+                    (false, true) => GREEN,
+                    (true, false) => BLUE,
+                    (false, false) => WHITE,
+                };
+                color.to_be_bytes()
+            })
+            .collect()
+    }
+
+    /// Rotozooms the quarters bitmap as it is, twice as big turned 90 degrees
+    /// and, with the nearest pixels only, a half again as big turned 30.
+    fn rotozoomed(name: &str, filter: Filter) {
+        let data = quarters();
+        let sprite = Bitmap::new(&data, 8, 8);
+        let blit = Blit::new().with_filter(filter);
+        let mut pixels = buffer(56, 20);
+        let mut frame = Framebuffer::new(&mut pixels, 56, 20);
+        rotozoom(&mut frame, 8, 10, &sprite, 0, 256, &blit);
+        rotozoom(&mut frame, 45, 10, &sprite, 90, 512, &blit);
+        if filter == Filter::Nearest {
+            rotozoom(&mut frame, 24, 10, &sprite, 30, 384, &blit);
+        }
+        assert_golden(name, &frame);
+    }
+
+    #[test]
+    fn rotozoom_picks_the_nearest_pixels() {
+        rotozoomed("rotozoom_nearest", Filter::Nearest);
+    }
+
+    #[test]
+    fn rotozoom_blends_the_pixels_around_each_point() {
+        rotozoomed("rotozoom_bilinear", Filter::Bilinear);
+    }
+
+    #[test]
+    fn rotozoom_at_its_own_size_draws_the_bitmap_as_it_is() {
+        let data = quarters();
+        let sprite = Bitmap::new(&data, 8, 8);
+        for filter in [Filter::Nearest, Filter::Bilinear] {
+            let mut pixels = buffer(8, 8);
+            let mut frame = Framebuffer::new(&mut pixels, 8, 8);
+            rotozoom(
+                &mut frame,
+                4,
+                4,
+                &sprite,
+                0,
+                256,
+                &Blit::new().with_filter(filter),
+            );
+            for (i, pixel) in data.chunks_exact(2).enumerate() {
+                let (x, y) = ((i % 8) as i32, (i / 8) as i32);
+                let color = u16::from_be_bytes([pixel[0], pixel[1]]);
+                assert_eq!(
+                    frame.get_pixel(x, y),
+                    Some(color),
+                    "{filter:?} at ({x}, {y})"
+                );
+            }
+        }
+    }
+
+    const GRAY: u16 = 0x4208;
+
+    #[test]
+    fn blits_are_keyed_flipped_cut_and_clipped() {
+        let data = quarters();
+        let sprite = Bitmap::new(&data, 8, 8);
+        let mut pixels = buffer(64, 24);
+        let mut frame = Framebuffer::new(&mut pixels, 64, 24);
+        frame.fill(GRAY);
+        let blits = [
+            Blit::new(),
+            Blit::new().with_color_key(RED),
+            Blit::new().with_flip(true, false),
+            Blit::new().with_flip(false, true),
+            Blit::new().with_flip(true, true),
+            Blit::new().with_source(4, 0, 4, 4),
+        ];
+        for (i, b) in blits.iter().enumerate() {
+            blit(&mut frame, 1 + i as i32 * 10, 1, &sprite, b);
+        }
+        // Off the left, the right and the bottom.
+        for (x, y) in [(-4, 12), (60, 12), (40, 20)] {
+            blit(&mut frame, x, y, &sprite, &Blit::new());
+        }
+        assert_golden("blit_options", &frame);
+    }
+
+    #[test]
+    fn blits_blend_their_alpha_and_mask() {
+        // Red fading in to the right, and an alpha byte a pixel fading in down.
+        let argb: Vec<u8> = (0..64u16)
+            .flat_map(|i| ((i % 8 * 2 + 1) << 12 | 0x0f00).to_be_bytes())
+            .collect();
+        let a8: Vec<u8> = (0..64).map(|i| (i / 8 * 36) as u8).collect();
+        // The quarters showing through a checkerboard.
+        let data = quarters();
+        let mask: Vec<u8> = (0..64).map(|i| ((i + i / 8) % 2 * 255) as u8).collect();
+        let mut pixels = buffer(32, 10);
+        let mut frame = Framebuffer::new(&mut pixels, 32, 10);
+        frame.fill(GRAY);
+        let sprite = Bitmap::with_format(&argb, 8, 8, PixelFormat::Argb4444);
+        blit(&mut frame, 1, 1, &sprite, &Blit::new());
+        let sprite = Bitmap::with_format(&a8, 8, 8, PixelFormat::A8);
+        blit(&mut frame, 12, 1, &sprite, &Blit::new().with_color(GREEN));
+        let sprite = Bitmap::new(&data, 8, 8).with_mask(&mask);
+        blit(&mut frame, 23, 1, &sprite, &Blit::new());
+        assert_golden("blit_alpha", &frame);
+    }
+
+    #[test]
+    fn pixels_past_the_data_are_transparent() {
+        let data = [0xff; 6];
+        let short = Bitmap {
+            data: &data,
+            width: 2,
+            height: 2,
+            format: PixelFormat::Rgb565,
+            mask: None,
+        };
+        let mut pixels = buffer(2, 2);
+        let mut frame = Framebuffer::new(&mut pixels, 2, 2);
+        blit(&mut frame, 0, 0, &short, &Blit::new());
+        assert_eq!(frame.get_pixel(0, 1), Some(WHITE));
+        assert_eq!(frame.get_pixel(1, 1), Some(0));
+        let mask = [255];
+        let masked = Bitmap {
+            mask: Some(&mask),
+            ..short
+        };
+        let mut pixels = buffer(2, 2);
+        let mut frame = Framebuffer::new(&mut pixels, 2, 2);
+        blit(&mut frame, 0, 0, &masked, &Blit::new());
+        assert_eq!(frame.get_pixel(0, 0), Some(WHITE));
+        assert_eq!(frame.get_pixel(1, 0), Some(0));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic]
+    fn a_bitmap_shorter_than_its_size_is_caught_in_debug_builds() {
+        Bitmap::new(&[0; 6], 2, 2);
+    }
+}
@@ -0,0 +1,533 @@
+use crate::canvas::{Canvas, Rect};
+use crate::color::rgb;
+
+/// The widest image decoded, the pixels of the line buffer.
+pub const MAX_WIDTH: usize = 320;
+
+const RGB: u32 = 0;
+const RLE8: u32 = 1;
+const RLE4: u32 = 2;
+const BITFIELDS: u32 = 3;
+
+/// Why a BMP can't be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BmpError {
+    /// the data doesn't start with a BMP header
+    NotBmp,
+    /// a kind of BMP this reader doesn't decode
+    Unsupported,
+    /// wider than `MAX_WIDTH`
+    TooWide,
+    /// the data ends before the image does
+    Truncated,
+}
+
+/// A BMP image in memory, e.g. in flash, decoded a row at a time as it's
+/// drawn so the whole image is never in RAM.
+///
+/// 1, 4 and 8-bit palette images, uncompressed or RLE4 and RLE8, and 16, 24
+/// and 32-bit images are read, stored bottom up or top down.
+///
+/// ```ignore
+/// static PHOTO: &[u8] = include_bytes!("photo.bmp");
+/// let bmp = Bmp::new(PHOTO).unwrap();
+/// bmp.draw(&mut display, 0, 0).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Bmp<'a> {
+    data: &'a [u8],
+    width: u16,
+    height: u16,
+    /// whether the first row stored is the top one
+    top_down: bool,
+    bits: u16,
+    compression: u32,
+    /// whether 16-bit pixels are RGB555 rather than RGB565
+    rgb555: bool,
+    /// where the palette starts
+    palette: usize,
+    /// the colors in the palette
+    palette_len: usize,
+    /// the bytes of a palette color, 3 for the oldest BMPs and 4 for the rest
+    palette_entry: usize,
+    /// where the pixels start
+    pixels: usize,
+    /// the bytes of a stored row, a multiple of 4
+    stride: usize,
+}
+
+impl<'a> Bmp<'a> {
+    /// Reads the headers of a BMP.
+    pub fn new(data: &'a [u8]) -> Result<Bmp<'a>, BmpError> {
+        if data.len() < 26 || &data[..2] != b"BM" {
+            return Err(BmpError::NotBmp);
+        }
+        let pixels = u32_at(data, 10)? as usize;
+        let header = u32_at(data, 14)? as usize;
+        let (width, height, bits, compression, colors, palette_entry) = match header {
+            // The OS/2 header of the oldest BMPs.
+            12 => {
+                let (width, height) = (u16_at(data, 18)? as i32, u16_at(data, 20)? as i32);
+                (width, height, u16_at(data, 24)?, RGB, 0, 3)
+            }
+            40.. => {
+                let (width, height) = (u32_at(data, 18)? as i32, u32_at(data, 22)? as i32);
+                let (bits, compression) = (u16_at(data, 28)?, u32_at(data, 30)?);
+                (
+                    width,
+                    height,
+                    bits,
+                    compression,
+                    u32_at(data, 46)? as usize,
+                    4,
+                )
+            }
+            _ => return Err(BmpError::NotBmp),
+        };
+        match (bits, compression) {
+            (1 | 4 | 8 | 16 | 24 | 32, RGB) | (8, RLE8) | (4, RLE4) | (16 | 32, BITFIELDS) => {}
+            _ => return Err(BmpError::Unsupported),
+        }
+        if width <= 0 || height == 0 || height.unsigned_abs() > u16::MAX as u32 {
+            return Err(BmpError::Unsupported);
+        }
+        if width as usize > MAX_WIDTH {
+            return Err(BmpError::TooWide);
+        }
+
+        // The masks of 16 and 32-bit pixels follow a 40-byte header and are
+        // in the larger ones. 32-bit pixels are read with their alpha left out.
+        let rgb555 = match compression {
+            BITFIELDS => match (
+                bits,
+                u32_at(data, 54)?,
+                u32_at(data, 58)?,
+                u32_at(data, 62)?,
+            ) {
+                (16, 0xf800, 0x07e0, 0x001f) | (32, 0xff0000, 0xff00, 0xff) => false,
+                (16, 0x7c00, 0x03e0, 0x001f) => true,
+                _ => return Err(BmpError::Unsupported),
+            },
+            _ => bits == 16,
+        };
+        let masks = match (header, compression) {
+            (40, BITFIELDS) => 12,
+            _ => 0,
+        };
+        let palette = header.saturating_add(14 + masks);
+        let palette_len = match (bits, colors) {
+            (16.., _) => 0,
+            (_, 0) => 1 << bits,
+            (_, colors) => colors.min(1 << bits),
+        };
+        if data.len() < palette.saturating_add(palette_len * palette_entry) {
+            return Err(BmpError::Truncated);
+        }
+
+        let stride = (width as usize * bits as usize).div_ceil(32) * 4;
+        let bmp = Bmp {
+            data,
+            width: width as u16,
+            height: height.unsigned_abs() as u16,
+            top_down: height < 0,
+            bits,
+            compression,
+            rgb555,
+            palette,
+            palette_len,
+            palette_entry,
+            pixels,
+            stride,
+        };
+        let size = match compression {
+            RLE8 | RLE4 => 0,
+            _ => stride * bmp.height as usize,
+        };
+        if data.len() < pixels.saturating_add(size) {
+            return Err(BmpError::Truncated);
+        }
+        Ok(bmp)
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Decodes the image a row at a time in the order it's stored, bottom up
+    /// for most BMPs, calling `line` with the row and its colors.
+    pub fn decode(&self, mut line: impl FnMut(u16, &[u16])) -> Result<(), BmpError> {
+        let mut colors = [0u16; MAX_WIDTH];
+        let colors = &mut colors[..self.width as usize];
+        if let RLE8 | RLE4 = self.compression {
+            let mut rle = Rle::new(&self.data[self.pixels..], self.compression == RLE4);
+            let mut indices = [0u8; MAX_WIDTH];
+            for row in 0..self.height {
+                let indices = &mut indices[..self.width as usize];
+                rle.row(indices)?;
+                for (color, &index) in colors.iter_mut().zip(indices.iter()) {
+                    *color = self.palette_color(index);
+                }
+                line(self.row_y(row), colors);
+            }
+        } else {
+            for row in 0..self.height {
+                self.decode_row(row, colors);
+                line(self.row_y(row), colors);
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the image with its top left at local (x, y), clipped to the
+    /// viewport, a row at a time.
+    ///
+    /// Only the rows that show are decoded, unless the image is compressed.
+    pub fn draw<C: Canvas + ?Sized>(&self, canvas: &mut C, x: i32, y: i32) -> Result<(), BmpError> {
+        let (origin_x, origin_y) = canvas.origin();
+        let target = Rect::new(
+            x + origin_x,
+            y + origin_y,
+            self.width as i32,
+            self.height as i32,
+        );
+        let visible = target.intersect(&canvas.clip());
+        if visible.is_empty() {
+            return Ok(());
+        }
+        let (left, right) = (
+            (visible.x - target.x) as usize,
+            (visible.right() - target.x) as usize,
+        );
+        let draw_row = |canvas: &mut C, y: u16, colors: &[u16]| {
+            let row = target.y + y as i32;
+            if row >= visible.y && row < visible.bottom() {
+                canvas.draw_span(visible.x as u16, row as u16, &colors[left..right]);
+            }
+        };
+
+        if let RLE8 | RLE4 = self.compression {
+            return self.decode(|y, colors| draw_row(canvas, y, colors));
+        }
+        let mut colors = [0u16; MAX_WIDTH];
+        let colors = &mut colors[..self.width as usize];
+        for y in visible.y - target.y..visible.bottom() - target.y {
+            // The same both ways, stored rows to image rows and back.
+            self.decode_row(self.row_y(y as u16), colors);
+            draw_row(canvas, y as u16, colors);
+        }
+        Ok(())
+    }
+
+    /// Returns the row of the image the `row`th one stored is.
+    fn row_y(&self, row: u16) -> u16 {
+        match self.top_down {
+            true => row,
+            false => self.height - 1 - row,
+        }
+    }
+
+    /// Decodes the `row`th row stored of an uncompressed image.
+    fn decode_row(&self, row: u16, colors: &mut [u16]) {
+        let start = self.pixels + row as usize * self.stride;
+        let bytes = &self.data[start..start + self.stride];
+        for (x, color) in colors.iter_mut().enumerate() {
+            *color = match self.bits {
+                1 => self.palette_color((bytes[x / 8] >> (7 - x % 8)) & 1),
+                4 => self.palette_color((bytes[x / 2] >> (4 - x % 2 * 4)) & 15),
+                8 => self.palette_color(bytes[x]),
+                16 => {
+                    let pixel = u16::from_le_bytes([bytes[x * 2], bytes[x * 2 + 1]]);
+                    match self.rgb555 {
+                        // Green widened to 6 bits by repeating its top bit.
+                        true => (pixel & 0x7fe0) << 1 | (pixel & 0x0200) >> 4 | (pixel & 0x1f),
+                        false => pixel,
+                    }
+                }
+                24 => rgb(bytes[x * 3 + 2], bytes[x * 3 + 1], bytes[x * 3]),
+                _ => rgb(bytes[x * 4 + 2], bytes[x * 4 + 1], bytes[x * 4]),
+            };
+        }
+    }
+
+    /// Returns the color of a palette index, black past the end of the
+    /// palette.
+    fn palette_color(&self, index: u8) -> u16 {
+        if index as usize >= self.palette_len {
+            return 0;
+        }
+        let at = self.palette + index as usize * self.palette_entry;
+        rgb(self.data[at + 2], self.data[at + 1], self.data[at])
+    }
+}
+
+/// Decodes RLE8 and RLE4 pixels a row at a time.
+struct Rle<'a> {
+    data: &'a [u8],
+    at: usize,
+    /// whether the pixels are RLE4, two to a byte
+    four: bool,
+    /// empty rows a delta skipped still to come
+    skip_rows: u16,
+    /// where the row after them starts
+    start_x: usize,
+    /// whether the end of the image was reached
+    done: bool,
+}
+
+impl<'a> Rle<'a> {
+    fn new(data: &'a [u8], four: bool) -> Rle<'a> {
+        Rle {
+            data,
+            at: 0,
+            four,
+            skip_rows: 0,
+            start_x: 0,
+            done: false,
+        }
+    }
+
+    /// Decodes the palette indices of the next row. Pixels skipped by a delta
+    /// or the end of a row or the image are index 0.
+    fn row(&mut self, indices: &mut [u8]) -> Result<(), BmpError> {
+        indices.fill(0);
+        if self.skip_rows > 0 {
+            self.skip_rows -= 1;
+            return Ok(());
+        }
+        let mut x = core::mem::take(&mut self.start_x);
+        while !self.done {
+            let (count, value) = (self.byte()?, self.byte()?);
+            if count > 0 {
+                // A run, alternating between two pixels in RLE4.
+                for i in 0..count as usize {
+                    self.put(indices, x + i, value, i);
+                }
+                x += count as usize;
+                continue;
+            }
+            match value {
+                0 => return Ok(()),
+                1 => self.done = true,
+                2 => {
+                    let (dx, dy) = (self.byte()?, self.byte()?);
+                    x += dx as usize;
+                    if dy > 0 {
+                        self.skip_rows = dy as u16 - 1;
+                        self.start_x = x;
+                        return Ok(());
+                    }
+                }
+                count => {
+                    // Pixels as they are, padded to two bytes.
+                    let bytes = match self.four {
+                        true => (count as usize).div_ceil(2),
+                        false => count as usize,
+                    };
+                    if self.data.len() < self.at + bytes {
+                        return Err(BmpError::Truncated);
+                    }
+                    for i in 0..count as usize {
+                        let byte = self.data[self.at + if self.four { i / 2 } else { i }];
+                        self.put(indices, x + i, byte, i);
+                    }
+                    x += count as usize;
+                    self.at += bytes + bytes % 2;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the index at `x` to the `i`th pixel of a byte, its high or low
+    /// half in RLE4.
+    fn put(&self, indices: &mut [u8], x: usize, byte: u8, i: usize) {
+        if let Some(index) = indices.get_mut(x) {
+            *index = match self.four {
+                true if i.is_multiple_of(2) => byte >> 4,
+                true => byte & 15,
+                false => byte,
+            };
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, BmpError> {
+        let byte = *self.data.get(self.at).ok_or(BmpError::Truncated)?;
+        self.at += 1;
+        Ok(byte)
+    }
+}
+
+fn u16_at(data: &[u8], at: usize) -> Result<u16, BmpError> {
+    match data.get(at..at + 2) {
+        Some(bytes) => Ok(u16::from_le_bytes([bytes[0], bytes[1]])),
+        None => Err(BmpError::Truncated),
+    }
+}
+
+fn u32_at(data: &[u8], at: usize) -> Result<u32, BmpError> {
+    match data.get(at..at + 4) {
+        Some(bytes) => Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err(BmpError::Truncated),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::framebuffer::Framebuffer;
+    use crate::testing::{buffer, fixture};
+    use std::vec;
+    use std::vec::Vec;
+
+    const BLACK: u16 = 0x0000;
+    const RED: u16 = 0xf800;
+    const GREEN: u16 = 0x07e0;
+    const BLUE: u16 = 0x001f;
+
+    /// The 5x3 picture of the uncompressed fixtures, palette indices into
+    /// `COLORS`.
+    const PICTURE: [[usize; 5]; 3] = [[1, 2, 3, 0, 1], [0, 0, 1, 1, 2], [3, 2, 1, 0, 3]];
+
+    /// The 5x4 picture of the RLE fixtures, with the pixels a delta skips
+    /// index 0.
+    const RLE_PICTURE: [[usize; 5]; 4] = [
+        [0, 0, 1, 1, 1],
+        [3, 0, 0, 0, 0],
+        [0, 0, 1, 2, 1],
+        [3, 2, 1, 0, 3],
+    ];
+
+    /// The colors of the fixtures' palettes, with #123456 in place of blue in
+    /// the images without one.
+    fn colors(palette: bool) -> [u16; 4] {
+        match palette {
+            true => [BLACK, RED, GREEN, BLUE],
+            false => [BLACK, RED, GREEN, rgb(0x12, 0x34, 0x56)],
+        }
+    }
+
+    /// Returns the rows of a BMP fixture as `decode` gives them, by image
+    /// row.
+    fn decoded(name: &str) -> Vec<Vec<u16>> {
+        let data = fixture(&std::format!("bmp/{name}"));
+        let bmp = Bmp::new(&data).unwrap();
+        let mut rows = vec![Vec::new(); bmp.height() as usize];
+        bmp.decode(|y, colors| rows[y as usize] = colors.to_vec())
+            .unwrap();
+        rows
+    }
+
+    fn expected<const W: usize>(picture: &[[usize; W]], colors: [u16; 4]) -> Vec<Vec<u16>> {
+        picture
+            .iter()
+            .map(|row| row.iter().map(|&index| colors[index]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn palette_images_of_each_depth_decode() {
+        let one_bit: Vec<Vec<u16>> = PICTURE
+            .iter()
+            .map(|row| row.iter().map(|&i| [BLACK, RED][i & 1]).collect())
+            .collect();
+        assert_eq!(decoded("1bpp.bmp"), one_bit);
+        assert_eq!(decoded("4bpp.bmp"), expected(&PICTURE, colors(true)));
+        assert_eq!(decoded("8bpp.bmp"), expected(&PICTURE, colors(true)));
+    }
+
+    #[test]
+    fn true_color_images_of_each_depth_decode() {
+        let colors = colors(false);
+        for name in [
+            "16bpp-565.bmp",
+            "24bpp.bmp",
+            "32bpp.bmp",
+            "32bpp-bitfields.bmp",
+        ] {
+            assert_eq!(decoded(name), expected(&PICTURE, colors), "{name}");
+        }
+        // RGB555 keeps 5 bits of green, so #123456 loses its lowest one.
+        let rgb555 = [BLACK, RED, GREEN, colors[3] & !0x0020];
+        assert_eq!(decoded("16bpp-555.bmp"), expected(&PICTURE, rgb555));
+    }
+
+    #[test]
+    fn top_down_images_draw_like_bottom_up_ones() {
+        let mut drawn = Vec::new();
+        for name in ["24bpp.bmp", "24bpp-top-down.bmp"] {
+            let data = fixture(&std::format!("bmp/{name}"));
+            let bmp = Bmp::new(&data).unwrap();
+            assert_eq!((bmp.width(), bmp.height()), (5, 3));
+            assert_eq!(decoded(name), expected(&PICTURE, colors(false)), "{name}");
+            let mut pixels = buffer(8, 8);
+            let mut frame = Framebuffer::new(&mut pixels, 8, 8);
+            bmp.draw(&mut frame, 4, -1).unwrap();
+            drawn.push(pixels);
+        }
+        assert_eq!(drawn[0], drawn[1]);
+        let frame = Framebuffer::new(&mut drawn[0], 8, 8);
+        // Clipped to the two lower rows and four columns.
+        assert_eq!(frame.get_pixel(4, 0), Some(BLACK));
+        assert_eq!(frame.get_pixel(6, 0), Some(RED));
+        assert_eq!(frame.get_pixel(4, 1), Some(rgb(0x12, 0x34, 0x56)));
+        assert_eq!(frame.get_pixel(3, 1), Some(0));
+        assert_eq!(frame.get_pixel(4, 2), Some(0));
+    }
+
+    #[test]
+    fn rle_runs_literals_deltas_and_line_ends_decode() {
+        for name in ["rle8.bmp", "rle4.bmp"] {
+            assert_eq!(
+                decoded(name),
+                expected(&RLE_PICTURE, colors(true)),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_images_are_errors() {
+        for name in [
+            "1bpp.bmp",
+            "4bpp.bmp",
+            "8bpp.bmp",
+            "16bpp-555.bmp",
+            "16bpp-565.bmp",
+            "24bpp.bmp",
+            "24bpp-top-down.bmp",
+            "32bpp.bmp",
+            "32bpp-bitfields.bmp",
+            "rle8.bmp",
+            "rle4.bmp",
+        ] {
+            let data = fixture(&std::format!("bmp/{name}"));
+            for length in 0..data.len() {
+                let result = Bmp::new(&data[..length]).and_then(|bmp| {
+                    let mut pixels = buffer(8, 8);
+                    let mut frame = Framebuffer::new(&mut pixels, 8, 8);
+                    bmp.draw(&mut frame, 0, 0)
+                });
+                assert!(result.is_err(), "{name} cut to {length} bytes");
+            }
+        }
+    }
+
+    #[test]
+    fn unsupported_images_are_errors() {
+        let mut data = fixture("bmp/16bpp-565.bmp");
+        // Blue and green masks swapped.
+        data[58..62].copy_from_slice(&0x001fu32.to_le_bytes());
+        data[62..66].copy_from_slice(&0x07e0u32.to_le_bytes());
+        assert_eq!(Bmp::new(&data).err(), Some(BmpError::Unsupported));
+        let mut data = fixture("bmp/8bpp.bmp");
+        data[30] = RLE4 as u8;
+        assert_eq!(Bmp::new(&data).err(), Some(BmpError::Unsupported));
+        assert_eq!(Bmp::new(b"PNG").err(), Some(BmpError::NotBmp));
+    }
+}
@@ -0,0 +1,240 @@
+/// The most viewports a `Viewport` can save to go back to.
+const VIEWPORT_DEPTH: usize = 8;
+
+/// Something shapes can be drawn on, the display or a framebuffer.
+///
+/// Shapes are drawn as horizontal spans, one window write each on the display.
+/// The spans are in canvas coordinates, already clipped; shapes are drawn in
+/// local coordinates from `origin`, clipped to `clip`.
+pub trait Canvas {
+    /// Returns the width and height of the canvas.
+    fn size(&self) -> (u16, u16);
+
+    /// Returns where local coordinates start on the canvas.
+    fn origin(&self) -> (i32, i32) {
+        (0, 0)
+    }
+
+    /// Returns the part of the canvas drawing is kept to, in canvas coordinates.
+    fn clip(&self) -> Rect {
+        let (width, height) = self.size();
+        Rect::new(0, 0, width as i32, height as i32)
+    }
+
+    /// Fills `width` pixels of row `y` from `x` with a color.
+    /// The span is inside the canvas.
+    fn fill_span(&mut self, x: u16, y: u16, width: u16, color: u16);
+
+    /// Draws pixels of row `y` from `x` in the colors of `colors`.
+    /// The pixels are inside the canvas.
+    fn draw_span(&mut self, x: u16, y: u16, colors: &[u16]) {
+        for (i, &color) in colors.iter().enumerate() {
+            self.fill_span(x + i as u16, y, 1, color);
+        }
+    }
+
+    /// Draws a block of pixels `width` wide from (x, y), its rows one after
+    /// another in `colors`. The block is inside the canvas.
+    fn draw_block(&mut self, x: u16, y: u16, width: u16, colors: &[u16]) {
+        for (i, row) in colors.chunks(width as usize).enumerate() {
+            self.draw_span(x, y + i as u16, row);
+        }
+    }
+
+    /// Draws pixels of row `y` from `x` in the colors of `colors` with the
+    /// opacities of `alphas`, from 0 to 255. The pixels are inside the canvas.
+    ///
+    /// Canvases that can't be read back draw the pixels at least half opaque.
+    fn blend_span(&mut self, x: u16, y: u16, colors: &[u16], alphas: &[u8]) {
+        let mut start = 0;
+        for i in 0..=colors.len() {
+            if i < colors.len() && alphas[i] >= 128 {
+                continue;
+            }
+            if start < i {
+                self.draw_span(x + start as u16, y, &colors[start..i]);
+            }
+            start = i + 1;
+        }
+    }
+
+    /// Fills a rectangle inside the canvas with a color.
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16) {
+        for row in y..y + height {
+            self.fill_span(x, row, width, color);
+        }
+    }
+}
+
+/// A canvas whose frame memory can be scrolled in hardware, like the ST7789.
+pub trait Scroll {
+    /// Splits the frame memory into a fixed area of `top` lines, `lines`
+    /// scrolled lines and a fixed area of `bottom` lines.
+    fn set_scroll_area(&mut self, top: u16, lines: u16, bottom: u16);
+
+    /// Shows frame memory line `line` at the top of the scrolled lines.
+    fn set_scroll_start(&mut self, line: u16);
+}
+
+/// A rectangle of pixels, which may be partly or wholly off the canvas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub const fn new(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the column right of the rectangle.
+    pub const fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    /// Returns the row below the rectangle.
+    pub const fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    pub const fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.right() && y < self.bottom()
+    }
+
+    /// Returns the rectangle moved by (dx, dy).
+    pub const fn offset(self, dx: i32, dy: i32) -> Rect {
+        Rect::new(self.x + dx, self.y + dy, self.width, self.height)
+    }
+
+    /// Returns the part of the rectangle inside another, empty if none.
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right()).max(x);
+        let bottom = self.bottom().min(other.bottom()).max(y);
+        Rect::new(x, y, right - x, bottom - y)
+    }
+}
+
+/// Where drawing goes on a canvas: an origin local coordinates start from and
+/// a clip rectangle nothing is drawn outside of, with a stack of the earlier
+/// ones to go back to. Widgets can draw in their own coordinates between a
+/// `push` and a `pop`.
+///
+/// ```ignore
+/// display.viewport_mut().push(40, 100, 160, 40);
+/// shapes::fill_rounded_rect(&mut display, -10, 0, 180, 40, 8, BLUE); // trimmed to 160x40
+/// display.viewport_mut().pop();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Viewport {
+    /// where local coordinates start, in canvas coordinates
+    origin: (i32, i32),
+    /// the part of the canvas drawn on, in canvas coordinates
+    clip: Rect,
+    /// the whole canvas
+    bounds: Rect,
+    saved: [((i32, i32), Rect); VIEWPORT_DEPTH],
+    depth: usize,
+}
+
+impl Viewport {
+    /// A viewport over the whole of a canvas.
+    pub const fn new(width: u16, height: u16) -> Viewport {
+        let bounds = Rect::new(0, 0, width as i32, height as i32);
+        Viewport {
+            origin: (0, 0),
+            clip: bounds,
+            bounds,
+            saved: [((0, 0), bounds); VIEWPORT_DEPTH],
+            depth: 0,
+        }
+    }
+
+    /// Returns where local coordinates start, in canvas coordinates.
+    pub fn origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    /// Returns the clip rectangle in canvas coordinates.
+    pub fn clip(&self) -> Rect {
+        self.clip
+    }
+
+    /// Returns how far the clip reaches right of and below the origin, the
+    /// room text has to wrap in.
+    pub fn local_size(&self) -> (u16, u16) {
+        let right = self.clip.right() - self.origin.0;
+        let bottom = self.clip.bottom() - self.origin.1;
+        (
+            right.clamp(0, u16::MAX as i32) as u16,
+            bottom.clamp(0, u16::MAX as i32) as u16,
+        )
+    }
+
+    /// Moves the origin by (dx, dy).
+    pub fn push_translation(&mut self, dx: i32, dy: i32) {
+        self.save();
+        self.origin = (self.origin.0 + dx, self.origin.1 + dy);
+    }
+
+    /// Narrows the clip to a local rectangle.
+    pub fn push_clip(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.save();
+        let rect = Rect::new(x, y, width, height).offset(self.origin.0, self.origin.1);
+        self.clip = self.clip.intersect(&rect);
+    }
+
+    /// Narrows the clip to a local rectangle and moves the origin to its top left.
+    pub fn push(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.push_clip(x, y, width, height);
+        self.origin = (self.origin.0 + x, self.origin.1 + y);
+    }
+
+    /// Goes back to the viewport before the last push.
+    pub fn pop(&mut self) {
+        if self.depth > 0 {
+            self.depth -= 1;
+            (self.origin, self.clip) = self.saved[self.depth];
+        }
+    }
+
+    /// Goes back to the whole canvas, forgetting every push.
+    pub fn reset(&mut self) {
+        self.origin = (0, 0);
+        self.clip = self.bounds;
+        self.depth = 0;
+    }
+
+    /// Returns the part of a local rectangle inside the clip, in canvas
+    /// coordinates, or None if it's all outside.
+    pub fn clip_rect(&self, x: i32, y: i32, width: i32, height: i32) -> Option<Rect> {
+        let rect = Rect::new(x, y, width, height).offset(self.origin.0, self.origin.1);
+        let rect = rect.intersect(&self.clip);
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// Returns the canvas coordinates of a local point, if it's inside the clip.
+    pub fn clip_point(&self, x: i32, y: i32) -> Option<(u16, u16)> {
+        let (x, y) = (x + self.origin.0, y + self.origin.1);
+        self.clip.contains(x, y).then_some((x as u16, y as u16))
+    }
+
+    fn save(&mut self) {
+        assert!(self.depth < VIEWPORT_DEPTH, "too many viewports pushed");
+        self.saved[self.depth] = (self.origin, self.clip);
+        self.depth += 1;
+    }
+}
@@ -0,0 +1,154 @@
+/// How the characters of a font are mapped to its glyphs.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CodePage {
+    /// The glyph index is the Unicode code point.
+    Unicode,
+    /// The glyphs are in IBM code page 437 order, as in the VGA ROM fonts.
+    ///
+    /// ASCII characters, including the control characters, map to themselves,
+    /// so `'\u{1}'` still selects the smiley. Other characters are looked up
+    /// by their Unicode value, e.g. `'═'` selects glyph `0xcd`, so an escape
+    /// like `'\u{cd}'` is `'Í'`, which the code page doesn't have. Glyphs are
+    /// still picked by index with the private use characters `U+F000` to
+    /// `U+F0FF`, made by `cp437_raw`.
+    Cp437,
+}
+
+impl CodePage {
+    /// Returns the glyph index of a character, if the code page has it.
+    pub fn index(&self, c: char) -> Option<u32> {
+        match self {
+            CodePage::Unicode => Some(c as u32),
+            CodePage::Cp437 => match c as u32 {
+                index @ RAW..=0xf0ff => Some(index - RAW),
+                _ => cp437_from_char(c).map(u32::from),
+            },
+        }
+    }
+}
+
+/// The private use character of glyph 0 of a code page 437 font.
+const RAW: u32 = 0xf000;
+
+/// Returns the character that selects a code page 437 glyph by its index, as
+/// the bytes of text in the code page did.
+pub const fn cp437_raw(index: u8) -> char {
+    match char::from_u32(RAW + index as u32) {
+        Some(c) => c,
+        None => ' ',
+    }
+}
+
+/// The Unicode characters of the 256 code page 437 glyphs.
+pub const CP437: [char; 256] = [
+    '\u{0}', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼', //
+    '►', '◄', '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼', //
+    ' ', '!', '"', '#', '$', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/', //
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?', //
+    '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', //
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '^', '_', //
+    '`', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', //
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '{', '|', '}', '~', '⌂', //
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', //
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', //
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', //
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', //
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', //
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', //
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', //
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// Characters missing from code page 437 that have a look-alike glyph.
+const CP437_ALIASES: [(char, u8); 8] = [
+    ('\u{3b2}', 0xe1),  // β, greek small beta
+    ('\u{3a0}', 0xe3),  // Π, greek capital pi
+    ('\u{2211}', 0xe4), // ∑, n-ary summation
+    ('\u{3bc}', 0xe6),  // μ, greek small mu
+    ('\u{2126}', 0xea), // Ω, ohm sign
+    ('\u{3d5}', 0xed),  // ϕ, phi symbol
+    ('\u{2205}', 0xed), // ∅, empty set
+    ('\u{2208}', 0xee), // ∈, element of
+];
+
+/// Returns the code page 437 glyph index of a character.
+pub fn cp437_from_char(c: char) -> Option<u8> {
+    if c.is_ascii() {
+        return Some(c as u8);
+    }
+    if let Some(index) = CP437[0x80..].iter().position(|&x| x == c) {
+        return Some(0x80 + index as u8);
+    }
+    if let Some(index) = CP437[0x01..0x20].iter().position(|&x| x == c) {
+        return Some(0x01 + index as u8);
+    }
+    if c == CP437[0x7f] {
+        return Some(0x7f);
+    }
+    CP437_ALIASES
+        .iter()
+        .find(|&&(alias, _)| alias == c)
+        .map(|&(_, index)| index)
+}
+
+/// Returns the Unicode character of a code page 437 byte.
+pub const fn cp437_to_char(b: u8) -> char {
+    CP437[b as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_maps_to_itself() {
+        for b in 0..0x80u8 {
+            assert_eq!(cp437_from_char(b as char), Some(b));
+        }
+        assert_eq!(CodePage::Cp437.index('A'), Some(0x41));
+        assert_eq!(CodePage::Unicode.index('═'), Some(0x2550));
+    }
+
+    #[test]
+    fn every_glyph_maps_back_from_its_character() {
+        for b in 0..=255u8 {
+            assert_eq!(cp437_from_char(cp437_to_char(b)), Some(b), "{b:#x}");
+        }
+    }
+
+    #[test]
+    fn box_drawing_and_symbols_map_to_their_glyphs() {
+        let glyphs = [
+            ('─', 0xc4),
+            ('│', 0xb3),
+            ('┌', 0xda),
+            ('╬', 0xce),
+            ('═', 0xcd),
+        ];
+        for (c, b) in glyphs {
+            assert_eq!(cp437_from_char(c), Some(b), "{c}");
+        }
+        assert_eq!(cp437_from_char('☺'), Some(0x01));
+        assert_eq!(cp437_from_char('⌂'), Some(0x7f));
+        assert_eq!(cp437_from_char('█'), Some(0xdb));
+        // Look-alikes of characters the code page lacks.
+        assert_eq!(cp437_from_char('β'), Some(cp437_from_char('ß').unwrap()));
+        assert_eq!(cp437_from_char('\u{2126}'), Some(0xea));
+    }
+
+    #[test]
+    fn missing_characters_are_none() {
+        for c in ['€', 'Í', '\u{cd}', '中', '😀'] {
+            assert_eq!(cp437_from_char(c), None, "{c}");
+            assert_eq!(CodePage::Cp437.index(c), None, "{c}");
+        }
+    }
+
+    #[test]
+    fn raw_characters_pick_glyphs_by_index() {
+        assert_eq!(CodePage::Cp437.index(cp437_raw(0xcd)), Some(0xcd));
+        assert_eq!(CodePage::Cp437.index(cp437_raw(0)), Some(0));
+        assert_eq!(CodePage::Cp437.index(cp437_raw(0xff)), Some(0xff));
+        assert_eq!(CodePage::Unicode.index(cp437_raw(1)), Some(0xf001));
+    }
+}
@@ -0,0 +1,83 @@
+/// Returns the RGB565 color of 8-bit red, green and blue components.
+pub const fn rgb(r: u8, g: u8, b: u8) -> u16 {
+    ((r as u16 >> 3) << 11) | ((g as u16 >> 2) << 5) | (b as u16 >> 3)
+}
+
+/// Returns the 8-bit red, green and blue components of a RGB565 color.
+pub const fn components(color: u16) -> (u8, u8, u8) {
+    let r = (color >> 11) as u8 & 0x1f;
+    let g = (color >> 5) as u8 & 0x3f;
+    let b = color as u8 & 0x1f;
+    (r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2)
+}
+
+/// Mixes two color components, `alpha` from 0 to 255, rounding without a division.
+const fn mix(f: u32, b: u32, alpha: u32) -> u32 {
+    let x = f * alpha + b * (255 - alpha) + 128;
+    (x + (x >> 8)) >> 8
+}
+
+/// Returns `fg` drawn over `bg` with an opacity from 0 (only `bg`) to 255 (only `fg`).
+pub const fn blend(fg: u16, bg: u16, alpha: u8) -> u16 {
+    match alpha {
+        0 => bg,
+        255 => fg,
+        _ => {
+            let a = alpha as u32;
+            let r = mix((fg >> 11) as u32, (bg >> 11) as u32, a);
+            let g = mix((fg >> 5 & 0x3f) as u32, (bg >> 5 & 0x3f) as u32, a);
+            let b = mix((fg & 0x1f) as u32, (bg & 0x1f) as u32, a);
+            (r << 11 | g << 5 | b) as u16
+        }
+    }
+}
+
+/// The order the pixels of a 4x4 block round up in when dithering.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Rounds an 8-bit component down to `bits`, up at the pixels of a 4x4 block
+/// of `rank` below its remainder.
+const fn dither_component(c: u8, bits: u32, rank: u8) -> u16 {
+    let shift = 8 - bits;
+    let c = c as u16 + ((rank as u16) << shift >> 4);
+    (if c > 255 { 255 } else { c }) >> shift
+}
+
+/// Returns the RGB565 color of 8-bit components like `rgb`, ordered dithered
+/// for the pixel at (x, y) so that an area of it averages to the exact color.
+pub const fn dithered_rgb(r: u8, g: u8, b: u8, x: i32, y: i32) -> u16 {
+    let rank = BAYER[(y & 3) as usize][(x & 3) as usize];
+    dither_component(r, 5, rank) << 11
+        | dither_component(g, 6, rank) << 5
+        | dither_component(b, 5, rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dithering_keeps_exact_colors() {
+        for (x, y) in [(0, 0), (1, 2), (3, 3), (-1, -5)] {
+            assert_eq!(dithered_rgb(0x40, 0x84, 0xf8, x, y), rgb(0x40, 0x84, 0xf8));
+            assert_eq!(dithered_rgb(255, 255, 255, x, y), 0xffff);
+        }
+    }
+
+    #[test]
+    fn dithering_rounds_up_as_many_pixels_as_the_remainder() {
+        let block = |r, g, b| {
+            let colors = (0..16).map(|i| dithered_rgb(r, g, b, i % 4, i / 4));
+            colors.fold((0, 0, 0), |(r, g, b), color| {
+                (
+                    r + (color >> 11),
+                    g + (color >> 5 & 0x3f),
+                    b + (color & 0x1f),
+                )
+            })
+        };
+        // Half a step of red and green, a quarter of blue.
+        assert_eq!(block(4, 2, 2), (8, 8, 4));
+        assert_eq!(block(0x44, 0x82, 0x0a), (8 * 16 + 8, 32 * 16 + 8, 16 + 4));
+    }
+}
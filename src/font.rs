@@ -1,5 +1,7 @@
 use core::ops::Range;
 
+use crate::codepage::CodePage;
+
 /// Font trait
 pub trait Font{
     /// Returns the bit the width of a character in the font.
@@ -12,27 +14,137 @@ pub trait Font{
     /// Returns the width of a character in the font.
     fn get_height(&self) -> u8;
 
+    /// Returns the bitmap of a character and where to draw it.
+    ///
+    /// By default the bitmap of `get_char` fills a cell as wide as the character,
+    /// its rows taking whole bytes like those of `ROMFont::new`.
+    fn get_glyph(&self, c: char) -> Option<Glyph<'_>> {
+        let (data, width) = self.get_char(c)?;
+        Some(Glyph {
+            data,
+            width,
+            height: self.get_height(),
+            x_offset: 0,
+            y_offset: 0,
+            advance: width,
+            bpp: 1,
+            stride: width.div_ceil(8) as u16 * 8,
+            packing: Packing::RowMajor,
+        })
+    }
+
+    /// Returns the horizontal adjustment of the pen between two characters.
+    fn get_kerning(&self, _left: char, _right: char) -> i8 {
+        0
+    }
+
     /// Returns the width and height of a character in the font.
     /// It will break lines when meeting a newline character.
     fn measure_text(&self, text: &str) -> (u16,u16){
         let mut width:u16 = 0;
         let mut max_width:u16 = 0;
         let mut height:u16 = 0;
+        let mut prev: Option<char> = None;
         for c in text.chars(){
             if c == '\n' {
                 if width > max_width {
                     max_width = width;
                 }
                 width = 0;
-                height += self.get_height() as u16;
-            }else{
-                width += self.get_width(c).unwrap_or(0) as u16;
+                height = height.saturating_add(self.get_height() as u16);
+                prev = None;
+            }else if let Some(w) = self.get_width(c) {
+                if let Some(p) = prev {
+                    width = width.saturating_add_signed(self.get_kerning(p, c) as i16);
+                }
+                width = width.saturating_add(w as u16);
+                prev = Some(c);
             }
         }
         if width > max_width {
             max_width = width;
         }
-        (max_width,height+self.get_height() as u16)
+        (max_width,height.saturating_add(self.get_height() as u16))
+    }
+}
+
+/// How the pixels of a bitmap are laid out.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Packing {
+    /// rows from the top, pixels from the left in the most significant bits first
+    RowMajor,
+    /// pages of 8 rows from the top, each a byte per column from the left with
+    /// the top pixel in the least significant bit, like the fonts of SSD1306 and
+    /// other page-addressed displays
+    ColumnMajor,
+}
+
+/// The bitmap of a character and where to draw it.
+///
+/// The bitmap has `bpp` bits of coverage per pixel, in rows or pages starting
+/// every `stride` bits.
+#[derive(Clone, Copy)]
+pub struct Glyph<'a> {
+    /// the bitmap
+    pub data: &'a [u8],
+    /// the width of the bitmap
+    pub width: u8,
+    /// the height of the bitmap
+    pub height: u8,
+    /// the distance from the pen position to the left of the bitmap
+    pub x_offset: i8,
+    /// the distance from the top of the line to the top of the bitmap
+    pub y_offset: i8,
+    /// the distance from the pen position to the next one
+    pub advance: u8,
+    /// the bits of coverage per pixel, 1, 2, 4 or 8
+    pub bpp: u8,
+    /// the bits from the start of a row, or page, to the next
+    pub stride: u16,
+    /// the layout of the bitmap
+    pub packing: Packing,
+}
+
+impl<'a> Glyph<'a> {
+    /// Returns the coverage of a pixel of the bitmap, from 0 to 255.
+    /// Pixels outside the bitmap are not covered.
+    pub fn coverage(&self, x: i32, y: i32) -> u8 {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return 0;
+        }
+        let bpp = self.bpp as usize;
+        let mask = (1u16 << bpp) - 1;
+        let (x, y) = (x as usize, y as usize);
+        let level = match self.packing {
+            Packing::RowMajor => {
+                let i = y * self.stride as usize + x * bpp;
+                self.data[i >> 3] as u16 >> (8 - bpp - (i & 7))
+            }
+            Packing::ColumnMajor => {
+                let rows = 8 / bpp;
+                let i = y / rows * self.stride as usize + x * 8 + y % rows * bpp;
+                self.data[i >> 3] as u16 >> (i & 7)
+            }
+        } & mask;
+        (level * 255 / mask) as u8
+    }
+
+    /// Returns whether a pixel of the bitmap is set, that is at least half covered.
+    /// Pixels outside the bitmap are not set.
+    pub fn pixel(&self, x: i32, y: i32) -> bool {
+        self.coverage(x, y) >= 0x80
+    }
+
+    /// Returns the coverage of the pixel at a position of the cell from the pen
+    /// position to the next one, as high as the line.
+    pub fn cell_coverage(&self, x: i32, y: i32) -> u8 {
+        self.coverage(x - self.x_offset as i32, y - self.y_offset as i32)
+    }
+
+    /// Returns whether the pixel at a position of the cell from the pen
+    /// position to the next one, as high as the line, is set.
+    pub fn cell_pixel(&self, x: i32, y: i32) -> bool {
+        self.pixel(x - self.x_offset as i32, y - self.y_offset as i32)
     }
 }
 
@@ -46,13 +158,20 @@ pub struct ROMFont {
     width: u8,
     /// the character contains in the font
     range: Range<char>,
+    /// the mapping from characters to glyphs
+    code_page: CodePage,
+    /// the bits from the start of a row, or page, to the next
+    stride: u16,
+    /// the layout of the glyphs
+    packing: Packing,
 }
 
 impl Font for ROMFont {
     fn get_char(&self, c: char) -> Option<(&[u8],u8)> {
+        let c = self.glyph_index(c)?;
         if self.range.contains(&c) {
             let index = c as usize - self.range.start as usize;
-            let size = self.width as usize * (self.height / 8 ) as usize;
+            let size = self.glyph_size();
             let offset = index * size;
             Some((&self.data[offset..offset+size], self.width))
         } else {
@@ -60,7 +179,23 @@ impl Font for ROMFont {
         }
     }
 
+    fn get_glyph(&self, c: char) -> Option<Glyph<'_>> {
+        let (data, width) = self.get_char(c)?;
+        Some(Glyph {
+            data,
+            width,
+            height: self.height,
+            x_offset: 0,
+            y_offset: 0,
+            advance: width,
+            bpp: 1,
+            stride: self.stride,
+            packing: self.packing,
+        })
+    }
+
     fn get_width(&self, c: char) -> Option<u8> {
+        let c = self.glyph_index(c)?;
         if self.range.contains(&c) {
             Some(self.width)
         } else {
@@ -74,14 +209,519 @@ impl Font for ROMFont {
 }
 
 impl ROMFont {
-    /// Creates a new ROMFont from the given data.
+    /// Creates a new ROMFont from the given data, with the glyphs laid out
+    /// row by row, each row taking whole bytes.
+    ///
+    /// Each glyph starts on a byte boundary.
     pub const fn new(data: &'static [u8], height: u8, width: u8, range: Range<char>) -> ROMFont {
         ROMFont {
             data: data,
             height: height,
             width: width,
             range: range,
+            code_page: CodePage::Unicode,
+            stride: width.div_ceil(8) as u16 * 8,
+            packing: Packing::RowMajor,
+        }
+    }
+
+    /// Returns the font with its glyphs laid out in pages of 8 rows, each page
+    /// a byte per column.
+    pub const fn column_major(self) -> ROMFont {
+        ROMFont {
+            stride: self.width as u16 * 8,
+            packing: Packing::ColumnMajor,
+            ..self
         }
     }
+
+    /// Returns the font with its rows one right after another, not padded to
+    /// whole bytes, like 5x7 fonts packed in 35 bits and a byte of padding.
+    pub const fn packed(self) -> ROMFont {
+        ROMFont {
+            stride: self.width as u16,
+            packing: Packing::RowMajor,
+            ..self
+        }
+    }
+
+    /// Returns the font with rows, or pages, starting every `stride` bits,
+    /// for glyphs padded to more than whole bytes.
+    pub const fn with_stride(self, stride: u16) -> ROMFont {
+        ROMFont { stride, ..self }
+    }
+
+    /// Returns the font with its glyphs mapped through a code page.
+    ///
+    /// The `range` of the font is then a range of glyph indices.
+    pub const fn with_code_page(self, code_page: CodePage) -> ROMFont {
+        ROMFont { code_page, ..self }
+    }
+
+    /// Returns the glyph index of a character as a `char`.
+    fn glyph_index(&self, c: char) -> Option<char> {
+        self.code_page.index(c).and_then(char::from_u32)
+    }
+
+    /// Returns the bytes of a glyph.
+    fn glyph_size(&self) -> usize {
+        let lines = match self.packing {
+            Packing::RowMajor => self.height,
+            Packing::ColumnMajor => self.height.div_ceil(8),
+        };
+        (self.stride as usize * lines as usize).div_ceil(8)
+    }
+}
+
+/// The placement of a glyph of a `ProportionalFont`.
+#[derive(Clone, Copy)]
+pub struct GlyphInfo {
+    /// the offset of the bitmap in the font data
+    pub offset: u32,
+    /// the width of the bitmap
+    pub width: u8,
+    /// the height of the bitmap
+    pub height: u8,
+    /// the distance from the pen position to the left of the bitmap
+    pub x_offset: i8,
+    /// the distance from the top of the line to the top of the bitmap
+    pub y_offset: i8,
+    /// the distance from the pen position to the next one
+    pub advance: u8,
 }
 
+/// A kerning adjustment between two characters.
+#[derive(Clone, Copy)]
+pub struct KerningPair {
+    /// the character on the left
+    pub left: char,
+    /// the character on the right
+    pub right: char,
+    /// the horizontal adjustment of the pen before drawing `right`
+    pub adjust: i8,
+}
+
+/// Fonts with a bounding box and advance width per glyph.
+pub struct ProportionalFont {
+    /// the bitmaps of the glyphs, each starting on a byte boundary
+    data: &'static [u8],
+    /// the placement of each glyph in `range`
+    glyphs: &'static [GlyphInfo],
+    /// the height of a line
+    height: u8,
+    /// the character contains in the font
+    range: Range<char>,
+    /// the mapping from characters to glyphs
+    code_page: CodePage,
+    /// the kerning pairs, sorted by `left` then `right`
+    kerning: &'static [KerningPair],
+    /// the bits of coverage per pixel
+    bpp: u8,
+}
+
+impl Font for ProportionalFont {
+    fn get_char(&self, c: char) -> Option<(&[u8], u8)> {
+        self.get_glyph(c).map(|glyph| (glyph.data, glyph.width))
+    }
+
+    fn get_width(&self, c: char) -> Option<u8> {
+        self.glyph_info(c).map(|info| info.advance)
+    }
+
+    fn get_height(&self) -> u8 {
+        self.height
+    }
+
+    fn get_glyph(&self, c: char) -> Option<Glyph<'_>> {
+        let info = self.glyph_info(c)?;
+        let offset = info.offset as usize;
+        let size = (info.width as usize * info.height as usize * self.bpp as usize).div_ceil(8);
+        Some(Glyph {
+            data: &self.data[offset..offset + size],
+            width: info.width,
+            height: info.height,
+            x_offset: info.x_offset,
+            y_offset: info.y_offset,
+            advance: info.advance,
+            bpp: self.bpp,
+            stride: info.width as u16 * self.bpp as u16,
+            packing: Packing::RowMajor,
+        })
+    }
+
+    fn get_kerning(&self, left: char, right: char) -> i8 {
+        self.kerning
+            .binary_search_by(|pair| (pair.left, pair.right).cmp(&(left, right)))
+            .map_or(0, |i| self.kerning[i].adjust)
+    }
+}
+
+impl ProportionalFont {
+    /// Creates a new ProportionalFont from the given data,
+    /// with one `GlyphInfo` per character of `range`.
+    pub const fn new(
+        data: &'static [u8],
+        glyphs: &'static [GlyphInfo],
+        height: u8,
+        range: Range<char>,
+    ) -> ProportionalFont {
+        ProportionalFont {
+            data,
+            glyphs,
+            height,
+            range,
+            code_page: CodePage::Unicode,
+            kerning: &[],
+            bpp: 1,
+        }
+    }
+
+    /// Returns the font with its glyphs mapped through a code page.
+    pub const fn with_code_page(self, code_page: CodePage) -> ProportionalFont {
+        ProportionalFont { code_page, ..self }
+    }
+
+    /// Returns the font with kerning pairs, sorted by `left` then `right`.
+    pub const fn with_kerning(self, kerning: &'static [KerningPair]) -> ProportionalFont {
+        ProportionalFont { kerning, ..self }
+    }
+
+    /// Returns the font with anti-aliased glyphs of 2, 4 or 8 bits of coverage per pixel.
+    pub const fn with_bpp(self, bpp: u8) -> ProportionalFont {
+        ProportionalFont { bpp, ..self }
+    }
+
+    fn glyph_info(&self, c: char) -> Option<&GlyphInfo> {
+        let c = self.code_page.index(c).and_then(char::from_u32)?;
+        if self.range.contains(&c) {
+            self.glyphs.get(c as usize - self.range.start as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// Fonts tried in order for each character, so characters missing from the
+/// first font are drawn with the next one that has them.
+///
+/// ```ignore
+/// const UI: FontStack = FontStack::new(&[
+///     &fonts::VGAPROP_16,
+///     &fonts::VGA2_8X16,
+///     &ReplacementGlyph::new(&fonts::VGA2_8X16, '■'),
+/// ]);
+/// ```
+pub struct FontStack<'a> {
+    /// the fonts, in order of preference
+    fonts: &'a [&'a dyn Font],
+}
+
+impl<'a> FontStack<'a> {
+    pub const fn new(fonts: &'a [&'a dyn Font]) -> FontStack<'a> {
+        FontStack { fonts }
+    }
+
+    /// Returns the position of the first font with a character.
+    fn font_index(&self, c: char) -> Option<usize> {
+        self.fonts.iter().position(|font| font.get_width(c).is_some())
+    }
+}
+
+impl Font for FontStack<'_> {
+    fn get_char(&self, c: char) -> Option<(&[u8], u8)> {
+        self.fonts[self.font_index(c)?].get_char(c)
+    }
+
+    fn get_width(&self, c: char) -> Option<u8> {
+        self.fonts[self.font_index(c)?].get_width(c)
+    }
+
+    /// Returns the height of the tallest font.
+    fn get_height(&self) -> u8 {
+        self.fonts
+            .iter()
+            .map(|font| font.get_height())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn get_glyph(&self, c: char) -> Option<Glyph<'_>> {
+        self.fonts[self.font_index(c)?].get_glyph(c)
+    }
+
+    /// Returns the kerning of two characters drawn with the same font.
+    fn get_kerning(&self, left: char, right: char) -> i8 {
+        match (self.font_index(left), self.font_index(right)) {
+            (Some(l), Some(r)) if l == r => self.fonts[l].get_kerning(left, right),
+            _ => 0,
+        }
+    }
+}
+
+/// A font drawing every character with the same glyph of another font,
+/// the last resort of a `FontStack`.
+pub struct ReplacementGlyph<'a> {
+    /// the font of the glyph
+    font: &'a dyn Font,
+    /// the character of the glyph
+    replacement: char,
+}
+
+impl<'a> ReplacementGlyph<'a> {
+    pub const fn new(font: &'a dyn Font, replacement: char) -> ReplacementGlyph<'a> {
+        ReplacementGlyph { font, replacement }
+    }
+}
+
+impl Font for ReplacementGlyph<'_> {
+    fn get_char(&self, _c: char) -> Option<(&[u8], u8)> {
+        self.font.get_char(self.replacement)
+    }
+
+    fn get_width(&self, _c: char) -> Option<u8> {
+        self.font.get_width(self.replacement)
+    }
+
+    fn get_height(&self) -> u8 {
+        self.font.get_height()
+    }
+
+    fn get_glyph(&self, _c: char) -> Option<Glyph<'_>> {
+        self.font.get_glyph(self.replacement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::string::String;
+    use std::vec::Vec;
+
+    use super::*;
+
+    /// Returns the rows of a glyph as `#` and `.`.
+    fn rows(font: &dyn Font, c: char) -> Vec<String> {
+        let glyph = font.get_glyph(c).unwrap();
+        (0..glyph.height as i32)
+            .map(|y| {
+                (0..glyph.width as i32)
+                    .map(|x| if glyph.pixel(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_packed_5x7_font_takes_35_bits_a_glyph() {
+        static DATA: [u8; 10] = [
+            0x74, 0x63, 0xf8, 0xc6, 0x20, // A
+            0xf4, 0x63, 0xe8, 0xc7, 0xc0, // B
+        ];
+        let font = ROMFont::new(&DATA, 7, 5, 'A'..'C').packed();
+        assert_eq!(
+            rows(&font, 'A'),
+            [".###.", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"]
+        );
+        assert_eq!(
+            rows(&font, 'B'),
+            ["####.", "#...#", "#...#", "####.", "#...#", "#...#", "####."]
+        );
+    }
+
+    #[test]
+    fn a_6x10_font_has_rows_of_whole_bytes() {
+        static DATA: [u8; 20] = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // @
+            0x00, 0x30, 0x48, 0x84, 0x84, 0xfc, 0x84, 0x84, 0x84, 0x00, // A
+        ];
+        let font = ROMFont::new(&DATA, 10, 6, '@'..'B');
+        assert_eq!(
+            rows(&font, 'A'),
+            [
+                "......", "..##..", ".#..#.", "#....#", "#....#", "######", "#....#", "#....#",
+                "#....#", "......",
+            ]
+        );
+        // Fonts that only give `get_char` are read the same way.
+        struct Chars<'a>(&'a ROMFont);
+        impl Font for Chars<'_> {
+            fn get_char(&self, c: char) -> Option<(&[u8], u8)> {
+                self.0.get_char(c)
+            }
+            fn get_width(&self, c: char) -> Option<u8> {
+                self.0.get_width(c)
+            }
+            fn get_height(&self) -> u8 {
+                self.0.get_height()
+            }
+        }
+        assert_eq!(rows(&Chars(&font), 'A'), rows(&font, 'A'));
+    }
+
+    #[test]
+    fn a_12x20_font_has_rows_of_two_bytes() {
+        static DATA: [u8; 40] = [
+            0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x19, 0x80, 0x19, 0x80, 0x30, 0xc0, 0x30, 0xc0,
+            0x30, 0xc0, 0x3f, 0xc0, 0x30, 0xc0, 0x30, 0xc0, 0x30, 0xc0, 0x30, 0xc0, 0x30, 0xc0,
+            0x30, 0xc0, 0x30, 0xc0, 0x30, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let font = ROMFont::new(&DATA, 20, 12, 'A'..'B');
+        let mut expected = std::vec!["............"; 2];
+        expected.push("....####....");
+        expected.extend(["...##..##..."; 2]);
+        expected.extend(["..##....##.."; 3]);
+        expected.push("..########..");
+        expected.extend(["..##....##.."; 8]);
+        expected.extend(["............"; 3]);
+        assert_eq!(rows(&font, 'A'), expected);
+    }
+
+    #[test]
+    fn a_column_major_font_has_a_byte_per_column() {
+        static DATA: [u8; 10] = [
+            0x00, 0x00, 0x00, 0x00, 0x00, // @
+            0x7c, 0x12, 0x11, 0x12, 0x7c, // A
+        ];
+        let font = ROMFont::new(&DATA, 7, 5, '@'..'B').column_major();
+        assert_eq!(
+            rows(&font, 'A'),
+            ["..#..", ".#.#.", "#...#", "#...#", "#####", "#...#", "#...#"]
+        );
+    }
+
+    #[test]
+    fn a_column_major_font_taller_than_8_has_pages_of_8_rows() {
+        static DATA: [u8; 32] = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // K, top page
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // K, bottom page
+            0xff, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // L, top page
+            0xff, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, // L, bottom page
+        ];
+        let font = ROMFont::new(&DATA, 16, 8, 'K'..'M').column_major();
+        let rows = rows(&font, 'L');
+        assert_eq!(rows[0], "##......");
+        for row in &rows[1..15] {
+            assert_eq!(row, "#.......");
+        }
+        assert_eq!(rows[15], "#######.");
+    }
+
+    /// A 3-glyph proportional font: an `A` cross set in from the pen, a `B`
+    /// bar hanging left of it and an empty `C`, with `AB` kerned closer and
+    /// `BA` apart.
+    static PROPORTIONAL_DATA: [u8; 3] = [0xaa, 0x80, 0xc0];
+    static PROPORTIONAL_GLYPHS: [GlyphInfo; 3] = [
+        GlyphInfo {
+            offset: 0,
+            width: 3,
+            height: 3,
+            x_offset: 1,
+            y_offset: 2,
+            advance: 5,
+        },
+        GlyphInfo {
+            offset: 2,
+            width: 2,
+            height: 1,
+            x_offset: -1,
+            y_offset: 0,
+            advance: 2,
+        },
+        GlyphInfo {
+            offset: 3,
+            width: 0,
+            height: 0,
+            x_offset: 0,
+            y_offset: 0,
+            advance: 4,
+        },
+    ];
+    static PROPORTIONAL_KERNING: [KerningPair; 2] = [
+        KerningPair {
+            left: 'A',
+            right: 'B',
+            adjust: -2,
+        },
+        KerningPair {
+            left: 'B',
+            right: 'A',
+            adjust: 1,
+        },
+    ];
+    const PROPORTIONAL: ProportionalFont =
+        ProportionalFont::new(&PROPORTIONAL_DATA, &PROPORTIONAL_GLYPHS, 6, 'A'..'D')
+            .with_kerning(&PROPORTIONAL_KERNING);
+
+    #[test]
+    fn proportional_glyphs_advance_by_their_own_widths() {
+        let widths: Vec<_> = "ABCD".chars().map(|c| PROPORTIONAL.get_width(c)).collect();
+        assert_eq!(widths, [Some(5), Some(2), Some(4), None]);
+        assert_eq!(PROPORTIONAL.measure_text("CC"), (8, 6));
+        assert_eq!(rows(&PROPORTIONAL, 'A'), ["#.#", ".#.", "#.#"]);
+        assert_eq!(rows(&PROPORTIONAL, 'B'), ["##"]);
+        assert!(rows(&PROPORTIONAL, 'C').is_empty());
+    }
+
+    #[test]
+    fn proportional_glyphs_sit_at_their_bearings() {
+        let a = PROPORTIONAL.get_glyph('A').unwrap();
+        let cell: Vec<String> = (0..6)
+            .map(|y| {
+                (0..5)
+                    .map(|x| if a.cell_pixel(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(cell, [".....", ".....", ".#.#.", "..#..", ".#.#.", "....."]);
+        // Left of the pen.
+        let b = PROPORTIONAL.get_glyph('B').unwrap();
+        let row: Vec<bool> = (-2..2).map(|x| b.cell_pixel(x, 0)).collect();
+        assert_eq!(row, [false, true, true, false]);
+    }
+
+    #[test]
+    fn kerning_pairs_move_the_pen() {
+        assert_eq!(PROPORTIONAL.get_kerning('A', 'B'), -2);
+        assert_eq!(PROPORTIONAL.get_kerning('B', 'A'), 1);
+        assert_eq!(PROPORTIONAL.get_kerning('A', 'A'), 0);
+        assert_eq!(PROPORTIONAL.get_kerning('B', 'C'), 0);
+        assert_eq!(PROPORTIONAL.measure_text("AB"), (5, 6));
+        assert_eq!(PROPORTIONAL.measure_text("ABA"), (11, 6));
+        // Not across lines.
+        assert_eq!(PROPORTIONAL.measure_text("A\nB"), (5, 12));
+    }
+
+    /// An 8x2 font with a `C`, a `D` and an `E`, under `PROPORTIONAL`.
+    static FALLBACK_DATA: [u8; 6] = [0xff, 0x00, 0xf0, 0x0f, 0x81, 0x81];
+    const FALLBACK: ROMFont = ROMFont::new(&FALLBACK_DATA, 2, 8, 'C'..'F');
+
+    #[test]
+    fn a_font_stack_draws_missing_characters_with_the_next_font() {
+        let replacement = ReplacementGlyph::new(&FALLBACK, 'E');
+        let fonts: [&dyn Font; 3] = [&PROPORTIONAL, &FALLBACK, &replacement];
+        let stack = FontStack::new(&fonts);
+        assert_eq!(stack.get_height(), 6);
+        assert_eq!(stack.get_width('A'), Some(5));
+        // The first font with a character wins.
+        assert_eq!(stack.get_width('C'), Some(4));
+        assert_eq!(stack.get_width('D'), Some(8));
+        assert_eq!(rows(&stack, 'D'), ["####....", "....####"]);
+        assert_eq!(stack.get_width('Z'), Some(8));
+        assert_eq!(rows(&stack, 'Z'), ["#......#", "#......#"]);
+        assert_eq!(stack.measure_text("ADZ"), (21, 6));
+        let empty = FontStack::new(&[]);
+        assert_eq!((empty.get_width('A'), empty.get_height()), (None, 0));
+    }
+
+    #[test]
+    fn a_font_stack_only_kerns_characters_of_the_same_font() {
+        let fonts: [&dyn Font; 2] = [&PROPORTIONAL, &FALLBACK];
+        let stack = FontStack::new(&fonts);
+        assert_eq!(stack.get_kerning('A', 'B'), -2);
+        assert_eq!(stack.get_kerning('B', 'A'), 1);
+        assert_eq!(stack.get_kerning('A', 'D'), 0);
+        assert_eq!(stack.get_kerning('D', 'A'), 0);
+        assert_eq!(stack.get_kerning('A', 'Z'), 0);
+        assert_eq!(stack.measure_text("ABD"), (13, 6));
+    }
+}
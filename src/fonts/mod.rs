@@ -1,25 +1,63 @@
-mod vga1_8x8;
-mod vga1_8x16;
-mod vga1_16x16;
-mod vga1_16x32;
-mod vga1_bold_16x16;
-mod vga1_bold_16x32;
-mod vga2_8x8;
-mod vga2_8x16;
-mod vga2_16x16;
-mod vga2_16x32;
-mod vga2_bold_16x16;
-mod vga2_bold_16x32;
+/// Fonts generated by `build.rs` from `assets/fonts`.
+mod generated {
+    #[allow(unused_imports)]
+    use crate::codepage::CodePage;
+    #[allow(unused_imports)]
+    use crate::font::{GlyphInfo, ProportionalFont, ROMFont};
 
-pub use vga1_8x8::VGA1_8X8 as VGA1_8x8;
-pub use vga1_8x16::VGA1_8X16 as VGA1_8X16;
-pub use vga1_16x16::VGA1_16X16 as VGA1_16X16;
-pub use vga1_16x32::VGA1_16X32 as VGA1_16X32;
-pub use vga1_bold_16x16::VGA1_BOLD_16X16 as VGA1_BOLD_16X16;
-pub use vga1_bold_16x32::VGA1_BOLD_16X32 as VGA1_BOLD_16X32;
-pub use vga2_8x8::VGA2_8X8 as VGA2_8X8;
-pub use vga2_8x16::VGA2_8X16 as VGA2_8X16;
-pub use vga2_16x16::VGA2_16X16 as VGA2_16X16;
-pub use vga2_16x32::VGA2_16X32 as VGA2_16X32;
-pub use vga2_bold_16x16::VGA2_BOLD_16X16 as VGA2_BOLD_16X16;
-pub use vga2_bold_16x32::VGA2_BOLD_16X32 as VGA2_BOLD_16X32;
+    include!(concat!(env!("OUT_DIR"), "/fonts.rs"));
+}
+
+pub use generated::VGA1_8X8 as VGA1_8x8;
+pub use generated::*;
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::string::String;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::font::Font;
+
+    /// Returns the rows of a glyph as `#` and `.`.
+    fn rows(font: &dyn Font, c: char) -> Vec<String> {
+        let glyph = font.get_glyph(c).unwrap();
+        (0..glyph.height as i32)
+            .map(|y| {
+                (0..glyph.width as i32)
+                    .map(|x| if glyph.pixel(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn the_vga1_fonts_come_from_their_psf_unicode_tables() {
+        assert_eq!(
+            rows(&VGA1_8x8, 'A'),
+            [
+                "..###...", ".##.##..", "##...##.", "#######.", "##...##.", "##...##.", "##...##.",
+                "........",
+            ]
+        );
+        assert_eq!(rows(&VGA1_16X32, '~').len(), 32);
+        assert!(VGA1_8X16.get_glyph(' ').is_some());
+        assert!(VGA1_8X16.get_glyph('\x7f').is_none());
+        assert!(VGA1_8X16.get_glyph('é').is_none());
+    }
+
+    #[test]
+    fn the_vga2_fonts_keep_the_order_of_the_rom_and_map_through_cp437() {
+        // '▒' is 0xb1 in code page 437.
+        assert_eq!(
+            rows(&VGA2_8X8, '▒'),
+            [
+                ".#.#.#.#", "#.#.#.#.", ".#.#.#.#", "#.#.#.#.", ".#.#.#.#", "#.#.#.#.", ".#.#.#.#",
+                "#.#.#.#.",
+            ]
+        );
+        assert_ne!(rows(&VGA2_8X16, 'é'), rows(&VGA2_8X16, 'e'));
+        assert_eq!(rows(&VGA2_BOLD_16X32, '─').len(), 32);
+    }
+}
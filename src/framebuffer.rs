@@ -0,0 +1,520 @@
+use crate::canvas::{Canvas, Rect, Viewport};
+use crate::color::blend;
+use crate::font::{Font, Glyph};
+use crate::text::{self, Span, TextCanvas};
+
+/// A RGB565 framebuffer in RAM, big-endian like the display expects, so it can
+/// be sent as is with `draw_color_buf_raw` or by DMA.
+pub struct Framebuffer<'a> {
+    /// the pixels, two bytes each, row by row
+    buffer: &'a mut [u8],
+    /// the width of the framebuffer
+    width: u16,
+    /// the height of the framebuffer
+    height: u16,
+    /// the origin and clip of drawing
+    viewport: Viewport,
+}
+
+impl<'a> Framebuffer<'a> {
+    /// Creates a framebuffer over a buffer of at least `width * height * 2` bytes.
+    pub fn new(buffer: &'a mut [u8], width: u16, height: u16) -> Self {
+        assert!(buffer.len() >= width as usize * height as usize * 2);
+        Framebuffer {
+            buffer,
+            width,
+            height,
+            viewport: Viewport::new(width, height),
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the origin and clip rectangle drawing goes through.
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// Returns the viewport to push and pop origins and clip rectangles.
+    pub fn viewport_mut(&mut self) -> &mut Viewport {
+        &mut self.viewport
+    }
+
+    /// Returns the pixels, ready to be sent to the display.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.width as usize * self.height as usize * 2]
+    }
+
+    /// Returns the color of a pixel, or None outside the framebuffer.
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<u16> {
+        let (origin_x, origin_y) = self.viewport.origin();
+        let (x, y) = (x + origin_x, y + origin_y);
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 2;
+        Some(u16::from_be_bytes([self.buffer[i], self.buffer[i + 1]]))
+    }
+
+    /// Sets the color of a pixel. Pixels outside the clip are ignored.
+    pub fn pixel(&mut self, x: i32, y: i32, color: u16) {
+        if let Some((x, y)) = self.viewport.clip_point(x, y) {
+            let i = (y as usize * self.width as usize + x as usize) * 2;
+            self.buffer[i..i + 2].copy_from_slice(&color.to_be_bytes());
+        }
+    }
+
+    /// Draws `color` over a pixel with an opacity from 0 to 255.
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: u16, alpha: u8) {
+        if let Some(bg) = self.get_pixel(x, y) {
+            self.pixel(x, y, blend(color, bg, alpha));
+        }
+    }
+
+    /// Draws a rectangle, clipped.
+    pub fn draw_solid_rect(&mut self, x: i32, y: i32, width: u16, height: u16, color: u16) {
+        if let Some(rect) = self.viewport.clip_rect(x, y, width as i32, height as i32) {
+            self.fill_area(rect, color);
+        }
+    }
+
+    /// Fills a rectangle inside the framebuffer.
+    fn fill_area(&mut self, rect: Rect, color: u16) {
+        let bytes = color.to_be_bytes();
+        for row in rect.y..rect.bottom() {
+            let start = (row as usize * self.width as usize + rect.x as usize) * 2;
+            let end = start + rect.width as usize * 2;
+            for pixel in self.buffer[start..end].chunks_exact_mut(2) {
+                pixel.copy_from_slice(&bytes);
+            }
+        }
+    }
+
+    /// Draws an anti-aliased line from (x0, y0) to (x1, y1) by Xiaolin Wu's
+    /// algorithm, blending each pixel by how close it is to the line.
+    pub fn draw_smooth_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u16) {
+        let steep = (y1 as i64 - y0 as i64).abs() > (x1 as i64 - x0 as i64).abs();
+        let (mut x0, mut y0, mut x1, mut y1) = if steep {
+            (y0, x0, y1, x1)
+        } else {
+            (x0, y0, x1, y1)
+        };
+        if x0 > x1 {
+            core::mem::swap(&mut x0, &mut x1);
+            core::mem::swap(&mut y0, &mut y1);
+        }
+        let gradient = if x1 == x0 {
+            0
+        } else {
+            ((y1 as i64 - y0 as i64) << 16) / (x1 as i64 - x0 as i64)
+        };
+        // Only the columns inside the clip, in local coordinates.
+        let clip = self.viewport.clip();
+        let (origin_x, origin_y) = self.viewport.origin();
+        let (low, high) = if steep {
+            (clip.y - origin_y, clip.bottom() - 1 - origin_y)
+        } else {
+            (clip.x - origin_x, clip.right() - 1 - origin_x)
+        };
+        let (first, last) = (x0.max(low), x1.min(high));
+        // Where the line crosses each column, with 16 fractional bits, is
+        // split between the pixels above and below.
+        let mut y = ((y0 as i64) << 16) + gradient * (first as i64 - x0 as i64);
+        for x in first..=last {
+            let row = y >> 16;
+            let fraction = (y >> 8) as u8;
+            for (row, alpha) in [(row, 255 - fraction), (row + 1, fraction)] {
+                let row = row.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+                let (px, py) = if steep { (row, x) } else { (x, row) };
+                if alpha > 0 {
+                    self.blend_pixel(px, py, color, alpha);
+                }
+            }
+            y += gradient;
+        }
+    }
+
+    /// Fills the clip rectangle, the whole framebuffer unless one is pushed.
+    pub fn fill(&mut self, color: u16) {
+        let clip = self.viewport.clip();
+        if !clip.is_empty() {
+            self.fill_area(clip, color);
+        }
+    }
+
+    /// Draws text like `ST7789Display::draw_text`. Without a background color,
+    /// only the glyphs are drawn, anti-aliased glyphs blended over the pixels
+    /// already in the framebuffer.
+    pub fn draw_text(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &dyn Font,
+        font_color: u16,
+        background_color: Option<u16>,
+    ) -> (i32, i32) {
+        self.draw_rich_text(x, y, &[Span::new(text, font, font_color)], background_color)
+    }
+
+    /// Draws spans of text like `ST7789Display::draw_rich_text`, with the
+    /// background of `draw_text`.
+    pub fn draw_rich_text(
+        &mut self,
+        x: i32,
+        y: i32,
+        spans: &[Span],
+        background_color: Option<u16>,
+    ) -> (i32, i32) {
+        text::draw_spans(self, x, y, spans, background_color)
+    }
+
+    /// Draws text with effects, the glyphs blended over the framebuffer.
+    /// Returns the bottom right corner of the text box, as `draw_text` does.
+    ///
+    /// Effects are drawn under the glyphs: the highlight, the cells, the
+    /// shadow and then the outline.
+    pub fn draw_styled_text(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &dyn Font,
+        style: &TextStyle,
+    ) -> (i32, i32) {
+        let spans = [Span::new(text, font, style.color)];
+        if let Some(color) = style.highlight {
+            let (end_x, end_y) =
+                text::draw_spans(&mut Pass::new(self, Effect::Measure), x, y, &spans, None);
+            // One more pixel around for the shadow and outline.
+            let (width, height) = ((end_x + 1 - x + 1) as u16, (end_y + 1 - y + 1) as u16);
+            self.draw_solid_rect(x - 1, y - 1, width, height, color);
+        }
+        let effects = [
+            style.background.map(Effect::Cell),
+            style.shadow.map(Effect::Shadow),
+            style.outline.map(Effect::Outline),
+        ];
+        for effect in effects.into_iter().flatten() {
+            text::draw_spans(&mut Pass::new(self, effect), x, y, &spans, None);
+        }
+        text::draw_spans(self, x, y, &spans, None)
+    }
+}
+
+impl Canvas for Framebuffer<'_> {
+    fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    fn origin(&self) -> (i32, i32) {
+        self.viewport.origin()
+    }
+
+    fn clip(&self) -> Rect {
+        self.viewport.clip()
+    }
+
+    fn fill_span(&mut self, x: u16, y: u16, width: u16, color: u16) {
+        self.fill_rect(x, y, width, 1, color);
+    }
+
+    fn draw_span(&mut self, x: u16, y: u16, colors: &[u16]) {
+        let start = (y as usize * self.width as usize + x as usize) * 2;
+        let end = start + colors.len() * 2;
+        for (bytes, color) in self.buffer[start..end].chunks_exact_mut(2).zip(colors) {
+            bytes.copy_from_slice(&color.to_be_bytes());
+        }
+    }
+
+    fn blend_span(&mut self, x: u16, y: u16, colors: &[u16], alphas: &[u8]) {
+        let start = (y as usize * self.width as usize + x as usize) * 2;
+        let end = start + colors.len() * 2;
+        let pixels = self.buffer[start..end].chunks_exact_mut(2);
+        for ((bytes, &color), &alpha) in pixels.zip(colors).zip(alphas) {
+            let bg = u16::from_be_bytes([bytes[0], bytes[1]]);
+            bytes.copy_from_slice(&blend(color, bg, alpha).to_be_bytes());
+        }
+    }
+
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16) {
+        let rect = Rect::new(x as i32, y as i32, width as i32, height as i32);
+        self.fill_area(rect, color);
+    }
+}
+
+impl TextCanvas for Framebuffer<'_> {
+    fn size(&self) -> (u16, u16) {
+        self.viewport.local_size()
+    }
+
+    /// Without a background color the glyph is blended over the framebuffer.
+    fn draw_glyph(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: &Glyph,
+        height: u16,
+        color: u16,
+        background: Option<u16>,
+    ) {
+        for py in 0..height as i32 {
+            for px in 0..glyph.advance as i32 {
+                let coverage = glyph.cell_coverage(px, py);
+                match background {
+                    Some(bg) => self.pixel(x + px, y + py, blend(color, bg, coverage)),
+                    None => self.blend_pixel(x + px, y + py, color, coverage),
+                }
+            }
+        }
+    }
+}
+
+/// How `Framebuffer::draw_styled_text` draws text.
+///
+/// ```ignore
+/// let style = TextStyle::new(WHITE).with_outline(BLACK).with_shadow(GRAY);
+/// framebuffer.draw_styled_text(10, 10, "Game Over", &fonts::VGA2_BOLD_16X32, &style);
+/// ```
+#[derive(Clone, Copy)]
+pub struct TextStyle {
+    /// the color of the glyphs
+    pub color: u16,
+    /// the color of the character cells, None to leave the framebuffer under them
+    pub background: Option<u16>,
+    /// the color of a shadow one pixel right and down of the glyphs
+    pub shadow: Option<u16>,
+    /// the color of a one pixel line around the glyphs
+    pub outline: Option<u16>,
+    /// the color of the whole text box, like a selection
+    pub highlight: Option<u16>,
+}
+
+impl TextStyle {
+    /// Creates a style drawing the glyphs only.
+    pub const fn new(color: u16) -> TextStyle {
+        TextStyle {
+            color,
+            background: None,
+            shadow: None,
+            outline: None,
+            highlight: None,
+        }
+    }
+
+    pub const fn with_background(self, color: u16) -> TextStyle {
+        TextStyle {
+            background: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn with_shadow(self, color: u16) -> TextStyle {
+        TextStyle {
+            shadow: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn with_outline(self, color: u16) -> TextStyle {
+        TextStyle {
+            outline: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn with_highlight(self, color: u16) -> TextStyle {
+        TextStyle {
+            highlight: Some(color),
+            ..self
+        }
+    }
+}
+
+/// What a pass of `draw_styled_text` draws of each glyph.
+#[derive(Clone, Copy)]
+enum Effect {
+    /// nothing, to find the text box
+    Measure,
+    Cell(u16),
+    Shadow(u16),
+    Outline(u16),
+}
+
+/// A framebuffer drawing an effect instead of the glyphs, so the effects are
+/// laid out like the text.
+struct Pass<'f, 'a> {
+    framebuffer: &'f mut Framebuffer<'a>,
+    effect: Effect,
+}
+
+impl<'f, 'a> Pass<'f, 'a> {
+    fn new(framebuffer: &'f mut Framebuffer<'a>, effect: Effect) -> Self {
+        Pass {
+            framebuffer,
+            effect,
+        }
+    }
+
+    fn blend_pixel(&mut self, x: i32, y: i32, color: u16, alpha: u8) {
+        self.framebuffer.blend_pixel(x, y, color, alpha);
+    }
+}
+
+impl TextCanvas for Pass<'_, '_> {
+    fn size(&self) -> (u16, u16) {
+        TextCanvas::size(self.framebuffer)
+    }
+
+    fn draw_glyph(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: &Glyph,
+        height: u16,
+        _color: u16,
+        _background: Option<u16>,
+    ) {
+        let w = glyph.advance as i32;
+        let h = height as i32;
+        match self.effect {
+            Effect::Measure => {}
+            Effect::Cell(color) => {
+                self.framebuffer
+                    .draw_solid_rect(x, y, glyph.advance as u16, height, color);
+            }
+            Effect::Shadow(color) => {
+                for py in 0..h {
+                    for px in 0..w {
+                        let coverage = glyph.cell_coverage(px, py);
+                        self.blend_pixel(x + px + 1, y + py + 1, color, coverage);
+                    }
+                }
+            }
+            Effect::Outline(color) => {
+                // Each pixel takes the most coverage around it, one pixel out of the cell.
+                for py in -1..=h {
+                    for px in -1..=w {
+                        let mut coverage = 0;
+                        for dy in -1..=1 {
+                            for dx in -1..=1 {
+                                coverage = coverage.max(glyph.cell_coverage(px + dx, py + dy));
+                            }
+                        }
+                        self.blend_pixel(x + px, y + py, color, coverage);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_golden, buffer, CODE_FONT};
+
+    const WHITE: u16 = 0xffff;
+
+    /// Smooth lines between each pair of points, both ways.
+    const LINES: [(i32, i32, i32, i32); 6] = [
+        (1, 1, 14, 1),
+        (1, 3, 14, 8),
+        (14, 14, 1, 10),
+        (17, 1, 20, 14),
+        (29, 14, 23, 2),
+        (23, 1, 29, 7),
+    ];
+
+    #[test]
+    fn smooth_lines_blend_between_two_pixels() {
+        let mut pixels = buffer(32, 16);
+        let mut frame = Framebuffer::new(&mut pixels, 32, 16);
+        for (x0, y0, x1, y1) in LINES {
+            frame.draw_smooth_line(x0, y0, x1, y1, WHITE);
+        }
+        assert_golden("smooth_lines", &frame);
+    }
+
+    #[test]
+    fn smooth_lines_end_on_their_end_points() {
+        for (x0, y0, x1, y1) in LINES {
+            let mut pixels = buffer(32, 16);
+            let mut frame = Framebuffer::new(&mut pixels, 32, 16);
+            frame.draw_smooth_line(x0, y0, x1, y1, WHITE);
+            assert_eq!(frame.get_pixel(x0, y0), Some(WHITE), "{x0},{y0}");
+            assert_eq!(frame.get_pixel(x1, y1), Some(WHITE), "{x1},{y1}");
+            let mut reversed = buffer(32, 16);
+            let mut frame = Framebuffer::new(&mut reversed, 32, 16);
+            frame.draw_smooth_line(x1, y1, x0, y0, WHITE);
+            assert_eq!(pixels, reversed);
+        }
+    }
+
+    #[test]
+    fn smooth_lines_far_outside_are_clipped_without_overflowing() {
+        let mut pixels = buffer(32, 16);
+        let mut frame = Framebuffer::new(&mut pixels, 32, 16);
+        frame.draw_smooth_line(i32::MIN, 5, i32::MAX, 5, WHITE);
+        frame.draw_smooth_line(3, i32::MIN, 3, i32::MAX, WHITE);
+        frame.draw_smooth_line(i32::MIN, i32::MIN, i32::MAX, i32::MAX, WHITE);
+        for x in 0..32 {
+            assert_eq!(frame.get_pixel(x, 5), Some(WHITE), "{x},5");
+        }
+        for y in 0..16 {
+            assert_eq!(frame.get_pixel(3, y), Some(WHITE), "3,{y}");
+        }
+        assert_eq!(frame.get_pixel(10, 10), Some(WHITE));
+    }
+
+    /// Checks "Hi" drawn with a style against the golden image `name`.
+    fn assert_styled_text(name: &str, style: TextStyle) {
+        let mut pixels = buffer(20, 6);
+        let mut frame = Framebuffer::new(&mut pixels, 20, 6);
+        assert_eq!(
+            frame.draw_styled_text(2, 2, "Hi", &CODE_FONT, &style),
+            (18, 4)
+        );
+        assert_golden(name, &frame);
+    }
+
+    #[test]
+    fn styled_text_has_a_shadow_right_and_down_of_the_glyphs() {
+        assert_styled_text(
+            "styled_text_shadow",
+            TextStyle::new(WHITE).with_shadow(0x8410),
+        );
+    }
+
+    #[test]
+    fn styled_text_has_an_outline_around_the_glyphs() {
+        assert_styled_text(
+            "styled_text_outline",
+            TextStyle::new(WHITE).with_outline(0xf800),
+        );
+    }
+
+    #[test]
+    fn styled_text_has_a_highlight_around_the_text_box() {
+        assert_styled_text(
+            "styled_text_highlight",
+            TextStyle::new(WHITE).with_highlight(0x001f),
+        );
+    }
+
+    #[test]
+    fn styled_text_effects_are_drawn_under_one_another() {
+        let style = TextStyle::new(WHITE)
+            .with_background(0x07e0)
+            .with_shadow(0x8410)
+            .with_outline(0xf800)
+            .with_highlight(0x001f);
+        assert_styled_text("styled_text_all", style);
+    }
+}
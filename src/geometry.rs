@@ -0,0 +1,57 @@
+/// The points of a line from one end to the other, by Bresenham's algorithm.
+pub struct LinePoints {
+    x: i32,
+    y: i32,
+    x1: i32,
+    y1: i32,
+    dx: i32,
+    dy: i32,
+    step_x: i32,
+    step_y: i32,
+    error: i32,
+    done: bool,
+}
+
+impl LinePoints {
+    pub fn new(x0: i32, y0: i32, x1: i32, y1: i32) -> LinePoints {
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        LinePoints {
+            x: x0,
+            y: y0,
+            x1,
+            y1,
+            dx,
+            dy,
+            step_x: if x0 < x1 { 1 } else { -1 },
+            step_y: if y0 < y1 { 1 } else { -1 },
+            error: dx + dy,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for LinePoints {
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<(i32, i32)> {
+        if self.done {
+            return None;
+        }
+        let point = (self.x, self.y);
+        if self.x == self.x1 && self.y == self.y1 {
+            self.done = true;
+            return Some(point);
+        }
+        let e2 = 2 * self.error;
+        if e2 >= self.dy {
+            self.error += self.dy;
+            self.x += self.step_x;
+        }
+        if e2 <= self.dx {
+            self.error += self.dx;
+            self.y += self.step_y;
+        }
+        Some(point)
+    }
+}
@@ -0,0 +1,386 @@
+use core::ops::Range;
+
+use crate::trig::{sin_cos, ONE};
+
+/// The vertices of a glyph, drawn as lines from one to the next until a
+/// `PEN_UP` pair.
+///
+/// They are in font units, `x` from the pen position and `y` up from the
+/// baseline.
+pub struct HersheyGlyph {
+    /// the distance from the pen position to the next one
+    width: u8,
+    /// the x and y of each vertex, or -1, -1 to lift the pen
+    vertices: &'static [i8],
+}
+
+impl HersheyGlyph {
+    pub const fn new(width: u8, vertices: &'static [i8]) -> HersheyGlyph {
+        HersheyGlyph { width, vertices }
+    }
+}
+
+/// The vertex lifting the pen between strokes.
+const PEN_UP: (i8, i8) = (-1, -1);
+
+/// A Hershey vector font, strokes that scale and turn to any size and angle.
+pub struct HersheyFont {
+    /// the glyphs of the characters of `range`
+    glyphs: &'static [HersheyGlyph],
+    /// the character contains in the font
+    range: Range<char>,
+    /// the height of capitals in font units
+    cap_height: u8,
+}
+
+impl HersheyFont {
+    pub const fn new(
+        glyphs: &'static [HersheyGlyph],
+        range: Range<char>,
+        cap_height: u8,
+    ) -> HersheyFont {
+        HersheyFont {
+            glyphs,
+            range,
+            cap_height,
+        }
+    }
+
+    fn glyph(&self, c: char) -> Option<&HersheyGlyph> {
+        if self.range.contains(&c) {
+            self.glyphs.get(c as usize - self.range.start as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the width of a text with capitals `size` pixels high.
+    pub fn measure_text(&self, text: &str, size: u16) -> u16 {
+        let units: u64 = text
+            .chars()
+            .filter_map(|c| self.glyph(c))
+            .map(|glyph| glyph.width as u64)
+            .sum();
+        // The same rounding as `strokes`.
+        let scale = size as u64 * 16 / self.cap_height as u64;
+        ((units * scale + 8) / 16).min(u16::MAX as u64) as u16
+    }
+
+    /// Calls `line` with both ends of each stroke of a text, with capitals
+    /// `size` pixels high, turned `angle` degrees counterclockwise around the
+    /// pen position `x` and `y` on the baseline.
+    /// Returns the pen position after the text.
+    pub fn strokes(
+        &self,
+        text: &str,
+        x: i32,
+        y: i32,
+        size: u16,
+        angle: i32,
+        mut line: impl FnMut(i32, i32, i32, i32),
+    ) -> (i32, i32) {
+        let (sin, cos) = sin_cos(angle);
+        let (sin, cos) = (sin as i64, cos as i64);
+        // Font units to 1/16 pixels.
+        let scale = size as i64 * 16 / self.cap_height as i64;
+        // In i64, as 1/16 pixels times sines of 14 bits overflow i32 from
+        // 8192 pixels away from the start of the text.
+        let point = |pen: i32, vx: i8, vy: i8| {
+            let u = (pen as i64 + vx as i64) * scale;
+            let v = -(vy as i64) * scale;
+            let one = ONE as i64 * 16;
+            let offset = |from: i32, d: i64| {
+                (from as i64 + (d + one / 2).div_euclid(one))
+                    .clamp(i32::MIN as i64, i32::MAX as i64) as i32
+            };
+            (offset(x, u * cos + v * sin), offset(y, v * cos - u * sin))
+        };
+        let mut pen: i32 = 0;
+        for c in text.chars() {
+            let Some(glyph) = self.glyph(c) else {
+                continue;
+            };
+            let mut from: Option<(i32, i32)> = None;
+            for vertex in glyph.vertices.chunks_exact(2) {
+                if (vertex[0], vertex[1]) == PEN_UP {
+                    from = None;
+                    continue;
+                }
+                let to = point(pen, vertex[0], vertex[1]);
+                if let Some((x0, y0)) = from {
+                    line(x0, y0, to.0, to.1);
+                }
+                from = Some(to);
+            }
+            pen += glyph.width as i32;
+        }
+        point(pen, 0, 0)
+    }
+}
+
+/// The Hershey simplex Roman font, from ' ' to '~'.
+///
+/// It is the only Hershey font here: the duplex Roman strokes, twice as many
+/// vertices for the same shapes, were left out to keep the flash they would
+/// take, and thick strokes of `ST7789Display::draw_vector_text` make bolder
+/// text from the simplex ones. Other Hershey fonts can be made with
+/// `HersheyFont::new`.
+pub const SIMPLEX: HersheyFont = HersheyFont::new(&SIMPLEX_GLYPHS, ' '..'\u{7f}', 21);
+
+#[rustfmt::skip]
+static SIMPLEX_GLYPHS: [HersheyGlyph; 95] = [
+    // ' '
+    HersheyGlyph::new(16, &[]),
+    // '!'
+    HersheyGlyph::new(10, &[5, 21, 5, 7, -1, -1, 5, 2, 4, 1, 5, 0, 6, 1, 5, 2]),
+    // '"'
+    HersheyGlyph::new(16, &[4, 21, 4, 14, -1, -1, 12, 21, 12, 14]),
+    // '#'
+    HersheyGlyph::new(21, &[11, 25, 4, -7, -1, -1, 17, 25, 10, -7, -1, -1, 4, 12, 18, 12, -1, -1, 3, 6, 17, 6]),
+    // '$'
+    HersheyGlyph::new(20, &[8, 25, 8, -4, -1, -1, 12, 25, 12, -4, -1, -1, 17, 18, 15, 20, 12, 21, 8, 21, 5, 20, 3, 18, 3, 16, 4, 14, 5, 13, 7, 12, 13, 10, 15, 9, 16, 8, 17, 6, 17, 3, 15, 1, 12, 0, 8, 0, 5, 1, 3, 3]),
+    // '%'
+    HersheyGlyph::new(24, &[21, 21, 3, 0, -1, -1, 8, 21, 10, 19, 10, 17, 9, 15, 7, 14, 5, 14, 3, 16, 3, 18, 4, 20, 6, 21, 8, 21, 10, 20, 13, 19, 16, 19, 19, 20, 21, 21, -1, -1, 17, 7, 15, 6, 14, 4, 14, 2, 16, 0, 18, 0, 20, 1, 21, 3, 21, 5, 19, 7, 17, 7]),
+    // '&'
+    HersheyGlyph::new(26, &[23, 12, 23, 13, 22, 14, 21, 14, 20, 13, 19, 11, 17, 6, 15, 3, 13, 1, 11, 0, 7, 0, 5, 1, 4, 2, 3, 4, 3, 6, 4, 8, 5, 9, 12, 13, 13, 14, 14, 16, 14, 18, 13, 20, 11, 21, 9, 20, 8, 18, 8, 16, 9, 13, 11, 10, 16, 3, 18, 1, 20, 0, 22, 0, 23, 1, 23, 2]),
+    // "'"
+    HersheyGlyph::new(10, &[5, 19, 4, 20, 5, 21, 6, 20, 6, 18, 5, 16, 4, 15]),
+    // '('
+    HersheyGlyph::new(14, &[11, 25, 9, 23, 7, 20, 5, 16, 4, 11, 4, 7, 5, 2, 7, -2, 9, -5, 11, -7]),
+    // ')'
+    HersheyGlyph::new(14, &[3, 25, 5, 23, 7, 20, 9, 16, 10, 11, 10, 7, 9, 2, 7, -2, 5, -5, 3, -7]),
+    // '*'
+    HersheyGlyph::new(16, &[8, 21, 8, 9, -1, -1, 3, 18, 13, 12, -1, -1, 13, 18, 3, 12]),
+    // '+'
+    HersheyGlyph::new(26, &[13, 18, 13, 0, -1, -1, 4, 9, 22, 9]),
+    // ','
+    HersheyGlyph::new(10, &[6, 1, 5, 0, 4, 1, 5, 2, 6, 1, 6, -1, 5, -3, 4, -4]),
+    // '-'
+    HersheyGlyph::new(26, &[4, 9, 22, 9]),
+    // '.'
+    HersheyGlyph::new(10, &[5, 2, 4, 1, 5, 0, 6, 1, 5, 2]),
+    // '/'
+    HersheyGlyph::new(22, &[20, 25, 2, -7]),
+    // '0'
+    HersheyGlyph::new(20, &[9, 21, 6, 20, 4, 17, 3, 12, 3, 9, 4, 4, 6, 1, 9, 0, 11, 0, 14, 1, 16, 4, 17, 9, 17, 12, 16, 17, 14, 20, 11, 21, 9, 21]),
+    // '1'
+    HersheyGlyph::new(20, &[6, 17, 8, 18, 11, 21, 11, 0]),
+    // '2'
+    HersheyGlyph::new(20, &[4, 16, 4, 17, 5, 19, 6, 20, 8, 21, 12, 21, 14, 20, 15, 19, 16, 17, 16, 15, 15, 13, 13, 10, 3, 0, 17, 0]),
+    // '3'
+    HersheyGlyph::new(20, &[5, 21, 16, 21, 10, 13, 13, 13, 15, 12, 16, 11, 17, 8, 17, 6, 16, 3, 14, 1, 11, 0, 8, 0, 5, 1, 4, 2, 3, 4]),
+    // '4'
+    HersheyGlyph::new(20, &[13, 21, 3, 7, 18, 7, -1, -1, 13, 21, 13, 0]),
+    // '5'
+    HersheyGlyph::new(20, &[15, 21, 5, 21, 4, 12, 5, 13, 8, 14, 11, 14, 14, 13, 16, 11, 17, 8, 17, 6, 16, 3, 14, 1, 11, 0, 8, 0, 5, 1, 4, 2, 3, 4]),
+    // '6'
+    HersheyGlyph::new(20, &[16, 18, 15, 20, 12, 21, 10, 21, 7, 20, 5, 17, 4, 12, 4, 7, 5, 3, 7, 1, 10, 0, 11, 0, 14, 1, 16, 3, 17, 6, 17, 7, 16, 10, 14, 12, 11, 13, 10, 13, 7, 12, 5, 10, 4, 7]),
+    // '7'
+    HersheyGlyph::new(20, &[17, 21, 7, 0, -1, -1, 3, 21, 17, 21]),
+    // '8'
+    HersheyGlyph::new(20, &[8, 21, 5, 20, 4, 18, 4, 16, 5, 14, 7, 13, 11, 12, 14, 11, 16, 9, 17, 7, 17, 4, 16, 2, 15, 1, 12, 0, 8, 0, 5, 1, 4, 2, 3, 4, 3, 7, 4, 9, 6, 11, 9, 12, 13, 13, 15, 14, 16, 16, 16, 18, 15, 20, 12, 21, 8, 21]),
+    // '9'
+    HersheyGlyph::new(20, &[16, 14, 15, 11, 13, 9, 10, 8, 9, 8, 6, 9, 4, 11, 3, 14, 3, 15, 4, 18, 6, 20, 9, 21, 10, 21, 13, 20, 15, 18, 16, 14, 16, 9, 15, 4, 13, 1, 10, 0, 8, 0, 5, 1, 4, 3]),
+    // ':'
+    HersheyGlyph::new(10, &[5, 14, 4, 13, 5, 12, 6, 13, 5, 14, -1, -1, 5, 2, 4, 1, 5, 0, 6, 1, 5, 2]),
+    // ';'
+    HersheyGlyph::new(10, &[5, 14, 4, 13, 5, 12, 6, 13, 5, 14, -1, -1, 6, 1, 5, 0, 4, 1, 5, 2, 6, 1, 6, -1, 5, -3, 4, -4]),
+    // '<'
+    HersheyGlyph::new(24, &[20, 18, 4, 9, 20, 0]),
+    // '='
+    HersheyGlyph::new(26, &[4, 12, 22, 12, -1, -1, 4, 6, 22, 6]),
+    // '>'
+    HersheyGlyph::new(24, &[4, 18, 20, 9, 4, 0]),
+    // '?'
+    HersheyGlyph::new(18, &[3, 16, 3, 17, 4, 19, 5, 20, 7, 21, 11, 21, 13, 20, 14, 19, 15, 17, 15, 15, 14, 13, 13, 12, 9, 10, 9, 7, -1, -1, 9, 2, 8, 1, 9, 0, 10, 1, 9, 2]),
+    // '@'
+    HersheyGlyph::new(27, &[18, 13, 17, 15, 15, 16, 12, 16, 10, 15, 9, 14, 8, 11, 8, 8, 9, 6, 11, 5, 14, 5, 16, 6, 17, 8, -1, -1, 12, 16, 10, 14, 9, 11, 9, 8, 10, 6, 11, 5, -1, -1, 18, 16, 17, 8, 17, 6, 19, 5, 21, 5, 23, 7, 24, 10, 24, 12, 23, 15, 22, 17, 20, 19, 18, 20, 15, 21, 12, 21, 9, 20, 7, 19, 5, 17, 4, 15, 3, 12, 3, 9, 4, 6, 5, 4, 7, 2, 9, 1, 12, 0, 15, 0, 18, 1, 20, 2, 21, 3, -1, -1, 19, 16, 18, 8, 18, 6, 19, 5]),
+    // 'A'
+    HersheyGlyph::new(18, &[9, 21, 1, 0, -1, -1, 9, 21, 17, 0, -1, -1, 4, 7, 14, 7]),
+    // 'B'
+    HersheyGlyph::new(21, &[4, 21, 4, 0, -1, -1, 4, 21, 13, 21, 16, 20, 17, 19, 18, 17, 18, 15, 17, 13, 16, 12, 13, 11, -1, -1, 4, 11, 13, 11, 16, 10, 17, 9, 18, 7, 18, 4, 17, 2, 16, 1, 13, 0, 4, 0]),
+    // 'C'
+    HersheyGlyph::new(21, &[18, 16, 17, 18, 15, 20, 13, 21, 9, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3, 8, 4, 5, 5, 3, 7, 1, 9, 0, 13, 0, 15, 1, 17, 3, 18, 5]),
+    // 'D'
+    HersheyGlyph::new(21, &[4, 21, 4, 0, -1, -1, 4, 21, 11, 21, 14, 20, 16, 18, 17, 16, 18, 13, 18, 8, 17, 5, 16, 3, 14, 1, 11, 0, 4, 0]),
+    // 'E'
+    HersheyGlyph::new(19, &[4, 21, 4, 0, -1, -1, 4, 21, 17, 21, -1, -1, 4, 11, 12, 11, -1, -1, 4, 0, 17, 0]),
+    // 'F'
+    HersheyGlyph::new(18, &[4, 21, 4, 0, -1, -1, 4, 21, 17, 21, -1, -1, 4, 11, 12, 11]),
+    // 'G'
+    HersheyGlyph::new(21, &[18, 16, 17, 18, 15, 20, 13, 21, 9, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3, 8, 4, 5, 5, 3, 7, 1, 9, 0, 13, 0, 15, 1, 17, 3, 18, 5, 18, 8, -1, -1, 13, 8, 18, 8]),
+    // 'H'
+    HersheyGlyph::new(22, &[4, 21, 4, 0, -1, -1, 18, 21, 18, 0, -1, -1, 4, 11, 18, 11]),
+    // 'I'
+    HersheyGlyph::new(8, &[4, 21, 4, 0]),
+    // 'J'
+    HersheyGlyph::new(16, &[12, 21, 12, 5, 11, 2, 10, 1, 8, 0, 6, 0, 4, 1, 3, 2, 2, 5, 2, 7]),
+    // 'K'
+    HersheyGlyph::new(21, &[4, 21, 4, 0, -1, -1, 18, 21, 4, 7, -1, -1, 9, 12, 18, 0]),
+    // 'L'
+    HersheyGlyph::new(17, &[4, 21, 4, 0, -1, -1, 4, 0, 16, 0]),
+    // 'M'
+    HersheyGlyph::new(24, &[4, 21, 4, 0, -1, -1, 4, 21, 12, 0, -1, -1, 20, 21, 12, 0, -1, -1, 20, 21, 20, 0]),
+    // 'N'
+    HersheyGlyph::new(22, &[4, 21, 4, 0, -1, -1, 4, 21, 18, 0, -1, -1, 18, 21, 18, 0]),
+    // 'O'
+    HersheyGlyph::new(22, &[9, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3, 8, 4, 5, 5, 3, 7, 1, 9, 0, 13, 0, 15, 1, 17, 3, 18, 5, 19, 8, 19, 13, 18, 16, 17, 18, 15, 20, 13, 21, 9, 21]),
+    // 'P'
+    HersheyGlyph::new(21, &[4, 21, 4, 0, -1, -1, 4, 21, 13, 21, 16, 20, 17, 19, 18, 17, 18, 14, 17, 12, 16, 11, 13, 10, 4, 10]),
+    // 'Q'
+    HersheyGlyph::new(22, &[9, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3, 8, 4, 5, 5, 3, 7, 1, 9, 0, 13, 0, 15, 1, 17, 3, 18, 5, 19, 8, 19, 13, 18, 16, 17, 18, 15, 20, 13, 21, 9, 21, -1, -1, 12, 4, 18, -2]),
+    // 'R'
+    HersheyGlyph::new(21, &[4, 21, 4, 0, -1, -1, 4, 21, 13, 21, 16, 20, 17, 19, 18, 17, 18, 15, 17, 13, 16, 12, 13, 11, 4, 11, -1, -1, 11, 11, 18, 0]),
+    // 'S'
+    HersheyGlyph::new(20, &[17, 18, 15, 20, 12, 21, 8, 21, 5, 20, 3, 18, 3, 16, 4, 14, 5, 13, 7, 12, 13, 10, 15, 9, 16, 8, 17, 6, 17, 3, 15, 1, 12, 0, 8, 0, 5, 1, 3, 3]),
+    // 'T'
+    HersheyGlyph::new(16, &[8, 21, 8, 0, -1, -1, 1, 21, 15, 21]),
+    // 'U'
+    HersheyGlyph::new(22, &[4, 21, 4, 6, 5, 3, 7, 1, 10, 0, 12, 0, 15, 1, 17, 3, 18, 6, 18, 21]),
+    // 'V'
+    HersheyGlyph::new(18, &[1, 21, 9, 0, -1, -1, 17, 21, 9, 0]),
+    // 'W'
+    HersheyGlyph::new(24, &[2, 21, 7, 0, -1, -1, 12, 21, 7, 0, -1, -1, 12, 21, 17, 0, -1, -1, 22, 21, 17, 0]),
+    // 'X'
+    HersheyGlyph::new(20, &[3, 21, 17, 0, -1, -1, 17, 21, 3, 0]),
+    // 'Y'
+    HersheyGlyph::new(18, &[1, 21, 9, 11, 9, 0, -1, -1, 17, 21, 9, 11]),
+    // 'Z'
+    HersheyGlyph::new(20, &[17, 21, 3, 0, -1, -1, 3, 21, 17, 21, -1, -1, 3, 0, 17, 0]),
+    // '['
+    HersheyGlyph::new(14, &[4, 25, 4, -7, -1, -1, 5, 25, 5, -7, -1, -1, 4, 25, 11, 25, -1, -1, 4, -7, 11, -7]),
+    // '\\'
+    HersheyGlyph::new(14, &[0, 21, 14, -3]),
+    // ']'
+    HersheyGlyph::new(14, &[9, 25, 9, -7, -1, -1, 10, 25, 10, -7, -1, -1, 3, 25, 10, 25, -1, -1, 3, -7, 10, -7]),
+    // '^'
+    HersheyGlyph::new(16, &[6, 15, 8, 18, 10, 15, -1, -1, 3, 12, 8, 17, 13, 12, -1, -1, 8, 17, 8, 0]),
+    // '_'
+    HersheyGlyph::new(16, &[0, -2, 16, -2]),
+    // '`'
+    HersheyGlyph::new(10, &[6, 21, 5, 20, 4, 18, 4, 16, 5, 15, 6, 16, 5, 17]),
+    // 'a'
+    HersheyGlyph::new(19, &[15, 14, 15, 0, -1, -1, 15, 11, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13, 1, 15, 3]),
+    // 'b'
+    HersheyGlyph::new(19, &[4, 21, 4, 0, -1, -1, 4, 11, 6, 13, 8, 14, 11, 14, 13, 13, 15, 11, 16, 8, 16, 6, 15, 3, 13, 1, 11, 0, 8, 0, 6, 1, 4, 3]),
+    // 'c'
+    HersheyGlyph::new(18, &[15, 11, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13, 1, 15, 3]),
+    // 'd'
+    HersheyGlyph::new(19, &[15, 21, 15, 0, -1, -1, 15, 11, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13, 1, 15, 3]),
+    // 'e'
+    HersheyGlyph::new(18, &[3, 8, 15, 8, 15, 10, 14, 12, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13, 1, 15, 3]),
+    // 'f'
+    HersheyGlyph::new(12, &[10, 21, 8, 21, 6, 20, 5, 17, 5, 0, -1, -1, 2, 14, 9, 14]),
+    // 'g'
+    HersheyGlyph::new(19, &[15, 14, 15, -2, 14, -5, 13, -6, 11, -7, 8, -7, 6, -6, -1, -1, 15, 11, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13, 1, 15, 3]),
+    // 'h'
+    HersheyGlyph::new(19, &[4, 21, 4, 0, -1, -1, 4, 10, 7, 13, 9, 14, 12, 14, 14, 13, 15, 10, 15, 0]),
+    // 'i'
+    HersheyGlyph::new(8, &[3, 21, 4, 20, 5, 21, 4, 22, 3, 21, -1, -1, 4, 14, 4, 0]),
+    // 'j'
+    HersheyGlyph::new(10, &[5, 21, 6, 20, 7, 21, 6, 22, 5, 21, -1, -1, 6, 14, 6, -3, 5, -6, 3, -7, 1, -7]),
+    // 'k'
+    HersheyGlyph::new(17, &[4, 21, 4, 0, -1, -1, 14, 14, 4, 4, -1, -1, 8, 8, 15, 0]),
+    // 'l'
+    HersheyGlyph::new(8, &[4, 21, 4, 0]),
+    // 'm'
+    HersheyGlyph::new(30, &[4, 14, 4, 0, -1, -1, 4, 10, 7, 13, 9, 14, 12, 14, 14, 13, 15, 10, 15, 0, -1, -1, 15, 10, 18, 13, 20, 14, 23, 14, 25, 13, 26, 10, 26, 0]),
+    // 'n'
+    HersheyGlyph::new(19, &[4, 14, 4, 0, -1, -1, 4, 10, 7, 13, 9, 14, 12, 14, 14, 13, 15, 10, 15, 0]),
+    // 'o'
+    HersheyGlyph::new(19, &[8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13, 1, 15, 3, 16, 6, 16, 8, 15, 11, 13, 13, 11, 14, 8, 14]),
+    // 'p'
+    HersheyGlyph::new(19, &[4, 14, 4, -7, -1, -1, 4, 11, 6, 13, 8, 14, 11, 14, 13, 13, 15, 11, 16, 8, 16, 6, 15, 3, 13, 1, 11, 0, 8, 0, 6, 1, 4, 3]),
+    // 'q'
+    HersheyGlyph::new(19, &[15, 14, 15, -7, -1, -1, 15, 11, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13, 1, 15, 3]),
+    // 'r'
+    HersheyGlyph::new(13, &[4, 14, 4, 0, -1, -1, 4, 8, 5, 11, 7, 13, 9, 14, 12, 14]),
+    // 's'
+    HersheyGlyph::new(17, &[14, 11, 13, 13, 10, 14, 7, 14, 4, 13, 3, 11, 4, 9, 6, 8, 11, 7, 13, 6, 14, 4, 14, 3, 13, 1, 10, 0, 7, 0, 4, 1, 3, 3]),
+    // 't'
+    HersheyGlyph::new(12, &[5, 21, 5, 4, 6, 1, 8, 0, 10, 0, -1, -1, 2, 14, 9, 14]),
+    // 'u'
+    HersheyGlyph::new(19, &[4, 14, 4, 4, 5, 1, 7, 0, 10, 0, 13, 1, 15, 4, -1, -1, 15, 14, 15, 0]),
+    // 'v'
+    HersheyGlyph::new(16, &[2, 14, 8, 0, -1, -1, 14, 14, 8, 0]),
+    // 'w'
+    HersheyGlyph::new(22, &[3, 14, 7, 0, -1, -1, 11, 14, 7, 0, -1, -1, 11, 14, 15, 0, -1, -1, 19, 14, 15, 0]),
+    // 'x'
+    HersheyGlyph::new(17, &[3, 14, 14, 0, -1, -1, 14, 14, 3, 0]),
+    // 'y'
+    HersheyGlyph::new(16, &[2, 14, 8, 0, -1, -1, 14, 14, 8, 0, 6, -4, 4, -6, 2, -7, 1, -7]),
+    // 'z'
+    HersheyGlyph::new(17, &[14, 14, 3, 0, -1, -1, 3, 14, 14, 14, -1, -1, 3, 0, 14, 0]),
+    // '{'
+    HersheyGlyph::new(14, &[9, 25, 7, 24, 6, 23, 5, 21, 5, 19, 6, 17, 7, 16, 8, 14, 8, 12, 6, 10, -1, -1, 7, 24, 6, 22, 6, 20, 7, 18, 8, 17, 9, 15, 9, 13, 8, 11, 4, 9, 8, 7, 9, 5, 9, 3, 8, 1, 7, 0, 6, -2, 6, -4, 7, -6, -1, -1, 6, 8, 8, 6, 8, 4, 7, 2, 6, 1, 5, -1, 5, -3, 6, -5, 7, -6, 9, -7]),
+    // '|'
+    HersheyGlyph::new(8, &[4, 25, 4, -7]),
+    // '}'
+    HersheyGlyph::new(14, &[5, 25, 7, 24, 8, 23, 9, 21, 9, 19, 8, 17, 7, 16, 6, 14, 6, 12, 8, 10, -1, -1, 7, 24, 8, 22, 8, 20, 7, 18, 6, 17, 5, 15, 5, 13, 6, 11, 10, 9, 6, 7, 5, 5, 5, 3, 6, 1, 7, 0, 8, -2, 8, -4, 7, -6, -1, -1, 8, 8, 6, 6, 6, 4, 7, 2, 8, 1, 9, -1, 9, -3, 8, -5, 7, -6, 5, -7]),
+    // '~'
+    HersheyGlyph::new(24, &[3, 6, 3, 8, 4, 11, 6, 12, 8, 12, 10, 11, 14, 8, 16, 7, 18, 7, 20, 8, 21, 10, -1, -1, 3, 8, 4, 10, 6, 11, 8, 11, 10, 10, 14, 7, 16, 6, 18, 6, 20, 7, 21, 10, 21, 12]),
+];
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec::Vec;
+
+    use super::*;
+
+    /// The ends of a stroke.
+    type Stroke = (i32, i32, i32, i32);
+
+    fn strokes(text: &str, size: u16, angle: i32) -> (Vec<Stroke>, (i32, i32)) {
+        let mut lines = Vec::new();
+        let pen = SIMPLEX.strokes(text, 100, 100, size, angle, |x0, y0, x1, y1| {
+            lines.push((x0, y0, x1, y1))
+        });
+        (lines, pen)
+    }
+
+    #[test]
+    fn a_stroke_ends_where_its_vertices_are() {
+        // '|' is a stroke from (4, 25) to (4, -7) and 8 units wide, a unit
+        // being a pixel with capitals 21 pixels high.
+        assert_eq!(
+            strokes("|", 21, 0),
+            (std::vec![(104, 75, 104, 107)], (108, 100))
+        );
+        assert_eq!(
+            strokes("|", 42, 0),
+            (std::vec![(108, 50, 108, 114)], (116, 100))
+        );
+    }
+
+    #[test]
+    fn a_stroke_turns_counterclockwise_around_the_pen() {
+        assert_eq!(
+            strokes("|", 21, 90),
+            (std::vec![(75, 96, 107, 96)], (100, 92))
+        );
+        assert_eq!(
+            strokes("|", 21, 180),
+            (std::vec![(96, 125, 96, 93)], (92, 100))
+        );
+    }
+
+    #[test]
+    fn the_width_of_a_text_is_where_its_strokes_leave_the_pen() {
+        for size in [1, 7, 16, 21, 30, 100, 1000] {
+            let (_, (x, y)) = strokes("Hello, world!", size, 0);
+            assert_eq!(
+                (x - 100, y),
+                (SIMPLEX.measure_text("Hello, world!", size) as i32, 100)
+            );
+        }
+    }
+
+    #[test]
+    fn huge_text_does_not_overflow() {
+        let (lines, (x, _)) = strokes("W~W~W~W~", u16::MAX, 45);
+        assert!(!lines.is_empty());
+        assert!(x > 100);
+        assert_eq!(SIMPLEX.measure_text("W~W~W~W~", u16::MAX), u16::MAX);
+    }
+}
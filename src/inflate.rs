@@ -0,0 +1,443 @@
+/// The bases and extra bits of the lengths of matches, symbols 257 to 285.
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// The bases and extra bits of the distances of matches.
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// The order the code lengths of the code length code are stored in.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// The modulus of the sums of an Adler-32 checksum.
+const ADLER_MODULUS: u32 = 65521;
+
+/// Why compressed data can't be inflated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InflateError {
+    /// the data ends before the compressed stream does
+    Truncated,
+    /// the data isn't a valid stream
+    Corrupt,
+    /// the stream was compressed with a larger window than the one given
+    WindowTooSmall,
+}
+
+/// A canonical Huffman code of up to `N` symbols, decoded a bit at a time.
+struct Huffman<const N: usize> {
+    /// the codes of each length, 0 to 15 bits
+    counts: [u16; 16],
+    /// the symbols in order of their codes
+    symbols: [u16; N],
+}
+
+impl<const N: usize> Huffman<N> {
+    const fn new() -> Self {
+        Huffman {
+            counts: [0; 16],
+            symbols: [0; N],
+        }
+    }
+
+    /// Builds the code of symbols with code lengths `lengths`, 0 for symbols
+    /// that aren't used.
+    fn build(&mut self, lengths: &[u8]) -> Result<(), InflateError> {
+        self.counts = [0; 16];
+        for &length in lengths {
+            self.counts[length as usize] += 1;
+        }
+        // Over-subscribed codes can't be decoded; incomplete ones can be.
+        let mut left = 1i32;
+        for &count in &self.counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(InflateError::Corrupt);
+            }
+        }
+        let mut offsets = [0u16; 16];
+        for length in 1..15 {
+            offsets[length + 1] = offsets[length] + self.counts[length];
+        }
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                self.symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The input of an inflater, read a few bits at a time.
+struct Bits<I: Iterator<Item = u8>> {
+    input: I,
+    /// bits read from the input and not used yet, the next in the low bit
+    bits: u32,
+    count: u32,
+}
+
+impl<I: Iterator<Item = u8>> Bits<I> {
+    /// Takes the next `count` bits, up to 16.
+    fn take(&mut self, count: u32) -> Result<u32, InflateError> {
+        while self.count < count {
+            let byte = self.input.next().ok_or(InflateError::Truncated)?;
+            self.bits |= (byte as u32) << self.count;
+            self.count += 8;
+        }
+        let bits = self.bits & ((1 << count) - 1);
+        self.bits >>= count;
+        self.count -= count;
+        Ok(bits)
+    }
+
+    /// Skips to the start of the next byte.
+    fn align(&mut self) {
+        // Fewer than 8 bits are ever left over.
+        (self.bits, self.count) = (0, 0);
+    }
+
+    /// Decodes a symbol of a code, a bit at a time.
+    fn decode<const N: usize>(&mut self, huffman: &Huffman<N>) -> Result<u16, InflateError> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &huffman.counts[1..] {
+            code |= self.take(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(huffman.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(InflateError::Corrupt)
+    }
+}
+
+/// Where an inflater is in the stream.
+#[derive(Clone, Copy, Debug)]
+enum State {
+    /// at the header of a block
+    Header,
+    /// in a stored block with bytes left to copy
+    Stored(u16),
+    /// in a compressed block, at a code
+    Codes,
+    /// in a match with bytes left to copy from a distance back
+    Match { length: u16, distance: u16 },
+    /// past the last block
+    Done,
+}
+
+/// A streaming decompressor of DEFLATE data, as in zlib streams and PNGs,
+/// reading bytes from an iterator and keeping only the window matches copy
+/// from.
+///
+/// ```ignore
+/// let mut window = [0u8; 32 * 1024];
+/// let mut inflate = Inflate::zlib(data.iter().copied(), &mut window)?;
+/// let mut line = [0u8; 64];
+/// while inflate.read(&mut line)? > 0 { ... }
+/// ```
+pub struct Inflate<'w, I: Iterator<Item = u8>> {
+    input: Bits<I>,
+    /// the last bytes inflated, going round
+    window: &'w mut [u8],
+    /// where the next byte goes in the window
+    position: usize,
+    /// the bytes inflated so far, up to the size of the window
+    filled: usize,
+    /// the two sums of the Adler-32 checksum of a zlib stream, None for raw
+    /// DEFLATE data
+    adler: Option<(u32, u32)>,
+    state: State,
+    /// whether the block being inflated is the last one
+    last: bool,
+    lengths: Huffman<288>,
+    distances: Huffman<30>,
+}
+
+impl<'w, I: Iterator<Item = u8>> Inflate<'w, I> {
+    /// Inflates raw DEFLATE data with a window as large as the one it was
+    /// compressed with, 32 KB at most.
+    pub fn new(input: I, window: &'w mut [u8]) -> Self {
+        Inflate {
+            input: Bits {
+                input,
+                bits: 0,
+                count: 0,
+            },
+            window,
+            position: 0,
+            filled: 0,
+            adler: None,
+            state: State::Header,
+            last: false,
+            lengths: Huffman::new(),
+            distances: Huffman::new(),
+        }
+    }
+
+    /// Inflates a zlib stream, checking its window fits in `window` and its
+    /// checksum once the last block ends.
+    pub fn zlib(mut input: I, window: &'w mut [u8]) -> Result<Self, InflateError> {
+        let method = input.next().ok_or(InflateError::Truncated)?;
+        let flags = input.next().ok_or(InflateError::Truncated)?;
+        // DEFLATE, a window of up to 32 KB, the check and no preset dictionary.
+        let check = u16::from_be_bytes([method, flags]) % 31;
+        if method & 15 != 8 || method >> 4 > 7 || check != 0 || flags & 0x20 != 0 {
+            return Err(InflateError::Corrupt);
+        }
+        if window.len() < 1 << (8 + (method >> 4)) {
+            return Err(InflateError::WindowTooSmall);
+        }
+        Ok(Inflate {
+            adler: Some((1, 0)),
+            ..Inflate::new(input, window)
+        })
+    }
+
+    /// Inflates bytes into `out` until it's full or the stream ends, and
+    /// returns how many.
+    pub fn read(&mut self, out: &mut [u8]) -> Result<usize, InflateError> {
+        let mut count = 0;
+        while count < out.len() {
+            let byte = match self.state {
+                State::Header => {
+                    self.block_header()?;
+                    continue;
+                }
+                State::Stored(0) => {
+                    self.state = State::Header;
+                    continue;
+                }
+                State::Stored(left) => {
+                    self.state = State::Stored(left - 1);
+                    self.input.take(8)? as u8
+                }
+                State::Match { length: 0, .. } => {
+                    self.state = State::Codes;
+                    continue;
+                }
+                State::Codes => match self.decode_length()? {
+                    Some(byte) => byte,
+                    None => continue,
+                },
+                State::Match { length, distance } => {
+                    self.state = State::Match {
+                        length: length - 1,
+                        distance,
+                    };
+                    let size = self.window.len();
+                    self.window[(self.position + size - distance as usize) % size]
+                }
+                State::Done => break,
+            };
+            self.window[self.position] = byte;
+            self.position = (self.position + 1) % self.window.len();
+            self.filled = (self.filled + 1).min(self.window.len());
+            if let Some((a, b)) = &mut self.adler {
+                // Kept below the modulus by subtracting, as neither sum more
+                // than doubles.
+                *a += byte as u32;
+                if *a >= ADLER_MODULUS {
+                    *a -= ADLER_MODULUS;
+                }
+                *b += *a;
+                if *b >= ADLER_MODULUS {
+                    *b -= ADLER_MODULUS;
+                }
+            }
+            out[count] = byte;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Reads the header of the next block, or ends the stream after the last.
+    fn block_header(&mut self) -> Result<(), InflateError> {
+        if self.last {
+            if let Some((a, b)) = self.adler {
+                // The checksum follows at the next byte, big-endian.
+                self.input.align();
+                let mut check = 0;
+                for _ in 0..4 {
+                    check = check << 8 | self.input.take(8)?;
+                }
+                if check != b << 16 | a {
+                    return Err(InflateError::Corrupt);
+                }
+            }
+            self.state = State::Done;
+            return Ok(());
+        }
+        self.last = self.input.take(1)? == 1;
+        match self.input.take(2)? {
+            0 => {
+                // Stored blocks start at a byte.
+                self.input.align();
+                let length = self.input.take(16)? as u16;
+                if self.input.take(16)? as u16 != !length {
+                    return Err(InflateError::Corrupt);
+                }
+                self.state = State::Stored(length);
+            }
+            1 => {
+                let mut lengths = [8u8; 288];
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                self.lengths.build(&lengths)?;
+                self.distances.build(&[5; 30])?;
+                self.state = State::Codes;
+            }
+            2 => {
+                self.dynamic_codes()?;
+                self.state = State::Codes;
+            }
+            _ => return Err(InflateError::Corrupt),
+        }
+        Ok(())
+    }
+
+    /// Reads the codes of a dynamic block, themselves coded.
+    fn dynamic_codes(&mut self) -> Result<(), InflateError> {
+        let length_count = self.input.take(5)? as usize + 257;
+        let distance_count = self.input.take(5)? as usize + 1;
+        let code_count = self.input.take(4)? as usize + 4;
+        if length_count > 286 || distance_count > 30 {
+            return Err(InflateError::Corrupt);
+        }
+        let mut lengths = [0u8; 320];
+        for &symbol in &CODE_LENGTH_ORDER[..code_count] {
+            lengths[symbol] = self.input.take(3)? as u8;
+        }
+        let mut code = Huffman::<19>::new();
+        code.build(&lengths[..19])?;
+
+        let total = length_count + distance_count;
+        let mut i = 0;
+        while i < total {
+            let symbol = self.input.decode(&code)?;
+            let (length, repeat) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 if i > 0 => (lengths[i - 1], 3 + self.input.take(2)?),
+                17 => (0, 3 + self.input.take(3)?),
+                18 => (0, 11 + self.input.take(7)?),
+                _ => return Err(InflateError::Corrupt),
+            };
+            if i + repeat as usize > total {
+                return Err(InflateError::Corrupt);
+            }
+            lengths[i..i + repeat as usize].fill(length);
+            i += repeat as usize;
+        }
+        if lengths[256] == 0 {
+            return Err(InflateError::Corrupt);
+        }
+        self.lengths.build(&lengths[..length_count])?;
+        self.distances.build(&lengths[length_count..total])
+    }
+
+    /// Decodes a literal or length code, returning a literal byte, or None
+    /// after starting a match or ending the block.
+    fn decode_length(&mut self) -> Result<Option<u8>, InflateError> {
+        let symbol = self.input.decode(&self.lengths)?;
+        match symbol {
+            0..=255 => return Ok(Some(symbol as u8)),
+            256 => self.state = State::Header,
+            257..=285 => {
+                let i = symbol as usize - 257;
+                let length = LENGTH_BASE[i] + self.input.take(LENGTH_EXTRA[i] as u32)? as u16;
+                let i = self.input.decode(&self.distances)? as usize;
+                if i >= 30 {
+                    return Err(InflateError::Corrupt);
+                }
+                let distance = DISTANCE_BASE[i] + self.input.take(DISTANCE_EXTRA[i] as u32)? as u16;
+                if distance as usize > self.window.len() {
+                    return Err(InflateError::WindowTooSmall);
+                }
+                // Reaching back before the first byte.
+                if distance as usize > self.filled {
+                    return Err(InflateError::Corrupt);
+                }
+                self.state = State::Match { length, distance };
+            }
+            _ => return Err(InflateError::Corrupt),
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::testing::fixture;
+    use std::vec;
+    use std::vec::Vec;
+
+    /// Inflates all of a zlib stream a few bytes at a time.
+    fn inflated(data: &[u8], window: usize) -> Result<Vec<u8>, InflateError> {
+        let mut window = vec![0u8; window];
+        let mut inflate = Inflate::zlib(data.iter().copied(), &mut window)?;
+        let mut out = Vec::new();
+        let mut chunk = [0u8; 100];
+        loop {
+            let count = inflate.read(&mut chunk)?;
+            if count == 0 {
+                return Ok(out);
+            }
+            out.extend_from_slice(&chunk[..count]);
+        }
+    }
+
+    #[test]
+    fn stored_fixed_and_dynamic_blocks_inflate() {
+        let text = fixture("inflate/text.txt");
+        for (name, kind) in [("stored", 0), ("fixed", 1), ("dynamic", 2)] {
+            let data = fixture(&std::format!("inflate/{name}.zlib"));
+            // The kind of the first block, in the bits after its last flag.
+            assert_eq!(data[2] >> 1 & 3, kind, "{name}");
+            assert_eq!(inflated(&data, 32 * 1024), Ok(text.clone()), "{name}");
+        }
+    }
+
+    #[test]
+    fn the_window_must_fit_the_stream() {
+        let data = fixture("inflate/small-window.zlib");
+        assert_eq!(inflated(&data, 256), Err(InflateError::WindowTooSmall));
+        assert_eq!(inflated(&data, 512), Ok(fixture("inflate/text.txt")));
+    }
+
+    #[test]
+    fn a_wrong_checksum_is_corrupt() {
+        let mut data = fixture("inflate/dynamic.zlib");
+        *data.last_mut().unwrap() ^= 1;
+        assert_eq!(inflated(&data, 32 * 1024), Err(InflateError::Corrupt));
+        data.pop();
+        assert_eq!(inflated(&data, 32 * 1024), Err(InflateError::Truncated));
+    }
+
+    #[test]
+    fn a_match_from_before_the_first_byte_is_corrupt() {
+        // A fixed block of the literal 'a' then 3 bytes from 2 back.
+        let data = [0x4b, 0x04, 0x42, 0x00];
+        let mut window = [0u8; 256];
+        let mut inflate = Inflate::new(data.iter().copied(), &mut window);
+        let mut out = [0u8; 4];
+        assert_eq!(inflate.read(&mut out), Err(InflateError::Corrupt));
+    }
+}
@@ -0,0 +1,736 @@
+use crate::canvas::{Canvas, Rect};
+use crate::color::rgb;
+
+/// Where each coefficient of a block is stored, in zigzag order.
+const ZIGZAG: [u8; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20,
+    13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59,
+    52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+/// cos((2x + 1)uπ/16) for each pixel x and frequency u, halved and halved
+/// again over √2 for u = 0, with 12 fractional bits.
+const IDCT: [[i32; 8]; 8] = [
+    [1448, 2009, 1892, 1703, 1448, 1138, 784, 400],
+    [1448, 1703, 784, -400, -1448, -2009, -1892, -1138],
+    [1448, 1138, -784, -2009, -1448, 400, 1892, 1703],
+    [1448, 400, -1892, -1138, 1448, 1703, -784, -2009],
+    [1448, -400, -1892, 1138, 1448, -1703, -784, 2009],
+    [1448, -1138, -784, 2009, -1448, -400, 1892, -1703],
+    [1448, -1703, 784, 400, -1448, 2009, -1892, 1138],
+    [1448, -2009, 1892, -1703, 1448, -1138, 784, -400],
+];
+
+/// Why a JPEG can't be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JpegError {
+    /// the data doesn't start with a JPEG header
+    NotJpeg,
+    /// a kind of JPEG this decoder doesn't decode, e.g. progressive
+    Unsupported,
+    /// the data ends before the image does
+    Truncated,
+    /// the data isn't a valid image
+    Corrupt,
+}
+
+/// How much smaller a JPEG is decoded than its own size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scale {
+    Full,
+    Half,
+    Quarter,
+    /// a pixel for each 8x8 block, without working out the rest of it
+    Eighth,
+}
+
+/// A Huffman table of a scan, decoded a bit at a time.
+#[derive(Clone, Copy, Debug)]
+struct Huffman<'a> {
+    /// the symbols in order of their codes
+    values: &'a [u8],
+    /// the largest code of each length, -1 for none
+    max_code: [i32; 16],
+    /// what to add to a code of each length for the index of its symbol
+    offsets: [i32; 16],
+}
+
+impl<'a> Huffman<'a> {
+    const EMPTY: Huffman<'static> = Huffman {
+        values: &[],
+        max_code: [-1; 16],
+        offsets: [0; 16],
+    };
+
+    /// The table of the codes of each length, 1 to 16 bits, and their symbols.
+    fn new(counts: [u8; 16], values: &'a [u8]) -> Huffman<'a> {
+        let mut huffman = Huffman {
+            values,
+            ..Huffman::EMPTY
+        };
+        let (mut code, mut index) = (0i32, 0i32);
+        for (length, &count) in counts.iter().enumerate() {
+            huffman.offsets[length] = index - code;
+            code += count as i32;
+            index += count as i32;
+            if count > 0 {
+                huffman.max_code[length] = code - 1;
+            }
+            code <<= 1;
+        }
+        huffman
+    }
+}
+
+/// A color component of the image.
+#[derive(Clone, Copy, Debug, Default)]
+struct Component {
+    id: u8,
+    /// the blocks across and down a unit of the scan
+    horizontal: u8,
+    vertical: u8,
+    quantization: u8,
+    dc_table: u8,
+    ac_table: u8,
+}
+
+/// The entropy coded data of a scan, read a bit at a time past the stuffed
+/// zeros after 0xFF bytes, with zeros at markers.
+struct Bits<'a> {
+    data: &'a [u8],
+    at: usize,
+    /// bits read and not used yet, the next in the top bit
+    bits: u32,
+    count: u32,
+    /// whether a marker was reached
+    marker: bool,
+}
+
+impl Bits<'_> {
+    /// Takes the next `count` bits, up to 16.
+    fn take(&mut self, count: u32) -> Result<u32, JpegError> {
+        while self.count < count {
+            let mut byte = 0;
+            if !self.marker {
+                byte = *self.data.get(self.at).ok_or(JpegError::Truncated)?;
+                match (byte, self.data.get(self.at + 1)) {
+                    (0xff, Some(0)) => self.at += 2,
+                    (0xff, _) => (byte, self.marker) = (0, true),
+                    _ => self.at += 1,
+                }
+            }
+            self.bits |= (byte as u32) << (24 - self.count);
+            self.count += 8;
+        }
+        let bits = self.bits.checked_shr(32 - count).unwrap_or(0);
+        self.bits = self.bits.checked_shl(count).unwrap_or(0);
+        self.count -= count;
+        Ok(bits)
+    }
+
+    /// Takes `count` bits of a coefficient, negative values below half the
+    /// range.
+    fn value(&mut self, count: u8) -> Result<i32, JpegError> {
+        if count > 16 {
+            return Err(JpegError::Corrupt);
+        }
+        let bits = self.take(count as u32)? as i32;
+        Ok(match count {
+            0 => 0,
+            _ if bits < 1 << (count - 1) => bits - (1 << count) + 1,
+            _ => bits,
+        })
+    }
+
+    fn decode(&mut self, huffman: &Huffman) -> Result<u8, JpegError> {
+        let mut code = 0;
+        for length in 0..16 {
+            code = code << 1 | self.take(1)? as i32;
+            if code <= huffman.max_code[length] {
+                let index = (code + huffman.offsets[length]) as usize;
+                return huffman.values.get(index).copied().ok_or(JpegError::Corrupt);
+            }
+        }
+        Err(JpegError::Corrupt)
+    }
+
+    /// Skips the padding bits left and the restart marker after them.
+    fn restart(&mut self) -> Result<(), JpegError> {
+        (self.bits, self.count, self.marker) = (0, 0, false);
+        loop {
+            match self.data.get(self.at..self.at + 2) {
+                Some(&[0xff, 0xd0..=0xd7]) => break,
+                Some(_) => self.at += 1,
+                None => return Err(JpegError::Truncated),
+            }
+        }
+        self.at += 2;
+        Ok(())
+    }
+}
+
+/// A baseline JPEG in memory, e.g. in flash, decoded a unit of 8x8 to 16x16
+/// pixels at a time into a fixed few hundred bytes, like TJpgDec, and drawn a
+/// window a unit.
+///
+/// Gray and YCbCr images with the chroma at full, half or quarter resolution
+/// are read; progressive ones aren't. Decoding at a half, a quarter or an
+/// eighth of the size is quicker still.
+///
+/// ```ignore
+/// static PHOTO: &[u8] = include_bytes!("photo.jpg");
+/// let jpeg = Jpeg::new(PHOTO).unwrap().with_scale(Scale::Half);
+/// jpeg.draw(&mut display, 0, 0).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Jpeg<'a> {
+    data: &'a [u8],
+    width: u16,
+    height: u16,
+    components: [Component; 3],
+    component_count: usize,
+    /// the frame component of each component of the scan, in the order the
+    /// scan stores their blocks
+    scan_order: [usize; 3],
+    /// the quantization tables in zigzag order
+    quantization: [[u16; 64]; 4],
+    dc_tables: [Huffman<'a>; 4],
+    ac_tables: [Huffman<'a>; 4],
+    /// the units between restart markers, 0 for none
+    restart_interval: u16,
+    /// where the entropy coded data starts
+    scan: usize,
+    scale: Scale,
+}
+
+impl<'a> Jpeg<'a> {
+    /// Reads the segments of a JPEG up to its scan.
+    pub fn new(data: &'a [u8]) -> Result<Jpeg<'a>, JpegError> {
+        if data.get(..2) != Some(&[0xff, 0xd8]) {
+            return Err(JpegError::NotJpeg);
+        }
+        let mut jpeg = Jpeg {
+            data,
+            width: 0,
+            height: 0,
+            components: [Component::default(); 3],
+            component_count: 0,
+            scan_order: [0, 1, 2],
+            quantization: [[0; 64]; 4],
+            dc_tables: [Huffman::EMPTY; 4],
+            ac_tables: [Huffman::EMPTY; 4],
+            restart_interval: 0,
+            scan: 0,
+            scale: Scale::Full,
+        };
+        let mut at = 2;
+        loop {
+            // Markers may be padded with any number of 0xFF bytes.
+            while data.get(at + 1) == Some(&0xff) {
+                at += 1;
+            }
+            let marker = match data.get(at..at + 2) {
+                Some(&[0xff, marker]) => marker,
+                Some(_) => return Err(JpegError::Corrupt),
+                None => return Err(JpegError::Truncated),
+            };
+            let length = match data.get(at + 2..at + 4) {
+                Some(bytes) => u16::from_be_bytes([bytes[0], bytes[1]]) as usize,
+                None => return Err(JpegError::Truncated),
+            };
+            let segment = data
+                .get(at + 4..at + 2 + length)
+                .ok_or(JpegError::Truncated)?;
+            match marker {
+                0xdb => jpeg.read_quantization(segment)?,
+                0xc4 => jpeg.read_huffman(segment)?,
+                0xc0 | 0xc1 => jpeg.read_frame(segment)?,
+                0xc2 | 0xc3 | 0xc5..=0xc7 | 0xc9..=0xcb | 0xcd..=0xcf => {
+                    return Err(JpegError::Unsupported)
+                }
+                0xdd if segment.len() >= 2 => {
+                    jpeg.restart_interval = u16::from_be_bytes([segment[0], segment[1]]);
+                }
+                0xda => {
+                    jpeg.read_scan(segment)?;
+                    jpeg.scan = at + 2 + length;
+                    return Ok(jpeg);
+                }
+                0xd9 => return Err(JpegError::Truncated),
+                _ => {}
+            }
+            at += 2 + length;
+        }
+    }
+
+    pub const fn with_scale(self, scale: Scale) -> Jpeg<'a> {
+        Jpeg { scale, ..self }
+    }
+
+    /// Returns the width drawn, at the scale.
+    pub fn width(&self) -> u16 {
+        self.width.div_ceil(1 << self.shift())
+    }
+
+    /// Returns the height drawn, at the scale.
+    pub fn height(&self) -> u16 {
+        self.height.div_ceil(1 << self.shift())
+    }
+
+    /// Decodes the image a unit at a time, left to right and top to bottom,
+    /// calling `block` with the position and size of each at the scale, and
+    /// its colors row by row.
+    pub fn decode(
+        &self,
+        mut block: impl FnMut(u16, u16, u16, u16, &[u16]),
+    ) -> Result<(), JpegError> {
+        let shift = self.shift();
+        let size = 8 >> shift;
+        let (horizontal, vertical) = match self.component_count {
+            1 => (1, 1),
+            _ => (
+                self.components[0].horizontal as usize,
+                self.components[0].vertical as usize,
+            ),
+        };
+        let (unit_width, unit_height) = (horizontal * size, vertical * size);
+        let units_across = (self.width as usize).div_ceil(horizontal * 8);
+        let units_down = (self.height as usize).div_ceil(vertical * 8);
+
+        let mut bits = Bits {
+            data: self.data,
+            at: self.scan,
+            bits: 0,
+            count: 0,
+            marker: false,
+        };
+        let mut predictions = [0i32; 3];
+        // The samples of a unit: up to four luma blocks and a block of each
+        // chroma component.
+        let mut luma = [0u8; 256];
+        let mut chroma = [[128u8; 64]; 2];
+        let mut colors = [0u16; 256];
+        for unit in 0..units_across * units_down {
+            if self.restart_interval > 0 && unit > 0 && unit % self.restart_interval as usize == 0 {
+                bits.restart()?;
+                predictions = [0; 3];
+            }
+            for &c in &self.scan_order[..self.component_count] {
+                let component = &self.components[c];
+                let (across, down) = match c {
+                    0 => (horizontal, vertical),
+                    _ => (1, 1),
+                };
+                for i in 0..across * down {
+                    let mut coefficients = [0i32; 64];
+                    self.read_block(&mut bits, component, &mut predictions[c], &mut coefficients)?;
+                    let (x, y) = (i % across * size, i / across * size);
+                    match c {
+                        0 => write_block(&coefficients, shift, &mut luma, unit_width, x, y),
+                        _ => write_block(&coefficients, shift, &mut chroma[c - 1], size, 0, 0),
+                    }
+                }
+            }
+
+            // The unit at the scale, cut at the right and bottom of the image.
+            let (x, y) = (
+                unit % units_across * unit_width,
+                unit / units_across * unit_height,
+            );
+            let width = unit_width.min(self.width() as usize - x);
+            let height = unit_height.min(self.height() as usize - y);
+            for row in 0..height {
+                for column in 0..width {
+                    let luma = luma[row * unit_width + column] as i32;
+                    colors[row * width + column] = match self.component_count {
+                        1 => rgb(luma as u8, luma as u8, luma as u8),
+                        _ => {
+                            let at = row / vertical * size + column / horizontal;
+                            ycbcr(luma, chroma[0][at] as i32, chroma[1][at] as i32)
+                        }
+                    };
+                }
+            }
+            let colors = &colors[..width * height];
+            block(x as u16, y as u16, width as u16, height as u16, colors);
+        }
+        Ok(())
+    }
+
+    /// Draws the image at the scale with its top left at local (x, y),
+    /// clipped to the viewport, a window a unit.
+    pub fn draw<C: Canvas + ?Sized>(
+        &self,
+        canvas: &mut C,
+        x: i32,
+        y: i32,
+    ) -> Result<(), JpegError> {
+        let (origin_x, origin_y) = canvas.origin();
+        let (x, y) = (x + origin_x, y + origin_y);
+        let clip = canvas.clip();
+        let target = Rect::new(x, y, self.width() as i32, self.height() as i32);
+        if target.intersect(&clip).is_empty() {
+            return Ok(());
+        }
+        self.decode(|left, top, width, height, colors| {
+            let unit = Rect::new(x + left as i32, y + top as i32, width as i32, height as i32);
+            let visible = unit.intersect(&clip);
+            if visible == unit {
+                canvas.draw_block(unit.x as u16, unit.y as u16, width, colors);
+            } else if !visible.is_empty() {
+                for row in visible.y..visible.bottom() {
+                    let start = ((row - unit.y) * unit.width + visible.x - unit.x) as usize;
+                    let colors = &colors[start..start + visible.width as usize];
+                    canvas.draw_span(visible.x as u16, row as u16, colors);
+                }
+            }
+        })
+    }
+
+    /// Returns how many times the size is halved.
+    fn shift(&self) -> usize {
+        match self.scale {
+            Scale::Full => 0,
+            Scale::Half => 1,
+            Scale::Quarter => 2,
+            Scale::Eighth => 3,
+        }
+    }
+
+    /// Reads the quantization tables of a DQT segment.
+    fn read_quantization(&mut self, mut segment: &[u8]) -> Result<(), JpegError> {
+        while let Some((&info, rest)) = segment.split_first() {
+            let (wide, id) = (info >> 4 != 0, (info & 15) as usize);
+            let size = if wide { 128 } else { 64 };
+            let (values, rest) = match rest.get(..size) {
+                Some(values) if id < 4 => (values, &rest[size..]),
+                _ => return Err(JpegError::Corrupt),
+            };
+            for (i, quantization) in self.quantization[id].iter_mut().enumerate() {
+                *quantization = match wide {
+                    true => u16::from_be_bytes([values[i * 2], values[i * 2 + 1]]),
+                    false => values[i] as u16,
+                };
+            }
+            segment = rest;
+        }
+        Ok(())
+    }
+
+    /// Reads the Huffman tables of a DHT segment.
+    fn read_huffman(&mut self, mut segment: &'a [u8]) -> Result<(), JpegError> {
+        while segment.len() >= 17 {
+            let (class, id) = (segment[0] >> 4, (segment[0] & 15) as usize);
+            let mut counts = [0u8; 16];
+            counts.copy_from_slice(&segment[1..17]);
+            let total = counts.iter().map(|&count| count as usize).sum::<usize>();
+            let values = segment.get(17..17 + total).ok_or(JpegError::Corrupt)?;
+            match (class, id) {
+                (0, 0..=3) => self.dc_tables[id] = Huffman::new(counts, values),
+                (1, 0..=3) => self.ac_tables[id] = Huffman::new(counts, values),
+                _ => return Err(JpegError::Corrupt),
+            }
+            segment = &segment[17 + total..];
+        }
+        Ok(())
+    }
+
+    /// Reads the size and components of a baseline SOF segment.
+    fn read_frame(&mut self, segment: &[u8]) -> Result<(), JpegError> {
+        let count = *segment.get(5).ok_or(JpegError::Corrupt)? as usize;
+        if segment.len() < 6 + count * 3 {
+            return Err(JpegError::Corrupt);
+        }
+        self.height = u16::from_be_bytes([segment[1], segment[2]]);
+        self.width = u16::from_be_bytes([segment[3], segment[4]]);
+        if segment[0] != 8 || !matches!(count, 1 | 3) || self.width == 0 || self.height == 0 {
+            return Err(JpegError::Unsupported);
+        }
+        self.component_count = count;
+        for (c, bytes) in segment[6..6 + count * 3].chunks_exact(3).enumerate() {
+            let component = &mut self.components[c];
+            component.id = bytes[0];
+            (component.horizontal, component.vertical) = (bytes[1] >> 4, bytes[1] & 15);
+            component.quantization = bytes[2] & 3;
+        }
+        // Luma at up to twice the resolution of the chroma each way.
+        let [luma, blue, red] = self.components;
+        let full = |c: Component| c.horizontal == 1 && c.vertical == 1;
+        let sampled = matches!(luma.horizontal, 1 | 2) && matches!(luma.vertical, 1 | 2);
+        if count == 3 && !(sampled && full(blue) && full(red)) {
+            return Err(JpegError::Unsupported);
+        }
+        Ok(())
+    }
+
+    /// Reads the order of the components of a SOS segment and the tables each
+    /// uses.
+    fn read_scan(&mut self, segment: &[u8]) -> Result<(), JpegError> {
+        let count = *segment.first().ok_or(JpegError::Corrupt)? as usize;
+        if self.component_count == 0 {
+            // No frame before the scan.
+            return Err(JpegError::Corrupt);
+        }
+        // Only scans with every component, in any order but each once.
+        if count != self.component_count {
+            return Err(JpegError::Unsupported);
+        }
+        let tables = segment.get(1..1 + count * 2).ok_or(JpegError::Corrupt)?;
+        for (i, bytes) in tables.chunks_exact(2).enumerate() {
+            let c = self.components[..count]
+                .iter()
+                .position(|component| component.id == bytes[0])
+                .ok_or(JpegError::Corrupt)?;
+            if self.scan_order[..i].contains(&c) {
+                return Err(JpegError::Corrupt);
+            }
+            self.scan_order[i] = c;
+            let component = &mut self.components[c];
+            (component.dc_table, component.ac_table) = (bytes[1] >> 4 & 3, bytes[1] & 3);
+        }
+        Ok(())
+    }
+
+    /// Reads the coefficients of a block in their natural order, dequantized,
+    /// or only the DC one at an eighth of the size.
+    fn read_block(
+        &self,
+        bits: &mut Bits,
+        component: &Component,
+        prediction: &mut i32,
+        coefficients: &mut [i32; 64],
+    ) -> Result<(), JpegError> {
+        let quantization = &self.quantization[component.quantization as usize];
+        let dc = &self.dc_tables[component.dc_table as usize];
+        let ac = &self.ac_tables[component.ac_table as usize];
+        let size = bits.decode(dc)?;
+        // Only corrupt data goes out of range, clamped like the AC
+        // coefficients so the sums of the IDCT fit.
+        *prediction = prediction.saturating_add(bits.value(size)?);
+        let value = prediction.saturating_mul(quantization[0] as i32);
+        coefficients[0] = value.clamp(-32768, 32767);
+
+        let mut k = 1;
+        while k < 64 {
+            let symbol = bits.decode(ac)?;
+            let (zeros, size) = ((symbol >> 4) as usize, symbol & 15);
+            if size == 0 {
+                match zeros {
+                    15 => k += 16,
+                    _ => break,
+                }
+                continue;
+            }
+            k += zeros;
+            if k > 63 {
+                return Err(JpegError::Corrupt);
+            }
+            let value = bits.value(size)?.saturating_mul(quantization[k] as i32);
+            // Only corrupt data goes out of range.
+            coefficients[ZIGZAG[k] as usize] = value.clamp(-32768, 32767);
+            k += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the samples of a block at `shift` halvings of its size at (x, y) in
+/// a plane of samples `stride` wide.
+fn write_block(
+    coefficients: &[i32; 64],
+    shift: usize,
+    plane: &mut [u8],
+    stride: usize,
+    x: usize,
+    y: usize,
+) {
+    if shift == 3 {
+        // The DC coefficient is eight times the mean of the block.
+        plane[y * stride + x] = (((coefficients[0] + 4) >> 3) + 128).clamp(0, 255) as u8;
+        return;
+    }
+    let mut samples = [0u8; 64];
+    idct(coefficients, &mut samples);
+    // Each sample at the scale the mean of a square of them.
+    let size = 8 >> shift;
+    let area = 1 << (shift * 2);
+    for row in 0..size {
+        for column in 0..size {
+            let mut sum = area / 2;
+            for dy in 0..1 << shift {
+                for dx in 0..1 << shift {
+                    sum += samples[((row << shift) + dy) * 8 + (column << shift) + dx] as usize;
+                }
+            }
+            plane[(y + row) * stride + x + column] = (sum >> (shift * 2)) as u8;
+        }
+    }
+}
+
+/// Works out the samples of a block from its coefficients, a column and then
+/// a row at a time.
+fn idct(coefficients: &[i32; 64], samples: &mut [u8; 64]) {
+    // The columns with a bit more than the samples' precision.
+    let mut columns = [0i32; 64];
+    for u in 0..8 {
+        for y in 0..8 {
+            let sum: i32 = (0..8).map(|v| IDCT[y][v] * coefficients[v * 8 + u]).sum();
+            // Only corrupt data goes out of range.
+            columns[y * 8 + u] = (sum >> 11).clamp(-32768, 32767);
+        }
+    }
+    for y in 0..8 {
+        for x in 0..8 {
+            let sum: i32 = (0..8).map(|u| IDCT[x][u] * columns[y * 8 + u]).sum();
+            samples[y * 8 + x] = (((sum + (1 << 12)) >> 13) + 128).clamp(0, 255) as u8;
+        }
+    }
+}
+
+/// Returns the RGB565 color of 8-bit luma and chroma.
+fn ycbcr(luma: i32, blue: i32, red: i32) -> u16 {
+    let (blue, red) = (blue - 128, red - 128);
+    // The factors with 16 fractional bits.
+    let r = luma + ((91881 * red + (1 << 15)) >> 16);
+    let g = luma - ((22554 * blue + 46802 * red - (1 << 15)) >> 16);
+    let b = luma + ((116130 * blue + (1 << 15)) >> 16);
+    let clamp = |c: i32| c.clamp(0, 255) as u8;
+    rgb(clamp(r), clamp(g), clamp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::testing::fixture;
+    use std::vec;
+    use std::vec::Vec;
+
+    /// Returns the colors of a JPEG decoded at a scale, row by row.
+    fn decoded(name: &str, scale: Scale) -> Vec<u16> {
+        let data = fixture(&std::format!("jpeg/{name}.jpg"));
+        let jpeg = Jpeg::new(&data).unwrap().with_scale(scale);
+        let width = jpeg.width() as usize;
+        let mut pixels = vec![0; width * jpeg.height() as usize];
+        jpeg.decode(|x, y, w, h, colors| {
+            for (row, colors) in colors.chunks(w as usize).enumerate().take(h as usize) {
+                let at = (y as usize + row) * width + x as usize;
+                pixels[at..at + w as usize].copy_from_slice(colors);
+            }
+        })
+        .unwrap();
+        pixels
+    }
+
+    /// Checks colors against `tests/fixtures/jpeg/<name>.rgb565`, worked
+    /// out in floating point, allowing each component to be a step off for
+    /// the rounding of the fixed point.
+    fn assert_close(colors: &[u16], name: &str) {
+        let data = fixture(&std::format!("jpeg/{name}.rgb565"));
+        let expected: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .collect();
+        assert_eq!(colors.len(), expected.len(), "{name}");
+        for (i, (&color, &expected)) in colors.iter().zip(&expected).enumerate() {
+            let components = |c: u16| [c >> 11, (c >> 5) & 0x3f, c & 0x1f];
+            let off = components(color)
+                .into_iter()
+                .zip(components(expected))
+                .any(|(a, b)| a.abs_diff(b) > 1);
+            assert!(!off, "{name} pixel {i} is {color:04x}, not {expected:04x}");
+        }
+    }
+
+    #[test]
+    fn gray_and_color_images_decode() {
+        for name in ["gray", "444", "420"] {
+            assert_close(&decoded(name, Scale::Full), name);
+        }
+    }
+
+    #[test]
+    fn restart_intervals_reset_the_predictions() {
+        let data = fixture("jpeg/restart.jpg");
+        assert_eq!(Jpeg::new(&data).unwrap().restart_interval, 2);
+        assert_close(&decoded("restart", Scale::Full), "restart");
+    }
+
+    #[test]
+    fn each_scale_decodes_smaller() {
+        // 21x19 pixels, cut at the right and bottom of each scale.
+        for (scale, suffix, size) in [
+            (Scale::Full, "", (21, 19)),
+            (Scale::Half, "-half", (11, 10)),
+            (Scale::Quarter, "-quarter", (6, 5)),
+            (Scale::Eighth, "-eighth", (3, 3)),
+        ] {
+            let data = fixture("jpeg/420.jpg");
+            let jpeg = Jpeg::new(&data).unwrap().with_scale(scale);
+            assert_eq!((jpeg.width(), jpeg.height()), size);
+            assert_close(&decoded("420", scale), &std::format!("420{suffix}"));
+        }
+    }
+
+    #[test]
+    fn scans_in_another_order_than_the_frame_decode() {
+        // The blocks of Cr, then Y, then Cb.
+        assert_close(&decoded("scan-order", Scale::Full), "scan-order");
+        assert_eq!(
+            decoded("scan-order", Scale::Full),
+            decoded("444", Scale::Full)
+        );
+    }
+
+    #[test]
+    fn scans_naming_a_component_twice_are_corrupt() {
+        let mut data = fixture("jpeg/444.jpg");
+        let sos = data
+            .windows(2)
+            .position(|bytes| bytes == [0xff, 0xda])
+            .unwrap();
+        // The first component again in place of the third.
+        data[sos + 9] = data[sos + 5];
+        assert_eq!(Jpeg::new(&data).err(), Some(JpegError::Corrupt));
+    }
+
+    #[test]
+    fn a_scan_without_a_frame_is_corrupt() {
+        let data = [
+            0xff, 0xd8, 0xff, 0xda, 0x00, 0x06, 0x00, 0x00, 0x3f, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(Jpeg::new(&data).err(), Some(JpegError::Corrupt));
+    }
+
+    #[test]
+    fn corrupt_images_are_errors_not_panics() {
+        for name in ["gray", "444", "420", "restart", "scan-order"] {
+            let data = fixture(&std::format!("jpeg/{name}.jpg"));
+            let decode = |data: &[u8]| {
+                for scale in [Scale::Full, Scale::Eighth] {
+                    let jpeg = Jpeg::new(data)?.with_scale(scale);
+                    jpeg.decode(|_, _, _, _, _| {})?;
+                }
+                Ok::<(), JpegError>(())
+            };
+            let scan = Jpeg::new(&data).unwrap().scan;
+            for length in 0..data.len() {
+                let result = decode(&data[..length]);
+                assert!(
+                    length >= scan || result.is_err(),
+                    "{name} cut to {length} bytes"
+                );
+            }
+            // Every byte set to values that break lengths, counts and codes.
+            let mut broken = data.clone();
+            for i in 0..data.len() {
+                for value in [0x00, 0x01, 0x7f, 0xff, data[i] ^ 0x80] {
+                    broken[i] = value;
+                    let _ = decode(&broken);
+                }
+                broken[i] = data[i];
+            }
+        }
+    }
+}
@@ -1,5 +1,33 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
+pub mod bitmap;
+pub mod bmp;
+// The font generator of `build.rs`, tested on the host.
+#[cfg(test)]
+#[path = "../build/fonts.rs"]
+mod build_fonts;
+pub mod canvas;
+pub mod codepage;
+pub mod color;
 pub mod font;
 pub mod fonts;
+pub mod framebuffer;
+pub mod geometry;
+pub mod hershey;
+pub mod inflate;
+pub mod jpeg;
+pub mod paint;
+pub mod path;
+pub mod png;
+pub mod qoi;
+pub mod segment_display;
+pub mod shapes;
 pub mod st7789;
+pub mod terminal;
+#[cfg(test)]
+mod testing;
+pub mod text;
+pub mod text_buffer;
+pub mod text_cursor;
+pub mod trig;
+pub mod widgets;
 //
@@ -96,6 +96,11 @@ fn main() -> ! {
     );
 
     let bmp_framebuffer = singleton!(: [u8; FRAMEBUFFER_SIZE] = [0xff; FRAMEBUFFER_SIZE]).unwrap();
+    #[cfg(feature = "bench")]
+    {
+        let timer = hal::Timer::new(peripherals.TIMER, &mut peripherals.RESETS, &clocks);
+        time_rotozoom(bmp_framebuffer, &timer);
+    }
     let dma = peripherals.DMA.split(&mut peripherals.RESETS);
     let mut transfer = single_buffer::Config::new(dma.ch0, bmp_framebuffer, spi);
 
@@ -137,6 +142,46 @@ fn main() -> ! {
     }
 }
 
+/// Times `bitmap::rotozoom` turning a 64x64 sprite at twice its size over a
+/// framebuffer a full circle, and reports the pixels drawn a second with each
+/// filter. Built with the `bench` feature.
+#[cfg(feature = "bench")]
+fn time_rotozoom(framebuffer: &mut [u8], timer: &hal::Timer) {
+    use rp2040_project_template::bitmap::{self, Bitmap, Blit, Filter};
+    use rp2040_project_template::framebuffer::Framebuffer;
+
+    const SIZE: u16 = 64;
+    const SCALE: i32 = 512;
+    let mut sprite = [0u8; SIZE as usize * SIZE as usize * 2];
+    for (i, pixel) in sprite.chunks_exact_mut(2).enumerate() {
+        let (x, y) = ((i % SIZE as usize) as u8, (i / SIZE as usize) as u8);
+        pixel.copy_from_slice(&rgb(x * 4, y * 4, (x ^ y) * 4).to_be_bytes());
+    }
+    let sprite = Bitmap::new(&sprite, SIZE, SIZE);
+    let mut frame = Framebuffer::new(framebuffer, SCREEN_SIZE as u16, SCREEN_SIZE as u16);
+    let center = SCREEN_SIZE as i32 / 2;
+
+    for filter in [Filter::Nearest, Filter::Bilinear] {
+        let blit = Blit::new().with_filter(filter);
+        let start = timer.get_counter();
+        let mut frames: u64 = 0;
+        for angle in (0..360).step_by(10) {
+            bitmap::rotozoom(&mut frame, center, center, &sprite, angle, SCALE, &blit);
+            frames += 1;
+        }
+        let micros = (timer.get_counter() - start).to_micros().max(1);
+        // The sprite covers its area times the scale squared at any angle.
+        let pixels = frames * (SIZE as u64 * SCALE as u64 / 256).pow(2);
+        info!(
+            "rotozoom {}: {} pixels in {} us, {} pixels/s",
+            Debug2Format(&filter),
+            pixels,
+            micros,
+            pixels * 1_000_000 / micros
+        );
+    }
+}
+
 fn split_into_2(i: u16) -> (u8, u8) {
     let msb = (i >> 8) as u8;
     let lsb = (i & 0xff) as u8;
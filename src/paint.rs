@@ -0,0 +1,385 @@
+use crate::canvas::{Canvas, Rect};
+use crate::color::{components, dithered_rgb, rgb};
+
+/// The pixels `Painted` draws at once.
+const CHUNK: usize = 64;
+
+/// The colors of a fill, pixel by pixel, for shapes drawn through `Painted`.
+pub trait Paint {
+    /// Returns the color of the pixel at local (x, y), or None to leave it as
+    /// it is.
+    fn color(&self, x: i32, y: i32) -> Option<u16>;
+}
+
+/// A canvas that fills shapes drawn on it with a paint instead of their
+/// color, the display or a framebuffer underneath.
+///
+/// ```ignore
+/// let gradient = LinearGradient::vertical(0, 240, color::rgb(0, 0, 64), color::rgb(0, 128, 255));
+/// let mut painted = Painted::new(&mut display, &gradient);
+/// shapes::fill_rounded_rect(&mut painted, 10, 10, 220, 220, 16, 0);
+/// ```
+pub struct Painted<'c, 'p, C: Canvas + ?Sized, P: Paint + ?Sized> {
+    canvas: &'c mut C,
+    paint: &'p P,
+}
+
+impl<'c, 'p, C: Canvas + ?Sized, P: Paint + ?Sized> Painted<'c, 'p, C, P> {
+    pub fn new(canvas: &'c mut C, paint: &'p P) -> Self {
+        Painted { canvas, paint }
+    }
+}
+
+impl<C: Canvas + ?Sized, P: Paint + ?Sized> Canvas for Painted<'_, '_, C, P> {
+    fn size(&self) -> (u16, u16) {
+        self.canvas.size()
+    }
+
+    fn origin(&self) -> (i32, i32) {
+        self.canvas.origin()
+    }
+
+    fn clip(&self) -> Rect {
+        self.canvas.clip()
+    }
+
+    /// Draws the painted pixels of the span, in runs between the pixels the
+    /// paint leaves.
+    fn fill_span(&mut self, x: u16, y: u16, width: u16, _color: u16) {
+        let (origin_x, origin_y) = self.canvas.origin();
+        let mut colors = [0u16; CHUNK];
+        let mut start = x;
+        let mut count = 0;
+        for px in x..x + width {
+            let color = self.paint.color(px as i32 - origin_x, y as i32 - origin_y);
+            if let Some(color) = color {
+                if count == 0 {
+                    start = px;
+                }
+                colors[count] = color;
+                count += 1;
+            }
+            if count > 0 && (color.is_none() || count == CHUNK || px == x + width - 1) {
+                self.canvas.draw_span(start, y, &colors[..count]);
+                count = 0;
+            }
+        }
+    }
+}
+
+/// Returns the 8-bit components of a color a fraction `t` with 16 fractional
+/// bits from `from` to `to`.
+fn mix(from: u16, to: u16, t: i64) -> (u8, u8, u8) {
+    let (from, to) = (components(from), components(to));
+    let at = |a: u8, b: u8| (a as i64 + (((b as i64 - a as i64) * t) >> 16)) as u8;
+    (at(from.0, to.0), at(from.1, to.1), at(from.2, to.2))
+}
+
+/// Returns the color at a fraction with 16 fractional bits along a gradient,
+/// dithered at (x, y) or not.
+fn gradient_color(from: u16, to: u16, t: i64, dither: bool, x: i32, y: i32) -> u16 {
+    let (r, g, b) = mix(from, to, t.clamp(0, 1 << 16));
+    if dither {
+        dithered_rgb(r, g, b, x, y)
+    } else {
+        rgb(r, g, b)
+    }
+}
+
+/// Colors blending from one to another along a line, and the same across it.
+#[derive(Clone, Copy, Debug)]
+pub struct LinearGradient {
+    /// where the gradient starts
+    pub start: (i32, i32),
+    /// where the gradient ends
+    pub end: (i32, i32),
+    /// the color at the start and before it
+    pub from: u16,
+    /// the color at the end and after it
+    pub to: u16,
+    /// whether the colors are ordered dithered to hide the steps of RGB565
+    pub dither: bool,
+}
+
+impl LinearGradient {
+    /// A gradient from `from` at `start` to `to` at `end`, at any angle.
+    pub const fn new(start: (i32, i32), end: (i32, i32), from: u16, to: u16) -> LinearGradient {
+        LinearGradient {
+            start,
+            end,
+            from,
+            to,
+            dither: false,
+        }
+    }
+
+    /// A gradient from `from` at column `x` to `to` `width` pixels to the right.
+    pub const fn horizontal(x: i32, width: i32, from: u16, to: u16) -> LinearGradient {
+        LinearGradient::new((x, 0), (x + width - 1, 0), from, to)
+    }
+
+    /// A gradient from `from` at row `y` to `to` `height` pixels down.
+    pub const fn vertical(y: i32, height: i32, from: u16, to: u16) -> LinearGradient {
+        LinearGradient::new((0, y), (0, y + height - 1), from, to)
+    }
+
+    pub const fn with_dither(self) -> LinearGradient {
+        LinearGradient {
+            dither: true,
+            ..self
+        }
+    }
+}
+
+impl Paint for LinearGradient {
+    fn color(&self, x: i32, y: i32) -> Option<u16> {
+        // How far the pixel is along the line, projected onto it. Lines too
+        // long to square are scaled down, offsets and all.
+        let dx = self.end.0 as i64 - self.start.0 as i64;
+        let dy = self.end.1 as i64 - self.start.1 as i64;
+        let shift = (64 - dx.abs().max(dy.abs()).leading_zeros()).saturating_sub(22);
+        let (dx, dy) = (dx >> shift, dy >> shift);
+        let length = dx * dx + dy * dy;
+        let along = ((x as i64 - self.start.0 as i64) >> shift) * dx
+            + ((y as i64 - self.start.1 as i64) >> shift) * dy;
+        let t = if length == 0 {
+            0
+        } else {
+            (along.clamp(0, length) << 16) / length
+        };
+        Some(gradient_color(self.from, self.to, t, self.dither, x, y))
+    }
+}
+
+/// Colors blending from one at a center to another at a radius around it.
+#[derive(Clone, Copy, Debug)]
+pub struct RadialGradient {
+    /// the center
+    pub center: (i32, i32),
+    /// the radius the gradient ends at
+    pub radius: i32,
+    /// the color at the center
+    pub inner: u16,
+    /// the color at the radius and past it
+    pub outer: u16,
+    /// whether the colors are ordered dithered to hide the steps of RGB565
+    pub dither: bool,
+}
+
+impl RadialGradient {
+    pub const fn new(center: (i32, i32), radius: i32, inner: u16, outer: u16) -> RadialGradient {
+        RadialGradient {
+            center,
+            radius,
+            inner,
+            outer,
+            dither: false,
+        }
+    }
+
+    pub const fn with_dither(self) -> RadialGradient {
+        RadialGradient {
+            dither: true,
+            ..self
+        }
+    }
+}
+
+impl Paint for RadialGradient {
+    fn color(&self, x: i32, y: i32) -> Option<u16> {
+        // Pixels further than the radius across or down are past it, so the
+        // offsets are clamped to it to keep their squares in range.
+        let r = (self.radius as i64).abs().max(1);
+        let dx = (x as i64 - self.center.0 as i64).clamp(-r, r);
+        let dy = (y as i64 - self.center.1 as i64).clamp(-r, r);
+        let squared = (dx * dx + dy * dy) as u64;
+        // The distance with 16 fractional bits, fewer when the square is too
+        // big to shift by 32.
+        let bits = (squared.leading_zeros() / 2).min(16);
+        let distance = ((squared << (2 * bits)).isqrt() << (16 - bits)) as i64;
+        let t = match self.radius {
+            0 => 1 << 16,
+            radius => distance / radius as i64,
+        };
+        Some(gradient_color(self.inner, self.outer, t, self.dither, x, y))
+    }
+}
+
+/// The 8x8 patterns of common hatches, a row a byte from the top, the left
+/// pixel in the high bit.
+pub mod hatch {
+    pub const HORIZONTAL: [u8; 8] = [0xff, 0, 0, 0, 0xff, 0, 0, 0];
+    pub const VERTICAL: [u8; 8] = [0x88; 8];
+    pub const CROSS: [u8; 8] = [0xff, 0x88, 0x88, 0x88, 0xff, 0x88, 0x88, 0x88];
+    /// lines rising to the right
+    pub const DIAGONAL: [u8; 8] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80];
+    /// lines falling to the right
+    pub const BACK_DIAGONAL: [u8; 8] = [0x80, 0x40, 0x20, 0x10, 0x08, 0x04, 0x02, 0x01];
+    pub const DIAGONAL_CROSS: [u8; 8] = [0x81, 0x42, 0x24, 0x18, 0x18, 0x24, 0x42, 0x81];
+    pub const CHECKER: [u8; 8] = [0xaa, 0x55, 0xaa, 0x55, 0xaa, 0x55, 0xaa, 0x55];
+    pub const DOTS: [u8; 8] = [0x88, 0, 0x22, 0, 0x88, 0, 0x22, 0];
+}
+
+/// An 8x8 pattern repeated over the canvas, so patterns of shapes side by
+/// side line up.
+#[derive(Clone, Copy, Debug)]
+pub struct Pattern {
+    /// the rows from the top, the left pixel in the high bit
+    pub bits: [u8; 8],
+    /// the color of the set bits
+    pub color: u16,
+    /// the color of the clear bits, None to leave those pixels
+    pub background: Option<u16>,
+}
+
+impl Pattern {
+    /// A pattern of `bits`, e.g. one of `hatch`, with the clear bits left.
+    pub const fn new(bits: [u8; 8], color: u16) -> Pattern {
+        Pattern {
+            bits,
+            color,
+            background: None,
+        }
+    }
+
+    pub const fn with_background(self, color: u16) -> Pattern {
+        Pattern {
+            background: Some(color),
+            ..self
+        }
+    }
+}
+
+impl Paint for Pattern {
+    fn color(&self, x: i32, y: i32) -> Option<u16> {
+        if self.bits[(y & 7) as usize] & (0x80 >> (x & 7)) != 0 {
+            Some(self.color)
+        } else {
+            self.background
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::Framebuffer;
+    use crate::shapes;
+    use crate::testing::{assert_golden, buffer};
+
+    const BLACK: u16 = 0x0000;
+    const WHITE: u16 = 0xffff;
+
+    #[test]
+    fn a_radial_gradient_goes_from_its_center_to_its_radius() {
+        let gradient = RadialGradient::new((10, 10), 8, BLACK, WHITE);
+        assert_eq!(gradient.color(10, 10), Some(BLACK));
+        assert_eq!(gradient.color(18, 10), Some(WHITE));
+        assert_eq!(gradient.color(10, 0), Some(WHITE));
+        let half = gradient.color(14, 10).unwrap();
+        assert!(half != BLACK && half != WHITE);
+        assert_eq!(gradient.color(6, 10), Some(half));
+    }
+
+    #[test]
+    fn a_radial_gradient_takes_any_distance() {
+        let gradient = RadialGradient::new((0, 0), 8, BLACK, WHITE);
+        assert_eq!(gradient.color(i32::MAX, i32::MIN), Some(WHITE));
+        let gradient = RadialGradient::new((i32::MIN, 0), 8, BLACK, WHITE);
+        assert_eq!(gradient.color(i32::MAX, 0), Some(WHITE));
+
+        // Radii too big for 16 fractional bits still blend evenly.
+        let half = RadialGradient::new((0, 0), 8, BLACK, WHITE).color(4, 0);
+        let gradient = RadialGradient::new((0, 0), 200_000, BLACK, WHITE);
+        assert_eq!(gradient.color(100_000, 0), half);
+        let gradient = RadialGradient::new((0, 0), i32::MAX, BLACK, WHITE);
+        assert_eq!(gradient.color(i32::MIN, i32::MIN), Some(WHITE));
+        assert_eq!(gradient.color(0, 0), Some(BLACK));
+    }
+
+    #[test]
+    fn a_linear_gradient_goes_from_its_start_to_its_end() {
+        let gradient = LinearGradient::horizontal(10, 9, BLACK, WHITE);
+        assert_eq!(gradient.color(10, 0), Some(BLACK));
+        assert_eq!(gradient.color(18, 0), Some(WHITE));
+        assert_eq!(gradient.color(0, 5), Some(BLACK));
+        assert_eq!(gradient.color(100, 5), Some(WHITE));
+        let half = gradient.color(14, 0).unwrap();
+        assert!(half != BLACK && half != WHITE);
+        // The same across the line.
+        assert_eq!(gradient.color(14, -1000), Some(half));
+        let gradient = LinearGradient::vertical(10, 9, BLACK, WHITE);
+        assert_eq!(gradient.color(-1000, 14), Some(half));
+        let gradient = LinearGradient::new((0, 0), (8, 8), BLACK, WHITE);
+        assert_eq!(gradient.color(8, 0), Some(half));
+        // A gradient without a length is all its first color.
+        let gradient = LinearGradient::new((5, 5), (5, 5), BLACK, WHITE);
+        assert_eq!(gradient.color(9, 9), Some(BLACK));
+    }
+
+    #[test]
+    fn a_linear_gradient_takes_any_points() {
+        let gradient =
+            LinearGradient::new((i32::MIN, i32::MIN), (i32::MAX, i32::MAX), BLACK, WHITE);
+        assert_eq!(gradient.color(i32::MIN, i32::MIN), Some(BLACK));
+        assert_eq!(gradient.color(i32::MAX, i32::MAX), Some(WHITE));
+        assert_eq!(gradient.color(i32::MAX, i32::MIN), gradient.color(0, 0));
+        let (r, _, _) = components(gradient.color(0, 0).unwrap());
+        assert!((120..=135).contains(&r), "{r}");
+        let gradient = LinearGradient::horizontal(i32::MAX, i32::MIN, BLACK, WHITE);
+        assert_eq!(gradient.color(i32::MAX, 0), Some(BLACK));
+        assert_eq!(gradient.color(-1, 0), Some(WHITE));
+    }
+
+    #[test]
+    fn a_dithered_gradient_mixes_the_colors_around_it() {
+        let gradient = LinearGradient::horizontal(0, 256, BLACK, 0x0800).with_dither();
+        // Half a step of red, rounded up at half the pixels of a block.
+        let reds: u16 = (0..16)
+            .map(|i| gradient.color(128 + i % 4, i / 4).unwrap() >> 11)
+            .sum();
+        assert_eq!(reds, 8);
+        assert_eq!(gradient.color(0, 3), Some(BLACK));
+        assert_eq!(gradient.color(255, 3), Some(0x0800));
+    }
+
+    #[test]
+    fn a_pattern_repeats_every_8_pixels_from_the_origin() {
+        let pattern = Pattern::new(hatch::DIAGONAL, WHITE);
+        assert_eq!(pattern.color(7, 0), Some(WHITE));
+        assert_eq!(pattern.color(0, 7), Some(WHITE));
+        assert_eq!(pattern.color(0, 0), None);
+        for (x, y) in [(3, 4), (5, 5), (0, 7)] {
+            for (dx, dy) in [(8, 0), (0, 8), (-16, -24)] {
+                assert_eq!(pattern.color(x + dx, y + dy), pattern.color(x, y));
+            }
+        }
+        let pattern = pattern.with_background(BLACK);
+        assert_eq!(pattern.color(0, 0), Some(BLACK));
+        let set = |bits: [u8; 8]| bits.iter().map(|row| row.count_ones()).sum::<u32>();
+        assert_eq!(set(hatch::CHECKER), 32);
+        assert_eq!(
+            set(hatch::CROSS),
+            set(hatch::HORIZONTAL) + set(hatch::VERTICAL) - 4
+        );
+    }
+
+    #[test]
+    fn painted_shapes_take_the_colors_of_the_paint() {
+        let mut pixels = buffer(40, 20);
+        let mut frame = Framebuffer::new(&mut pixels, 40, 20);
+        shapes::fill_rect(&mut frame, 0, 0, 40, 20, 0x001f);
+        let hatch = Pattern::new(hatch::DIAGONAL_CROSS, WHITE);
+        shapes::fill_circle(&mut Painted::new(&mut frame, &hatch), 9, 9, 8, BLACK);
+        let gradient = LinearGradient::vertical(2, 16, 0xf800, 0x07e0);
+        shapes::fill_rect(
+            &mut Painted::new(&mut frame, &gradient),
+            22,
+            2,
+            16,
+            16,
+            BLACK,
+        );
+        assert_golden("painted_shapes", &frame);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::canvas::Canvas;
+use crate::shapes::{self, FillRule, LineStyle, Stroker};
+
+/// The most lines a curve is flattened into.
+const MAX_CURVE_LINES: i64 = 32;
+
+/// An outline of lines and Bezier curves, in one or more parts, to stroke or
+/// fill. The curves are flattened into lines as they are added, and the
+/// points are kept with 8 fractional bits.
+///
+/// A path holds up to `N` points. A curve takes up to 32; points past the
+/// last are dropped.
+///
+/// ```ignore
+/// let mut path = Path::<64>::new();
+/// path.move_to(20, 100).quad_to(120, 0, 220, 100).line_to(120, 200).close();
+/// path.fill(&mut display, FillRule::NonZero, color::rgb(0, 128, 255));
+/// path.stroke(&mut display, &LineStyle::new(0xffff).with_thickness(3));
+/// ```
+pub struct Path<const N: usize> {
+    points: [(i32, i32); N],
+    /// whether each point starts a new part
+    starts: [bool; N],
+    len: usize,
+    /// the first point of the current part
+    start: (i32, i32),
+}
+
+impl<const N: usize> Path<N> {
+    pub const fn new() -> Self {
+        Path {
+            points: [(0, 0); N],
+            starts: [false; N],
+            len: 0,
+            start: (0, 0),
+        }
+    }
+
+    /// Removes all the points.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Returns the number of points, lines and curves flattened.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Starts a new part at (x, y).
+    pub fn move_to(&mut self, x: i32, y: i32) -> &mut Self {
+        self.start = (x << 8, y << 8);
+        self.push(self.start, true);
+        self
+    }
+
+    /// Adds a line to (x, y).
+    pub fn line_to(&mut self, x: i32, y: i32) -> &mut Self {
+        if self.is_empty() {
+            return self.move_to(x, y);
+        }
+        self.push((x << 8, y << 8), false);
+        self
+    }
+
+    /// Adds a quadratic Bezier curve to (x, y), pulled toward the control
+    /// point (cx, cy).
+    pub fn quad_to(&mut self, cx: i32, cy: i32, x: i32, y: i32) -> &mut Self {
+        let p0 = self.last();
+        let (p1, p2) = ((cx << 8, cy << 8), (x << 8, y << 8));
+        // Flattened to within a quarter of a pixel.
+        let lines = ((deviation(p0, p1, p2) + 255) >> 8).isqrt() + 1;
+        for i in 1..=lines.min(MAX_CURVE_LINES) {
+            let t = (i << 16) / lines.min(MAX_CURVE_LINES);
+            let (a, b) = (lerp(p0, p1, t), lerp(p1, p2, t));
+            self.push(lerp(a, b, t), false);
+        }
+        self
+    }
+
+    /// Adds a cubic Bezier curve to (x, y), leaving toward the control point
+    /// (c1x, c1y) and arriving from (c2x, c2y).
+    pub fn cubic_to(
+        &mut self,
+        c1x: i32,
+        c1y: i32,
+        c2x: i32,
+        c2y: i32,
+        x: i32,
+        y: i32,
+    ) -> &mut Self {
+        let p0 = self.last();
+        let (p1, p2, p3) = ((c1x << 8, c1y << 8), (c2x << 8, c2y << 8), (x << 8, y << 8));
+        let worst = deviation(p0, p1, p2).max(deviation(p1, p2, p3));
+        let lines = ((3 * worst + 255) >> 8).isqrt() + 1;
+        for i in 1..=lines.min(MAX_CURVE_LINES) {
+            let t = (i << 16) / lines.min(MAX_CURVE_LINES);
+            let (a, b, c) = (lerp(p0, p1, t), lerp(p1, p2, t), lerp(p2, p3, t));
+            let (d, e) = (lerp(a, b, t), lerp(b, c, t));
+            self.push(lerp(d, e, t), false);
+        }
+        self
+    }
+
+    /// Closes the current part with a line back to its start.
+    pub fn close(&mut self) -> &mut Self {
+        if self.len > 0 && self.points[self.len - 1] != self.start {
+            self.push(self.start, false);
+        }
+        self
+    }
+
+    /// Draws the lines of the path in a style, through the centers of the
+    /// pixels at its points like `shapes::draw_polyline`.
+    pub fn stroke<C: Canvas + ?Sized>(&self, canvas: &mut C, style: &LineStyle) {
+        let mut stroker = Stroker::new(style);
+        for (&point, &start) in self.points[..self.len].iter().zip(&self.starts) {
+            if start {
+                stroker.move_to(point);
+            } else {
+                stroker.line_to(canvas, point);
+            }
+        }
+    }
+
+    /// Fills the path by a fill rule, each part closed, with the points at the
+    /// corners of pixels like `shapes::fill_polygon`.
+    pub fn fill<C: Canvas + ?Sized>(&self, canvas: &mut C, rule: FillRule, color: u16) {
+        let points = &self.points[..self.len];
+        let ys = points.iter().map(|point| point.1);
+        let (top, bottom) = (ys.clone().min().unwrap_or(0), ys.max().unwrap_or(0));
+        let edges = |edge: &mut dyn FnMut((i32, i32), (i32, i32))| {
+            let mut start = 0;
+            for i in 0..points.len() {
+                if self.starts[i] {
+                    start = i;
+                }
+                let end = i + 1 == points.len() || self.starts[i + 1];
+                edge(points[i], points[if end { start } else { i + 1 }]);
+            }
+        };
+        shapes::fill_edges(canvas, top, bottom, edges, rule, color);
+    }
+
+    /// Returns the last point, the start of a path without any.
+    fn last(&self) -> (i32, i32) {
+        match self.len {
+            0 => self.start,
+            len => self.points[len - 1],
+        }
+    }
+
+    fn push(&mut self, point: (i32, i32), start: bool) {
+        if self.len < N {
+            self.points[self.len] = point;
+            self.starts[self.len] = start;
+            self.len += 1;
+        }
+    }
+}
+
+impl<const N: usize> Default for Path<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the point a fraction `t` with 16 fractional bits from `a` to `b`.
+fn lerp(a: (i32, i32), b: (i32, i32), t: i64) -> (i32, i32) {
+    let at = |a: i32, b: i32| a + (((b - a) as i64 * t) >> 16) as i32;
+    (at(a.0, b.0), at(a.1, b.1))
+}
+
+/// Returns how far the middle of three points bends away from the others.
+fn deviation(p0: (i32, i32), p1: (i32, i32), p2: (i32, i32)) -> i64 {
+    let dx = (p0.0 - 2 * p1.0 + p2.0) as i64;
+    let dy = (p0.1 - 2 * p1.1 + p2.1) as i64;
+    dx.abs().max(dy.abs())
+}
+
+/// Draws a quadratic Bezier curve from `p0` to `p2`, pulled toward `p1`.
+pub fn draw_quad_bezier<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    p0: (i32, i32),
+    p1: (i32, i32),
+    p2: (i32, i32),
+    style: &LineStyle,
+) {
+    let mut path = Path::<33>::new();
+    path.move_to(p0.0, p0.1).quad_to(p1.0, p1.1, p2.0, p2.1);
+    path.stroke(canvas, style);
+}
+
+/// Draws a cubic Bezier curve from `p0` to `p3`, leaving toward `p1` and
+/// arriving from `p2`.
+pub fn draw_cubic_bezier<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    p0: (i32, i32),
+    p1: (i32, i32),
+    p2: (i32, i32),
+    p3: (i32, i32),
+    style: &LineStyle,
+) {
+    let mut path = Path::<33>::new();
+    path.move_to(p0.0, p0.1)
+        .cubic_to(p1.0, p1.1, p2.0, p2.1, p3.0, p3.1);
+    path.stroke(canvas, style);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::Framebuffer;
+    use crate::testing::{assert_golden, buffer};
+
+    const WHITE: u16 = 0xffff;
+
+    /// A five-pointed star drawn in one stroke, its edges crossing around a
+    /// pentagon in the middle.
+    fn star(dx: i32) -> Path<6> {
+        let mut path = Path::new();
+        path.move_to(dx + 10, 1)
+            .line_to(dx + 16, 19)
+            .line_to(dx + 1, 8)
+            .line_to(dx + 19, 8)
+            .line_to(dx + 4, 19)
+            .close();
+        path
+    }
+
+    #[test]
+    fn the_fill_rules_differ_inside_a_crossed_outline() {
+        let mut pixels = buffer(44, 20);
+        let mut frame = Framebuffer::new(&mut pixels, 44, 20);
+        star(0).fill(&mut frame, FillRule::EvenOdd, WHITE);
+        star(22).fill(&mut frame, FillRule::NonZero, WHITE);
+        assert_eq!(frame.get_pixel(10, 11), Some(0));
+        assert_eq!(frame.get_pixel(32, 11), Some(WHITE));
+        assert_golden("path_fill_rules", &frame);
+    }
+
+    #[test]
+    fn curves_end_on_their_end_points() {
+        let mut pixels = buffer(40, 20);
+        let mut frame = Framebuffer::new(&mut pixels, 40, 20);
+        let mut path = Path::<64>::new();
+        path.move_to(1, 18).quad_to(10, -10, 18, 17);
+        assert_eq!(path.last(), (18 << 8, 17 << 8));
+        path.move_to(21, 18).cubic_to(21, 1, 38, 30, 38, 2);
+        assert_eq!(path.last(), (38 << 8, 2 << 8));
+        path.stroke(&mut frame, &LineStyle::new(WHITE));
+        assert_eq!(frame.get_pixel(18, 17), Some(WHITE));
+        assert_eq!(frame.get_pixel(38, 2), Some(WHITE));
+        assert_golden("path_curves", &frame);
+    }
+
+    #[test]
+    fn points_past_the_capacity_are_dropped() {
+        let mut pixels = buffer(20, 20);
+        let mut frame = Framebuffer::new(&mut pixels, 20, 20);
+        let mut path = Path::<4>::new();
+        path.move_to(1, 1)
+            .line_to(18, 1)
+            .line_to(18, 18)
+            .line_to(1, 18);
+        path.line_to(10, 10)
+            .quad_to(0, 0, 5, 5)
+            .cubic_to(1, 2, 3, 4, 5, 6);
+        path.move_to(3, 3).close();
+        assert_eq!(path.len(), 4);
+        path.fill(&mut frame, FillRule::NonZero, 0x8410);
+        path.stroke(&mut frame, &LineStyle::new(WHITE));
+        assert_golden("path_capacity", &frame);
+    }
+}
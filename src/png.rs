@@ -0,0 +1,456 @@
+use crate::bitmap::draw_pixels;
+use crate::canvas::{Canvas, Rect};
+use crate::color::{dithered_rgb, rgb};
+use crate::inflate::{Inflate, InflateError};
+
+/// The widest image decoded.
+pub const MAX_WIDTH: usize = 320;
+
+/// The most bytes a row takes, 16-bit RGBA, and its filter byte.
+const MAX_ROW: usize = MAX_WIDTH * 8 + 1;
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+const GRAY: u8 = 0;
+const RGB: u8 = 2;
+const PALETTE: u8 = 3;
+const GRAY_ALPHA: u8 = 4;
+const RGBA: u8 = 6;
+
+/// Why a PNG can't be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PngError {
+    /// the data doesn't start with a PNG header
+    NotPng,
+    /// a kind of PNG this reader doesn't decode, e.g. interlaced
+    Unsupported,
+    /// wider than `MAX_WIDTH`
+    TooWide,
+    /// the data ends before the image does
+    Truncated,
+    /// the compressed pixels aren't valid
+    Corrupt,
+    /// the pixels were compressed with a larger window than the one given
+    WindowTooSmall,
+}
+
+impl From<InflateError> for PngError {
+    fn from(error: InflateError) -> Self {
+        match error {
+            InflateError::Truncated => PngError::Truncated,
+            InflateError::Corrupt => PngError::Corrupt,
+            InflateError::WindowTooSmall => PngError::WindowTooSmall,
+        }
+    }
+}
+
+/// A PNG image in memory, e.g. in flash, inflated and decoded a row at a time
+/// as it's drawn.
+///
+/// Gray, RGB and palette images of any bit depth, with or without alpha or
+/// a transparent color, are read; interlaced ones aren't. Inflating needs a
+/// window as large as the one the image was compressed with, 32 KB for most
+/// encoders and less for images compressed with a smaller one.
+///
+/// ```ignore
+/// static LOGO: &[u8] = include_bytes!("logo.png");
+/// let mut window = [0u8; 32 * 1024];
+/// Png::new(LOGO).unwrap().with_dither().draw(&mut display, 0, 0, &mut window).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Png<'a> {
+    data: &'a [u8],
+    width: u16,
+    height: u16,
+    bit_depth: u8,
+    color_type: u8,
+    /// the RGB colors of a palette image
+    palette: &'a [u8],
+    /// the alphas of palette colors, or the transparent gray or RGB color
+    transparency: &'a [u8],
+    /// where the first IDAT chunk starts
+    pixels: usize,
+    /// whether the colors are ordered dithered down to RGB565
+    dither: bool,
+}
+
+impl<'a> Png<'a> {
+    /// Reads the chunks of a PNG before its pixels.
+    pub fn new(data: &'a [u8]) -> Result<Png<'a>, PngError> {
+        if data.len() < 33 || &data[..8] != SIGNATURE || &data[12..16] != b"IHDR" {
+            return Err(PngError::NotPng);
+        }
+        let width = u32::from_be_bytes([data[16], data[17], data[18], data[19]]);
+        let height = u32::from_be_bytes([data[20], data[21], data[22], data[23]]);
+        let (bit_depth, color_type, interlace) = (data[24], data[25], data[28]);
+        match (color_type, bit_depth) {
+            (GRAY, 1 | 2 | 4 | 8 | 16) | (PALETTE, 1 | 2 | 4 | 8) => {}
+            (RGB | GRAY_ALPHA | RGBA, 8 | 16) => {}
+            _ => return Err(PngError::Unsupported),
+        }
+        if interlace != 0 || height > u16::MAX as u32 {
+            return Err(PngError::Unsupported);
+        }
+        if width as usize > MAX_WIDTH {
+            return Err(PngError::TooWide);
+        }
+
+        let mut png = Png {
+            data,
+            width: width as u16,
+            height: height as u16,
+            bit_depth,
+            color_type,
+            palette: &[],
+            transparency: &[],
+            pixels: 0,
+            dither: false,
+        };
+        let mut at = 8;
+        loop {
+            let (kind, body) = chunk(data, at)?;
+            match kind {
+                b"PLTE" => png.palette = body,
+                b"tRNS" => png.transparency = body,
+                b"IDAT" => break,
+                b"IEND" => return Err(PngError::Truncated),
+                _ => {}
+            }
+            at += 12 + body.len();
+        }
+        png.pixels = at;
+        Ok(png)
+    }
+
+    pub const fn with_dither(self) -> Png<'a> {
+        Png {
+            dither: true,
+            ..self
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Decodes the image a row at a time from the top, calling `line` with
+    /// the row and its colors and opacities. `window` is the inflate window.
+    pub fn decode(
+        &self,
+        window: &mut [u8],
+        mut line: impl FnMut(u16, &[u16], &[u8]),
+    ) -> Result<(), PngError> {
+        let mut inflate = Inflate::zlib(Idat::new(self.data, self.pixels), window)?;
+        let channels = match self.color_type {
+            GRAY | PALETTE => 1,
+            GRAY_ALPHA => 2,
+            RGB => 3,
+            _ => 4,
+        };
+        let bits = channels * self.bit_depth as usize;
+        // The bytes of a row after the filter byte, and a pixel, at least one.
+        let stride = (self.width as usize * bits).div_ceil(8);
+        let step = bits.div_ceil(8);
+
+        let mut previous = [0u8; MAX_ROW];
+        let mut current = [0u8; MAX_ROW];
+        let mut colors = [0u16; MAX_WIDTH];
+        let mut alphas = [0u8; MAX_WIDTH];
+        for y in 0..self.height {
+            let row = &mut current[..stride + 1];
+            if inflate.read(row)? < row.len() {
+                return Err(PngError::Truncated);
+            }
+            unfilter(row, &previous[1..stride + 1], step)?;
+            let row = &row[1..];
+            for x in 0..self.width as usize {
+                let samples = |channel: usize| sample(row, x * channels + channel, self.bit_depth);
+                let (color, alpha) = self.pixel(samples, x as i32, y as i32);
+                colors[x] = color;
+                alphas[x] = alpha;
+            }
+            line(
+                y,
+                &colors[..self.width as usize],
+                &alphas[..self.width as usize],
+            );
+            core::mem::swap(&mut previous, &mut current);
+        }
+        // On to the end of the stream, which checks its checksum.
+        inflate.read(&mut [0])?;
+        Ok(())
+    }
+
+    /// Draws the image with its top left at local (x, y), clipped to the
+    /// viewport, blending its transparent pixels like `bitmap::blit`.
+    pub fn draw<C: Canvas + ?Sized>(
+        &self,
+        canvas: &mut C,
+        x: i32,
+        y: i32,
+        window: &mut [u8],
+    ) -> Result<(), PngError> {
+        let (origin_x, origin_y) = canvas.origin();
+        let target = Rect::new(
+            x + origin_x,
+            y + origin_y,
+            self.width as i32,
+            self.height as i32,
+        );
+        let visible = target.intersect(&canvas.clip());
+        if visible.is_empty() {
+            return Ok(());
+        }
+        let (left, right) = (
+            (visible.x - target.x) as usize,
+            (visible.right() - target.x) as usize,
+        );
+        self.decode(window, |y, colors, alphas| {
+            let row = target.y + y as i32;
+            if row >= visible.y && row < visible.bottom() {
+                let (colors, alphas) = (&colors[left..right], &alphas[left..right]);
+                draw_pixels(canvas, visible.x, row, colors, alphas);
+            }
+        })
+    }
+
+    /// Returns the color and opacity of the pixel at (x, y) from its samples.
+    fn pixel(&self, sample: impl Fn(usize) -> u16, x: i32, y: i32) -> (u16, u8) {
+        let eight = |value: u16| match self.bit_depth {
+            16 => (value >> 8) as u8,
+            8 => value as u8,
+            bits => (value * 255 / ((1 << bits) - 1)) as u8,
+        };
+        // A transparent gray or RGB color is stored as 16-bit samples.
+        let key = |channel: usize| {
+            let bytes = self.transparency.get(channel * 2..channel * 2 + 2)?;
+            Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+        };
+        let ((r, g, b), alpha) = match self.color_type {
+            GRAY => {
+                let gray = sample(0);
+                let alpha = if key(0) == Some(gray) { 0 } else { 255 };
+                ((eight(gray), eight(gray), eight(gray)), alpha)
+            }
+            RGB => {
+                let (r, g, b) = (sample(0), sample(1), sample(2));
+                let transparent = (key(0), key(1), key(2)) == (Some(r), Some(g), Some(b));
+                (
+                    (eight(r), eight(g), eight(b)),
+                    if transparent { 0 } else { 255 },
+                )
+            }
+            PALETTE => {
+                let index = sample(0) as usize;
+                let alpha = self.transparency.get(index).copied().unwrap_or(255);
+                match self.palette.get(index * 3..index * 3 + 3) {
+                    Some(rgb) => ((rgb[0], rgb[1], rgb[2]), alpha),
+                    None => ((0, 0, 0), alpha),
+                }
+            }
+            GRAY_ALPHA => {
+                let gray = eight(sample(0));
+                ((gray, gray, gray), eight(sample(1)))
+            }
+            _ => {
+                let (r, g, b) = (eight(sample(0)), eight(sample(1)), eight(sample(2)));
+                ((r, g, b), eight(sample(3)))
+            }
+        };
+        match self.dither {
+            true => (dithered_rgb(r, g, b, x, y), alpha),
+            false => (rgb(r, g, b), alpha),
+        }
+    }
+}
+
+/// Returns the type and body of the chunk at `at`.
+fn chunk(data: &[u8], at: usize) -> Result<(&[u8], &[u8]), PngError> {
+    let header = data.get(at..at + 8).ok_or(PngError::Truncated)?;
+    let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let body = data
+        .get(at + 8..at + 8 + length)
+        .ok_or(PngError::Truncated)?;
+    Ok((&header[4..], body))
+}
+
+/// The bytes of the IDAT chunks of a PNG, one after another.
+struct Idat<'a> {
+    data: &'a [u8],
+    /// the next byte
+    at: usize,
+    /// the end of the chunk body `at` is in
+    end: usize,
+}
+
+impl<'a> Idat<'a> {
+    /// The bytes of the IDAT chunk at `at` and those right after it.
+    fn new(data: &'a [u8], at: usize) -> Idat<'a> {
+        // As if at the end of a chunk before it, with no CRC.
+        Idat {
+            data,
+            at: at - 4,
+            end: at - 4,
+        }
+    }
+}
+
+impl Iterator for Idat<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        while self.at == self.end {
+            // Past the CRC to the next chunk.
+            let (kind, body) = chunk(self.data, self.end + 4).ok()?;
+            if kind != b"IDAT" {
+                return None;
+            }
+            self.at = self.end + 12;
+            self.end = self.at + body.len();
+        }
+        self.at += 1;
+        Some(self.data[self.at - 1])
+    }
+}
+
+/// Undoes the filter named by the first byte of a row, given the row above
+/// without its filter byte and the bytes of a pixel.
+fn unfilter(row: &mut [u8], above: &[u8], step: usize) -> Result<(), PngError> {
+    let (filter, row) = row.split_first_mut().ok_or(PngError::Corrupt)?;
+    for i in 0..row.len() {
+        let left = if i >= step { row[i - step] } else { 0 };
+        let upper_left = if i >= step { above[i - step] } else { 0 };
+        let predicted = match *filter {
+            0 => 0,
+            1 => left,
+            2 => above[i],
+            3 => ((left as u16 + above[i] as u16) / 2) as u8,
+            4 => paeth(left, above[i], upper_left),
+            _ => return Err(PngError::Corrupt),
+        };
+        row[i] = row[i].wrapping_add(predicted);
+    }
+    Ok(())
+}
+
+/// Returns whichever of the left, upper and upper left bytes is closest to
+/// left + upper - upper left.
+fn paeth(left: u8, upper: u8, upper_left: u8) -> u8 {
+    let estimate = left as i16 + upper as i16 - upper_left as i16;
+    let (a, b, c) = (
+        (estimate - left as i16).abs(),
+        (estimate - upper as i16).abs(),
+        (estimate - upper_left as i16).abs(),
+    );
+    if a <= b && a <= c {
+        left
+    } else if b <= c {
+        upper
+    } else {
+        upper_left
+    }
+}
+
+/// Returns the `index`th sample of a row of samples of `bits` bits.
+fn sample(row: &[u8], index: usize, bits: u8) -> u16 {
+    match bits {
+        16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+        8 => row[index] as u16,
+        // Packed from the high bits of each byte.
+        _ => {
+            let bit = index * bits as usize;
+            let shift = 8 - bits as usize - bit % 8;
+            ((row[bit / 8] >> shift) & ((1 << bits) - 1)) as u16
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::testing::fixture;
+    use std::vec;
+    use std::vec::Vec;
+
+    /// The colors and opacities of a row.
+    type Row = (Vec<u16>, Vec<u8>);
+
+    /// Returns the rows of a PNG fixture from the top.
+    fn decoded(name: &str) -> Result<Vec<Row>, PngError> {
+        let data = fixture(&std::format!("png/{name}"));
+        let png = Png::new(&data)?;
+        let mut window = vec![0u8; 32 * 1024];
+        let mut rows = Vec::new();
+        png.decode(&mut window, |_, colors, alphas| {
+            rows.push((colors.to_vec(), alphas.to_vec()))
+        })?;
+        Ok(rows)
+    }
+
+    #[test]
+    fn rows_of_each_filter_decode() {
+        // Row y of the 6x5 image is stored with filter y, none, sub, up,
+        // average and Paeth.
+        let rows = decoded("filters.png").unwrap();
+        assert_eq!(rows.len(), 5);
+        for (y, (colors, alphas)) in rows.into_iter().enumerate() {
+            let expected: Vec<u16> = (0..6)
+                .map(|x| {
+                    let r = (40 * x + 7 * y) % 256;
+                    let g = (255 - 30 * x - 11 * y) % 256;
+                    let b = (x * y * 37) % 256;
+                    rgb(r as u8, g as u8, b as u8)
+                })
+                .collect();
+            assert_eq!(colors, expected, "filter {y}");
+            assert_eq!(alphas, [255; 6]);
+        }
+    }
+
+    #[test]
+    fn palette_images_take_their_alphas_from_trns() {
+        // Two bits an index, with alphas for the first three colors.
+        let palette = [
+            rgb(255, 0, 0),
+            rgb(0, 255, 0),
+            rgb(0, 0, 255),
+            rgb(0x12, 0x34, 0x56),
+        ];
+        let alphas = [255, 0, 128, 255];
+        let rows = decoded("palette.png").unwrap();
+        for (row, indices) in rows.iter().zip([[0, 1, 2, 3, 0], [3, 2, 1, 0, 1]]) {
+            assert_eq!(row.0, indices.map(|i| palette[i]));
+            assert_eq!(row.1, indices.map(|i| alphas[i]));
+        }
+    }
+
+    #[test]
+    fn unsupported_depths_and_color_types_are_unsupported() {
+        let data = fixture("png/filters.png");
+        for (bit_depth, color_type) in [(4, RGB), (16, PALETTE), (8, 1), (3, GRAY)] {
+            let mut data = data.clone();
+            (data[24], data[25]) = (bit_depth, color_type);
+            assert_eq!(Png::new(&data).err(), Some(PngError::Unsupported));
+        }
+        assert_eq!(Png::new(&data[1..]).err(), Some(PngError::NotPng));
+    }
+
+    #[test]
+    fn a_truncated_image_is_an_error() {
+        let data = fixture("png/filters.png");
+        let mut window = vec![0u8; 32 * 1024];
+        // Without the CRC of its last IDAT chunk and IEND, which aren't read,
+        // the image is all there.
+        for length in 0..data.len() - 16 {
+            let result =
+                Png::new(&data[..length]).and_then(|png| png.decode(&mut window, |_, _, _| {}));
+            assert!(result.is_err(), "cut to {length} bytes");
+        }
+    }
+}
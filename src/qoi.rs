@@ -0,0 +1,218 @@
+use crate::bitmap::draw_pixels;
+use crate::canvas::{Canvas, Rect};
+use crate::color::{dithered_rgb, rgb};
+
+/// The widest image decoded, the pixels of the line buffer.
+pub const MAX_WIDTH: usize = 320;
+
+const OP_RGB: u8 = 0xfe;
+const OP_RGBA: u8 = 0xff;
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+
+/// Why a QOI image can't be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QoiError {
+    /// the data doesn't start with a QOI header
+    NotQoi,
+    /// wider than `MAX_WIDTH`
+    TooWide,
+    /// the data ends before the image does
+    Truncated,
+}
+
+/// A QOI image in memory, e.g. in flash, decoded a row at a time as it's
+/// drawn. QOI compresses about as well as PNG and decodes several times faster.
+///
+/// ```ignore
+/// static ICON: &[u8] = include_bytes!("icon.qoi");
+/// Qoi::new(ICON).unwrap().with_dither().draw(&mut display, 20, 20).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Qoi<'a> {
+    data: &'a [u8],
+    width: u16,
+    height: u16,
+    /// whether the colors are ordered dithered down to RGB565
+    dither: bool,
+}
+
+impl<'a> Qoi<'a> {
+    /// Reads the header of a QOI image.
+    pub fn new(data: &'a [u8]) -> Result<Qoi<'a>, QoiError> {
+        if data.len() < 14 || &data[..4] != b"qoif" {
+            return Err(QoiError::NotQoi);
+        }
+        let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+        let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
+        if width as usize > MAX_WIDTH {
+            return Err(QoiError::TooWide);
+        }
+        if height > u16::MAX as u32 {
+            return Err(QoiError::NotQoi);
+        }
+        Ok(Qoi {
+            data,
+            width: width as u16,
+            height: height as u16,
+            dither: false,
+        })
+    }
+
+    pub const fn with_dither(self) -> Qoi<'a> {
+        Qoi {
+            dither: true,
+            ..self
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Decodes the image a row at a time from the top, calling `line` with
+    /// the row and its colors and opacities.
+    pub fn decode(&self, mut line: impl FnMut(u16, &[u16], &[u8])) -> Result<(), QoiError> {
+        let mut colors = [0u16; MAX_WIDTH];
+        let mut alphas = [0u8; MAX_WIDTH];
+        let (colors, alphas) = (
+            &mut colors[..self.width as usize],
+            &mut alphas[..self.width as usize],
+        );
+        let mut index = [[0u8; 4]; 64];
+        let mut pixel = [0, 0, 0, 255];
+        let mut run = 0;
+        let mut at = 14;
+        let mut byte = || {
+            let byte = *self.data.get(at).ok_or(QoiError::Truncated)?;
+            at += 1;
+            Ok(byte)
+        };
+        for y in 0..self.height {
+            for x in 0..self.width as usize {
+                if run > 0 {
+                    run -= 1;
+                } else {
+                    let op = byte()?;
+                    match op {
+                        OP_RGB => pixel[..3].copy_from_slice(&[byte()?, byte()?, byte()?]),
+                        OP_RGBA => pixel = [byte()?, byte()?, byte()?, byte()?],
+                        _ => match op & 0xc0 {
+                            OP_INDEX => pixel = index[op as usize],
+                            OP_DIFF => {
+                                pixel[0] = pixel[0].wrapping_add((op >> 4) & 3).wrapping_sub(2);
+                                pixel[1] = pixel[1].wrapping_add((op >> 2) & 3).wrapping_sub(2);
+                                pixel[2] = pixel[2].wrapping_add(op & 3).wrapping_sub(2);
+                            }
+                            OP_LUMA => {
+                                let green = (op & 0x3f).wrapping_sub(32);
+                                let next = byte()?;
+                                let red = green.wrapping_add(next >> 4).wrapping_sub(8);
+                                let blue = green.wrapping_add(next & 15).wrapping_sub(8);
+                                pixel[0] = pixel[0].wrapping_add(red);
+                                pixel[1] = pixel[1].wrapping_add(green);
+                                pixel[2] = pixel[2].wrapping_add(blue);
+                            }
+                            // A run, the pixel again 1 to 62 times.
+                            _ => run = op & 0x3f,
+                        },
+                    }
+                    let [r, g, b, a] = pixel.map(|c| c as usize);
+                    index[(r * 3 + g * 5 + b * 7 + a * 11) % 64] = pixel;
+                }
+                let [r, g, b, a] = pixel;
+                colors[x] = match self.dither {
+                    true => dithered_rgb(r, g, b, x as i32, y as i32),
+                    false => rgb(r, g, b),
+                };
+                alphas[x] = a;
+            }
+            line(y, colors, alphas);
+        }
+        Ok(())
+    }
+
+    /// Draws the image with its top left at local (x, y), clipped to the
+    /// viewport, blending its transparent pixels like `bitmap::blit`.
+    pub fn draw<C: Canvas + ?Sized>(&self, canvas: &mut C, x: i32, y: i32) -> Result<(), QoiError> {
+        let (origin_x, origin_y) = canvas.origin();
+        let target = Rect::new(
+            x + origin_x,
+            y + origin_y,
+            self.width as i32,
+            self.height as i32,
+        );
+        let visible = target.intersect(&canvas.clip());
+        if visible.is_empty() {
+            return Ok(());
+        }
+        let (left, right) = (
+            (visible.x - target.x) as usize,
+            (visible.right() - target.x) as usize,
+        );
+        self.decode(|y, colors, alphas| {
+            let row = target.y + y as i32;
+            if row >= visible.y && row < visible.bottom() {
+                let (colors, alphas) = (&colors[left..right], &alphas[left..right]);
+                draw_pixels(canvas, visible.x, row, colors, alphas);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::testing::fixture;
+    use std::vec::Vec;
+
+    /// The pixels of the 4x3 fixture, coded with each op: RGB, a run, a
+    /// diff, luma, an index, RGBA, a run, an index, RGB and a run.
+    const PIXELS: [[u8; 4]; 12] = [
+        [200, 100, 50, 255],
+        [200, 100, 50, 255],
+        [200, 100, 50, 255],
+        [201, 99, 51, 255],
+        [211, 109, 59, 255],
+        [200, 100, 50, 255],
+        [0, 0, 255, 128],
+        [0, 0, 255, 128],
+        [211, 109, 59, 255],
+        [50, 60, 70, 255],
+        [50, 60, 70, 255],
+        [50, 60, 70, 255],
+    ];
+
+    #[test]
+    fn each_op_decodes() {
+        let data = fixture("qoi/ops.qoi");
+        let qoi = Qoi::new(&data).unwrap();
+        assert_eq!((qoi.width(), qoi.height()), (4, 3));
+        let mut rows = Vec::new();
+        qoi.decode(|y, colors, alphas| rows.push((y, colors.to_vec(), alphas.to_vec())))
+            .unwrap();
+        for (y, row) in PIXELS.chunks(4).enumerate() {
+            let colors: Vec<u16> = row.iter().map(|&[r, g, b, _]| rgb(r, g, b)).collect();
+            let alphas: Vec<u8> = row.iter().map(|pixel| pixel[3]).collect();
+            assert_eq!(rows[y], (y as u16, colors, alphas));
+        }
+    }
+
+    #[test]
+    fn a_truncated_image_is_an_error() {
+        let data = fixture("qoi/ops.qoi");
+        // Without its 8-byte end marker the image is all there.
+        for length in 0..data.len() - 8 {
+            let result = Qoi::new(&data[..length]).and_then(|qoi| qoi.decode(|_, _, _| {}));
+            assert!(result.is_err(), "cut to {length} bytes");
+        }
+        assert_eq!(Qoi::new(b"qoi").err(), Some(QoiError::NotQoi));
+    }
+}
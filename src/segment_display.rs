@@ -0,0 +1,560 @@
+use crate::canvas::Canvas;
+use crate::shapes;
+
+/// The segments of a digit, as bits of a mask.
+///
+/// ```text
+///  ---A---
+/// |\  |  /|
+/// F H J K B
+/// |  \|/  |
+///  -G1-G2-
+/// |  /|\  |
+/// E L M N C
+/// |/  |  \|
+///  ---D---  DP
+/// ```
+///
+/// Seven-segment digits light both halves of G.
+pub mod segment {
+    pub const A: u16 = 1 << 0;
+    pub const B: u16 = 1 << 1;
+    pub const C: u16 = 1 << 2;
+    pub const D: u16 = 1 << 3;
+    pub const E: u16 = 1 << 4;
+    pub const F: u16 = 1 << 5;
+    pub const G1: u16 = 1 << 6;
+    pub const G2: u16 = 1 << 7;
+    pub const H: u16 = 1 << 8;
+    pub const J: u16 = 1 << 9;
+    pub const K: u16 = 1 << 10;
+    pub const L: u16 = 1 << 11;
+    pub const M: u16 = 1 << 12;
+    pub const N: u16 = 1 << 13;
+    pub const DP: u16 = 1 << 14;
+    /// both halves of the middle segment
+    pub const G: u16 = G1 | G2;
+}
+
+use segment::*;
+
+/// The segments of seven-segment and fourteen-segment digits.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    Seven,
+    Fourteen,
+}
+
+impl SegmentKind {
+    /// Returns the segments of the digits of this kind.
+    pub const fn segments(&self) -> u16 {
+        match self {
+            SegmentKind::Seven => A | B | C | D | E | F | G | DP,
+            SegmentKind::Fourteen => 0x7fff,
+        }
+    }
+
+    /// Returns the segments showing a character, nothing for characters
+    /// without a shape.
+    pub fn encode(&self, c: char) -> u16 {
+        match self {
+            SegmentKind::Seven => seven_segments(c),
+            SegmentKind::Fourteen => fourteen_segments(c),
+        }
+    }
+}
+
+fn seven_segments(c: char) -> u16 {
+    match c.to_ascii_uppercase() {
+        '0' | 'O' => A | B | C | D | E | F,
+        '1' => B | C,
+        '2' => A | B | G | E | D,
+        '3' => A | B | G | C | D,
+        '4' => F | G | B | C,
+        '5' | 'S' => A | F | G | C | D,
+        '6' => A | F | G | E | C | D,
+        '7' => A | B | C,
+        '8' => A | B | C | D | E | F | G,
+        '9' => A | B | C | D | F | G,
+        'A' => A | B | C | E | F | G,
+        'B' => C | D | E | F | G,
+        'C' => A | D | E | F,
+        'D' => B | C | D | E | G,
+        'E' => A | D | E | F | G,
+        'F' => A | E | F | G,
+        'H' => B | C | E | F | G,
+        'L' => D | E | F,
+        'P' => A | B | E | F | G,
+        'U' => B | C | D | E | F,
+        '-' => G,
+        '_' => D,
+        '=' => D | G,
+        _ => 0,
+    }
+}
+
+fn fourteen_segments(c: char) -> u16 {
+    match c.to_ascii_uppercase() {
+        '0' => A | B | C | D | E | F | K | L,
+        '1' => B | C | K,
+        '2' => A | B | D | E | G,
+        '3' => A | B | C | D | G2,
+        '4' => B | C | F | G,
+        '5' => A | C | D | F | G,
+        '6' => A | C | D | E | F | G,
+        '7' => A | B | C,
+        '8' => A | B | C | D | E | F | G,
+        '9' => A | B | C | D | F | G,
+        'A' => A | B | C | E | F | G,
+        'B' => A | B | C | D | G2 | J | M,
+        'C' => A | D | E | F,
+        'D' => A | B | C | D | J | M,
+        'E' => A | D | E | F | G1,
+        'F' => A | E | F | G1,
+        'G' => A | C | D | E | F | G2,
+        'H' => B | C | E | F | G,
+        'I' => A | D | J | M,
+        'J' => B | C | D | E,
+        'K' => E | F | G1 | K | N,
+        'L' => D | E | F,
+        'M' => B | C | E | F | H | K,
+        'N' => B | C | E | F | H | N,
+        'O' => A | B | C | D | E | F,
+        'P' => A | B | E | F | G,
+        'Q' => A | B | C | D | E | F | N,
+        'R' => A | B | E | F | G | N,
+        'S' => A | C | D | F | G,
+        'T' => A | J | M,
+        'U' => B | C | D | E | F,
+        'V' => E | F | K | L,
+        'W' => B | C | E | F | L | N,
+        'X' => H | K | L | N,
+        'Y' => H | K | M,
+        'Z' => A | D | K | L,
+        '-' => G,
+        '+' => G | J | M,
+        '*' => G | H | J | K | L | M | N,
+        '/' => K | L,
+        '\\' => H | N,
+        '(' | '<' => K | N,
+        ')' | '>' => H | L,
+        '_' => D,
+        '=' => D | G,
+        '\'' => J,
+        '"' => B | J,
+        _ => 0,
+    }
+}
+
+/// The size and colors of segment digits.
+#[derive(Clone, Copy)]
+pub struct SegmentStyle {
+    /// the width of a digit, without the slant
+    pub width: u16,
+    /// the height of a digit
+    pub height: u16,
+    /// the thickness of the segments
+    pub thickness: u16,
+    /// how far right the top of a digit is from its bottom
+    pub slant: u16,
+    /// the pixels between two digits, where the decimal point is
+    pub spacing: u16,
+    /// the color of lit segments
+    pub lit: u16,
+    /// the color of unlit segments, the background to hide them
+    pub unlit: u16,
+}
+
+impl SegmentStyle {
+    /// Creates upright digits spaced by a thickness.
+    pub const fn new(
+        width: u16,
+        height: u16,
+        thickness: u16,
+        lit: u16,
+        unlit: u16,
+    ) -> SegmentStyle {
+        SegmentStyle {
+            width,
+            height,
+            thickness,
+            slant: 0,
+            spacing: thickness,
+            lit,
+            unlit,
+        }
+    }
+
+    pub const fn with_slant(self, slant: u16) -> SegmentStyle {
+        SegmentStyle { slant, ..self }
+    }
+
+    pub const fn with_spacing(self, spacing: u16) -> SegmentStyle {
+        SegmentStyle { spacing, ..self }
+    }
+
+    /// Returns the columns of a segment on a row of a digit, from its left
+    /// without the slant, or None when the segment isn't on the row.
+    fn span(&self, segment: u16, y: u16) -> Option<(u16, u16)> {
+        // The pixels between segments.
+        const GAP: u16 = 1;
+        let (w, h, t) = (self.width, self.height, self.thickness);
+        let middle_top = h.saturating_sub(t) / 2;
+        let middle_bottom = middle_top + t;
+        let center_left = w.saturating_sub(t) / 2;
+        let center_right = center_left + t;
+
+        let rows = |top: u16, bottom: u16| y >= top && y < bottom;
+        let upper = rows(t + GAP, middle_top.saturating_sub(GAP));
+        let lower = rows(middle_bottom + GAP, h.saturating_sub(t + GAP));
+        // A diagonal from one corner of an area to the other.
+        let diagonal = |left: u16, right: u16, top: u16, bottom: u16, down_right: bool| {
+            let travel = right.saturating_sub(left + t) as u32;
+            let height = bottom.saturating_sub(top + 1).max(1) as u32;
+            let step = ((y - top) as u32 * travel / height) as u16;
+            let x = if down_right {
+                left + step
+            } else {
+                right.saturating_sub(t) - step
+            };
+            Some((x, (x + t).min(right)))
+        };
+        let inner_left = t + GAP;
+        let inner_right = w.saturating_sub(t + GAP);
+
+        match segment {
+            A if rows(0, t) => Some((inner_left, inner_right)),
+            D if rows(h.saturating_sub(t), h) => Some((inner_left, inner_right)),
+            B if upper => Some((w.saturating_sub(t), w)),
+            C if lower => Some((w.saturating_sub(t), w)),
+            E if lower => Some((0, t)),
+            F if upper => Some((0, t)),
+            G1 if rows(middle_top, middle_bottom) => Some((inner_left, center_left + t / 2)),
+            G2 if rows(middle_top, middle_bottom) => Some((center_left + t / 2, inner_right)),
+            J if upper => Some((center_left, center_right)),
+            M if lower => Some((center_left, center_right)),
+            H if upper => diagonal(
+                inner_left,
+                center_left.saturating_sub(GAP),
+                t + GAP,
+                middle_top.saturating_sub(GAP),
+                true,
+            ),
+            K if upper => diagonal(
+                center_right + GAP,
+                inner_right,
+                t + GAP,
+                middle_top.saturating_sub(GAP),
+                false,
+            ),
+            L if lower => diagonal(
+                inner_left,
+                center_left.saturating_sub(GAP),
+                middle_bottom + GAP,
+                h.saturating_sub(t + GAP),
+                false,
+            ),
+            N if lower => diagonal(
+                center_right + GAP,
+                inner_right,
+                middle_bottom + GAP,
+                h.saturating_sub(t + GAP),
+                true,
+            ),
+            DP if rows(h.saturating_sub(t), h) && self.spacing >= t => {
+                let x = w + (self.spacing - t) / 2;
+                Some((x, x + t))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A row of seven-segment or fourteen-segment digits, redrawing only the
+/// segments that changed.
+///
+/// ```ignore
+/// let mut mines = SegmentDisplay::<3>::new(4, 4, SegmentKind::Seven, style);
+/// mines.set_number(remaining, true);
+/// mines.render(&mut display);
+/// ```
+pub struct SegmentDisplay<const DIGITS: usize> {
+    /// the left of the bottom of the first digit
+    x: i32,
+    /// the top of the digits
+    y: i32,
+    /// the segments of the digits
+    kind: SegmentKind,
+    /// the size and colors of the digits
+    style: SegmentStyle,
+    /// the lit segments of each digit
+    segments: [u16; DIGITS],
+    /// the lit segments of each digit on the display, None when not drawn
+    shown: [Option<u16>; DIGITS],
+}
+
+impl<const DIGITS: usize> SegmentDisplay<DIGITS> {
+    pub const fn new(x: i32, y: i32, kind: SegmentKind, style: SegmentStyle) -> Self {
+        SegmentDisplay {
+            x,
+            y,
+            kind,
+            style,
+            segments: [0; DIGITS],
+            shown: [None; DIGITS],
+        }
+    }
+
+    /// Returns the width of the digits on the display.
+    pub fn width(&self) -> u16 {
+        let style = &self.style;
+        DIGITS as u16 * (style.width + style.spacing) + style.slant
+    }
+
+    /// Lights segments of a digit, see `segment`.
+    pub fn set_segments(&mut self, digit: usize, segments: u16) {
+        self.segments[digit] = segments & self.kind.segments();
+    }
+
+    /// Shows a text from the left, blanking the digits after it.
+    /// A '.' lights the decimal point of the digit before it.
+    pub fn set_text(&mut self, text: &str) {
+        let mut digit = 0;
+        let mut segments = [0; DIGITS];
+        for c in text.chars() {
+            if c == '.' && digit > 0 && segments[digit - 1] & DP == 0 {
+                segments[digit - 1] |= DP;
+                continue;
+            }
+            if digit == DIGITS {
+                break;
+            }
+            segments[digit] = self.kind.encode(c);
+            digit += 1;
+        }
+        for (digit, segments) in segments.into_iter().enumerate() {
+            self.set_segments(digit, segments);
+        }
+    }
+
+    /// Shows a number on the right, after zeros or blanks.
+    /// Numbers too long for the digits show dashes.
+    pub fn set_number(&mut self, value: i32, zero_padded: bool) {
+        let mut segments = [0; DIGITS];
+        let mut n = value.unsigned_abs();
+        let mut digit = DIGITS;
+        loop {
+            if digit == 0 {
+                segments = [G; DIGITS];
+                break;
+            }
+            digit -= 1;
+            segments[digit] = self.kind.encode(char::from(b'0' + (n % 10) as u8));
+            n /= 10;
+            if n == 0 {
+                let sign = if value < 0 { 1 } else { 0 };
+                if digit < sign {
+                    segments = [G; DIGITS];
+                    break;
+                }
+                let fill = if zero_padded {
+                    self.kind.encode('0')
+                } else {
+                    0
+                };
+                for s in &mut segments[sign..digit] {
+                    *s = fill;
+                }
+                if value < 0 {
+                    // The sign before the padding zeros, right before the number otherwise.
+                    segments[if zero_padded { 0 } else { digit - 1 }] = G;
+                }
+                break;
+            }
+        }
+        for (digit, segments) in segments.into_iter().enumerate() {
+            self.set_segments(digit, segments);
+        }
+    }
+
+    /// Redraws all the digits on the next render.
+    pub fn invalidate(&mut self) {
+        self.shown = [None; DIGITS];
+    }
+
+    /// Draws the changed segments on the display or a framebuffer.
+    pub fn render<C: Canvas + ?Sized>(&mut self, canvas: &mut C) {
+        let style = self.style;
+        for digit in 0..DIGITS {
+            let segments = self.segments[digit];
+            let changed = match self.shown[digit] {
+                Some(shown) => shown ^ segments,
+                None => self.kind.segments(),
+            };
+            if changed == 0 {
+                continue;
+            }
+            let x = self.x + digit as i32 * (style.width + style.spacing) as i32;
+            // Unlit segments first, so they never cover a lit one.
+            for lit in [false, true] {
+                for bit in 0..15 {
+                    let segment = 1 << bit;
+                    if changed & segment != 0 && (segments & segment != 0) == lit {
+                        let color = if lit { style.lit } else { style.unlit };
+                        self.draw_segment(canvas, x, segment, color);
+                    }
+                }
+            }
+            self.shown[digit] = Some(segments);
+        }
+    }
+
+    /// Draws a segment of the digit at `x`, merging rows of the same span
+    /// into one rectangle.
+    fn draw_segment<C: Canvas + ?Sized>(&self, canvas: &mut C, x: i32, segment: u16, color: u16) {
+        let style = &self.style;
+        let height = style.height;
+        let shift = |y: u16| (style.slant as u32 * (height - 1 - y) as u32 / height as u32) as u16;
+        // The columns and first row of the rectangle being merged.
+        let mut run: Option<(u16, u16, u16)> = None;
+        for y in 0..=height {
+            let span = if y < height {
+                style
+                    .span(segment, y)
+                    .map(|(l, r)| (l + shift(y), r + shift(y)))
+            } else {
+                None
+            };
+            match (run, span) {
+                (Some((l, r, _)), Some(span)) if (l, r) == span => continue,
+                (Some((l, r, top)), _) => {
+                    if r > l {
+                        let (left, top_y) = (x + l as i32, self.y + top as i32);
+                        let (width, height) = ((r - l) as i32, (y - top) as i32);
+                        shapes::fill_rect(canvas, left, top_y, width, height, color);
+                    }
+                    run = span.map(|(l, r)| (l, r, y));
+                }
+                (None, _) => run = span.map(|(l, r)| (l, r, y)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::Rect;
+    use crate::framebuffer::Framebuffer;
+    use crate::testing::buffer;
+
+    const LIT: u16 = 0xffff;
+    const UNLIT: u16 = 0x0841;
+    const STYLE: SegmentStyle = SegmentStyle::new(8, 14, 2, LIT, UNLIT);
+
+    fn digits(text: &str) -> [u16; 4] {
+        let mut digits = [0; 4];
+        for (digit, c) in digits.iter_mut().zip(text.chars()) {
+            *digit = match c {
+                '-' => G,
+                ' ' => 0,
+                c => SegmentKind::Seven.encode(c),
+            };
+        }
+        digits
+    }
+
+    #[test]
+    fn negative_numbers_have_their_sign_before_the_digits_or_the_zeros() {
+        let mut display = SegmentDisplay::<4>::new(0, 0, SegmentKind::Seven, STYLE);
+        display.set_number(-42, false);
+        assert_eq!(display.segments, digits(" -42"));
+        display.set_number(-42, true);
+        assert_eq!(display.segments, digits("-042"));
+        display.set_number(-999, false);
+        assert_eq!(display.segments, digits("-999"));
+        display.set_number(42, true);
+        assert_eq!(display.segments, digits("0042"));
+    }
+
+    #[test]
+    fn numbers_too_long_for_the_digits_show_dashes() {
+        let mut display = SegmentDisplay::<4>::new(0, 0, SegmentKind::Seven, STYLE);
+        display.set_number(12345, false);
+        assert_eq!(display.segments, digits("----"));
+        display.set_number(-1000, true);
+        assert_eq!(display.segments, digits("----"));
+        display.set_number(i32::MIN, false);
+        assert_eq!(display.segments, digits("----"));
+    }
+
+    #[test]
+    fn a_single_digit_shows_a_dash_for_any_negative_number() {
+        let mut display = SegmentDisplay::<1>::new(0, 0, SegmentKind::Seven, STYLE);
+        display.set_number(7, false);
+        assert_eq!(display.segments, [SegmentKind::Seven.encode('7')]);
+        display.set_number(-1, false);
+        assert_eq!(display.segments, [G]);
+        display.set_number(-1, true);
+        assert_eq!(display.segments, [G]);
+    }
+
+    /// A framebuffer counting the pixels drawn on it.
+    struct Counter<'a> {
+        frame: Framebuffer<'a>,
+        drawn: usize,
+    }
+
+    impl Canvas for Counter<'_> {
+        fn size(&self) -> (u16, u16) {
+            Canvas::size(&self.frame)
+        }
+
+        fn clip(&self) -> Rect {
+            self.frame.clip()
+        }
+
+        fn fill_span(&mut self, x: u16, y: u16, width: u16, color: u16) {
+            self.drawn += width as usize;
+            self.frame.fill_span(x, y, width, color);
+        }
+    }
+
+    fn count(frame: &Framebuffer, color: u16) -> usize {
+        let (width, height) = Canvas::size(frame);
+        (0..height as i32)
+            .flat_map(|y| (0..width as i32).map(move |x| (x, y)))
+            .filter(|&(x, y)| frame.get_pixel(x, y) == Some(color))
+            .count()
+    }
+
+    #[test]
+    fn render_draws_only_the_segments_that_changed() {
+        let mut pixels = buffer(40, 14);
+        let frame = Framebuffer::new(&mut pixels, 40, 14);
+        let mut canvas = Counter { frame, drawn: 0 };
+        let mut display = SegmentDisplay::<4>::new(0, 0, SegmentKind::Seven, STYLE);
+        display.set_text("1234");
+        display.render(&mut canvas);
+        assert!(canvas.drawn > 0);
+
+        canvas.drawn = 0;
+        display.set_number(1234, false);
+        display.render(&mut canvas);
+        assert_eq!(canvas.drawn, 0);
+
+        // '1' to '7' lights A and nothing else.
+        let lit = count(&canvas.frame, LIT);
+        display.set_text("7234");
+        display.render(&mut canvas);
+        assert!(canvas.drawn > 0);
+        assert_eq!(count(&canvas.frame, LIT), lit + canvas.drawn);
+
+        // The same pixels as drawing "7234" from scratch.
+        let mut expected = buffer(40, 14);
+        let mut frame = Framebuffer::new(&mut expected, 40, 14);
+        display.invalidate();
+        display.render(&mut frame);
+        assert_eq!(pixels, expected);
+    }
+}
@@ -0,0 +1,857 @@
+use crate::canvas::{Canvas, Rect};
+use crate::geometry::LinePoints;
+use crate::trig::sin_cos;
+
+/// The most polygon edges crossing a row that `fill_polygon` fills between.
+const MAX_CROSSINGS: usize = 64;
+
+/// Returns the clip rectangle of a canvas in local coordinates.
+fn local_clip<C: Canvas + ?Sized>(canvas: &C) -> Rect {
+    let (x, y) = canvas.origin();
+    canvas.clip().offset(-x, -y)
+}
+
+/// Fills the pixels from `x0` to `x1` of row `y`, clipped.
+pub fn span<C: Canvas + ?Sized>(canvas: &mut C, x0: i32, x1: i32, y: i32, color: u16) {
+    let (x, y) = (x0.min(x1), y);
+    fill_rect(canvas, x, y, x0.max(x1) - x + 1, 1, color);
+}
+
+/// Draws a pixel, clipped.
+pub fn pixel<C: Canvas + ?Sized>(canvas: &mut C, x: i32, y: i32, color: u16) {
+    span(canvas, x, x, y, color);
+}
+
+/// Fills a rectangle, clipped.
+pub fn fill_rect<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    color: u16,
+) {
+    let (origin_x, origin_y) = canvas.origin();
+    let rect = Rect::new(x + origin_x, y + origin_y, width, height).intersect(&canvas.clip());
+    if rect.is_empty() {
+        return;
+    }
+    let (x, y, width) = (rect.x as u16, rect.y as u16, rect.width as u16);
+    if rect.height == 1 {
+        canvas.fill_span(x, y, width, color);
+    } else {
+        canvas.fill_rect(x, y, width, rect.height as u16, color);
+    }
+}
+
+/// Draws the outline of a rectangle, clipped.
+pub fn draw_rect<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    color: u16,
+) {
+    if width <= 0 || height <= 0 {
+        return;
+    }
+    span(canvas, x, x + width - 1, y, color);
+    span(canvas, x, x + width - 1, y + height - 1, color);
+    fill_rect(canvas, x, y + 1, 1, height - 2, color);
+    fill_rect(canvas, x + width - 1, y + 1, 1, height - 2, color);
+}
+
+/// Draws a line from (x0, y0) to (x1, y1), clipped.
+/// The pixels of a row are drawn as one span.
+pub fn draw_line<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    color: u16,
+) {
+    let mut run: Option<(i32, i32, i32)> = None;
+    for (x, y) in LinePoints::new(x0, y0, x1, y1) {
+        run = match run {
+            Some((start, _, row)) if row == y => Some((start, x, row)),
+            Some((start, end, row)) => {
+                span(canvas, start, end, row, color);
+                Some((x, x, y))
+            }
+            None => Some((x, x, y)),
+        };
+    }
+    if let Some((start, end, row)) = run {
+        span(canvas, start, end, row, color);
+    }
+}
+
+/// Returns the half width of a circle of radius `r` on a row `dy` from its
+/// center, where the pixels are within `r` and a half of it.
+fn half_width(r: i32, dy: i32) -> i32 {
+    let (r, dy) = (r as i64, dy as i64);
+    (r * r + r - dy * dy).max(0).isqrt() as i32
+}
+
+/// Returns the columns of each row of a circle.
+fn circle_columns(cx: i32, cy: i32, r: i32) -> impl Fn(i32) -> Option<(i32, i32)> {
+    move |y| {
+        let dy = y - cy;
+        if dy.abs() > r {
+            return None;
+        }
+        let w = half_width(r, dy);
+        Some((cx - w, cx + w))
+    }
+}
+
+/// Returns the columns of each row of an ellipse, a circle stretched to
+/// radius `rx` across.
+fn ellipse_columns(cx: i32, cy: i32, rx: i32, ry: i32) -> impl Fn(i32) -> Option<(i32, i32)> {
+    move |y| {
+        let dy = y - cy;
+        if dy.abs() > ry {
+            return None;
+        }
+        if ry == 0 {
+            return Some((cx - rx, cx + rx));
+        }
+        let (rx, ry, dy) = (rx as i64, ry as i64, dy as i64);
+        let w = ((rx * rx * (ry * ry - dy * dy) + rx * ry * ry) / (ry * ry)).isqrt() as i32;
+        Some((cx - w, cx + w))
+    }
+}
+
+/// Returns the columns of each row of a rectangle with rounded corners.
+fn rounded_rect_columns(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    r: i32,
+) -> impl Fn(i32) -> Option<(i32, i32)> {
+    let r = r.min(width / 2).min(height / 2).max(0);
+    move |row| {
+        if row < y || row >= y + height {
+            return None;
+        }
+        // The distance from the row to the centers of the corners.
+        let d = (y + r - row).max(row - (y + height - 1 - r)).max(0);
+        let inset = r - half_width(r, d);
+        Some((x + inset, x + width - 1 - inset))
+    }
+}
+
+/// The angles of an arc, counterclockwise from the right of its center.
+struct Sector {
+    /// the direction of the start, y up, with 14 fractional bits
+    start: (i32, i32),
+    /// the direction of the end
+    end: (i32, i32),
+    /// whether the arc is over half a turn
+    wide: bool,
+    /// whether the arc is a whole turn
+    full: bool,
+}
+
+impl Sector {
+    fn new(start_angle: i32, end_angle: i32) -> Sector {
+        let sweep = (end_angle - start_angle).rem_euclid(360);
+        let (start_sin, start_cos) = sin_cos(start_angle);
+        let (end_sin, end_cos) = sin_cos(end_angle);
+        Sector {
+            start: (start_cos, start_sin),
+            end: (end_cos, end_sin),
+            wide: sweep > 180,
+            full: sweep == 0 && end_angle != start_angle,
+        }
+    }
+
+    /// Returns whether the arc covers a point from its center, y down.
+    fn contains(&self, dx: i32, dy: i32) -> bool {
+        if self.full {
+            return true;
+        }
+        let dy = -dy;
+        let after_start = self.start.0 * dy - self.start.1 * dx >= 0;
+        let before_end = dx * self.end.1 - dy * self.end.0 >= 0;
+        if self.wide {
+            after_start || before_end
+        } else {
+            after_start && before_end
+        }
+    }
+}
+
+/// Draws a shape from its columns on each row from `top` to `bottom`,
+/// filled or as a one pixel outline, keeping the pixels in `sector` around
+/// (`cx`, `cy`) if any.
+#[allow(clippy::too_many_arguments)]
+fn draw_rows<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    top: i32,
+    bottom: i32,
+    columns: impl Fn(i32) -> Option<(i32, i32)>,
+    fill: bool,
+    sector: Option<(&Sector, i32, i32)>,
+    color: u16,
+) {
+    let emit = |canvas: &mut C, left: i32, right: i32, y: i32| match sector {
+        None => span(canvas, left, right, y, color),
+        Some((sector, cx, cy)) => {
+            let mut run: Option<i32> = None;
+            for x in left..=right + 1 {
+                let inside = x <= right && sector.contains(x - cx, y - cy);
+                match (run, inside) {
+                    (None, true) => run = Some(x),
+                    (Some(start), false) => {
+                        span(canvas, start, x - 1, y, color);
+                        run = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    };
+
+    // The outline of a row reaches the narrower of the rows around it.
+    let clip = local_clip(canvas);
+    for y in top.max(clip.y)..=bottom.min(clip.bottom() - 1) {
+        let Some((left, right)) = columns(y) else {
+            continue;
+        };
+        if fill {
+            emit(canvas, left, right, y);
+            continue;
+        }
+        let above = if y > top { columns(y - 1) } else { None };
+        let below = if y < bottom { columns(y + 1) } else { None };
+        match (above, below) {
+            (Some((above_left, above_right)), Some((below_left, below_right))) => {
+                let left_end = (above_left.max(below_left) - 1).clamp(left, right);
+                let right_start = (above_right.min(below_right) + 1).clamp(left, right);
+                if left_end + 1 >= right_start {
+                    emit(canvas, left, right, y);
+                } else {
+                    emit(canvas, left, left_end, y);
+                    emit(canvas, right_start, right, y);
+                }
+            }
+            _ => emit(canvas, left, right, y),
+        }
+    }
+}
+
+/// Draws the outline of a circle.
+pub fn draw_circle<C: Canvas + ?Sized>(canvas: &mut C, cx: i32, cy: i32, r: i32, color: u16) {
+    let columns = circle_columns(cx, cy, r);
+    draw_rows(canvas, cy - r, cy + r, columns, false, None, color);
+}
+
+/// Fills a circle.
+pub fn fill_circle<C: Canvas + ?Sized>(canvas: &mut C, cx: i32, cy: i32, r: i32, color: u16) {
+    let columns = circle_columns(cx, cy, r);
+    draw_rows(canvas, cy - r, cy + r, columns, true, None, color);
+}
+
+/// Draws the outline of an ellipse with radii `rx` and `ry`.
+pub fn draw_ellipse<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    color: u16,
+) {
+    let columns = ellipse_columns(cx, cy, rx, ry);
+    draw_rows(canvas, cy - ry, cy + ry, columns, false, None, color);
+}
+
+/// Fills an ellipse with radii `rx` and `ry`.
+pub fn fill_ellipse<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    color: u16,
+) {
+    let columns = ellipse_columns(cx, cy, rx, ry);
+    draw_rows(canvas, cy - ry, cy + ry, columns, true, None, color);
+}
+
+/// Draws an arc of a circle, counterclockwise from `start_angle` to
+/// `end_angle`, in degrees from the right of the center.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_arc<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    start_angle: i32,
+    end_angle: i32,
+    color: u16,
+) {
+    let sector = Sector::new(start_angle, end_angle);
+    let columns = circle_columns(cx, cy, r);
+    draw_rows(
+        canvas,
+        cy - r,
+        cy + r,
+        columns,
+        false,
+        Some((&sector, cx, cy)),
+        color,
+    );
+}
+
+/// Draws the outline of a pie slice, an arc like `draw_arc` and the radii
+/// to its ends.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_pie<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    start_angle: i32,
+    end_angle: i32,
+    color: u16,
+) {
+    draw_arc(canvas, cx, cy, r, start_angle, end_angle, color);
+    for angle in [start_angle, end_angle] {
+        let (sin, cos) = sin_cos(angle);
+        let x = cx + (r * cos + (1 << 13)) / (1 << 14);
+        let y = cy - (r * sin + (1 << 13)) / (1 << 14);
+        draw_line(canvas, cx, cy, x, y, color);
+    }
+}
+
+/// Fills a pie slice, counterclockwise from `start_angle` to `end_angle`,
+/// in degrees from the right of the center.
+#[allow(clippy::too_many_arguments)]
+pub fn fill_pie<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    start_angle: i32,
+    end_angle: i32,
+    color: u16,
+) {
+    let sector = Sector::new(start_angle, end_angle);
+    let columns = circle_columns(cx, cy, r);
+    draw_rows(
+        canvas,
+        cy - r,
+        cy + r,
+        columns,
+        true,
+        Some((&sector, cx, cy)),
+        color,
+    );
+}
+
+/// Draws the outline of a rectangle with corners rounded to a radius.
+pub fn draw_rounded_rect<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    r: i32,
+    color: u16,
+) {
+    let columns = rounded_rect_columns(x, y, width, height, r);
+    draw_rows(canvas, y, y + height - 1, columns, false, None, color);
+}
+
+/// Fills a rectangle with corners rounded to a radius.
+pub fn fill_rounded_rect<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    r: i32,
+    color: u16,
+) {
+    let columns = rounded_rect_columns(x, y, width, height, r);
+    draw_rows(canvas, y, y + height - 1, columns, true, None, color);
+}
+
+/// Draws the outline of a triangle.
+pub fn draw_triangle<C: Canvas + ?Sized>(canvas: &mut C, points: [(i32, i32); 3], color: u16) {
+    draw_polygon(canvas, &points, color);
+}
+
+/// Fills a triangle, like `fill_polygon`.
+pub fn fill_triangle<C: Canvas + ?Sized>(canvas: &mut C, points: [(i32, i32); 3], color: u16) {
+    fill_polygon(canvas, &points, color);
+}
+
+/// Draws the outline of a polygon, closed from the last point to the first.
+pub fn draw_polygon<C: Canvas + ?Sized>(canvas: &mut C, points: &[(i32, i32)], color: u16) {
+    for (i, &(x0, y0)) in points.iter().enumerate() {
+        let (x1, y1) = points[(i + 1) % points.len()];
+        draw_line(canvas, x0, y0, x1, y1, color);
+    }
+}
+
+/// Fills a polygon, convex or not, by the even-odd rule.
+///
+/// The points are the corners of pixels, so a square from (0, 0) to (10, 10)
+/// fills the pixels of `fill_rect(canvas, 0, 0, 10, 10, color)`. Rows crossing
+/// more than 64 edges are filled between the first 64.
+pub fn fill_polygon<C: Canvas + ?Sized>(canvas: &mut C, points: &[(i32, i32)], color: u16) {
+    fill_fixed_polygon(
+        canvas,
+        points.len(),
+        |i| (points[i].0 << 8, points[i].1 << 8),
+        color,
+    );
+}
+
+/// Which parts of a shape whose outlines cross or nest are filled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    /// the parts inside an odd number of outlines
+    EvenOdd,
+    /// the parts the outlines wind around more one way than the other
+    NonZero,
+}
+
+/// Fills a polygon of `count` points with 8 fractional bits, by the even-odd rule.
+fn fill_fixed_polygon<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    count: usize,
+    point: impl Fn(usize) -> (i32, i32),
+    color: u16,
+) {
+    if count < 3 {
+        return;
+    }
+    let ys = (0..count).map(|i| point(i).1);
+    let (top, bottom) = (ys.clone().min().unwrap_or(0), ys.max().unwrap_or(0));
+    let edges = |edge: &mut dyn FnMut((i32, i32), (i32, i32))| {
+        for i in 0..count {
+            edge(point(i), point((i + 1) % count));
+        }
+    };
+    fill_edges(canvas, top, bottom, edges, FillRule::EvenOdd, color);
+}
+
+/// Fills a shape between `top` and `bottom` outlined by the edges `edges`
+/// calls back with, in points with 8 fractional bits. The edges are asked for
+/// once per row.
+pub(crate) fn fill_edges<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    top: i32,
+    bottom: i32,
+    edges: impl Fn(&mut dyn FnMut((i32, i32), (i32, i32))),
+    rule: FillRule,
+    color: u16,
+) {
+    let clip = local_clip(canvas);
+    let top = (top >> 8).max(clip.y);
+    let bottom = ((bottom >> 8) + 1).min(clip.bottom());
+
+    // Where the edges cross the centers of a row, and which way they go.
+    let mut crossings = [(0i32, 0i32); MAX_CROSSINGS];
+    for y in top..bottom {
+        let center = (y << 8) + 128;
+        let mut found = 0;
+        edges(&mut |(x0, y0), (x1, y1)| {
+            if (center > y0) == (center > y1) || found == MAX_CROSSINGS {
+                return;
+            }
+            let dx = (center - y0) as i64 * (x1 - x0) as i64 / (y1 - y0) as i64;
+            crossings[found] = (x0 + dx as i32, if y1 > y0 { 1 } else { -1 });
+            found += 1;
+        });
+        let crossings = &mut crossings[..found];
+        crossings.sort_unstable_by_key(|crossing| crossing.0);
+
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            winding += pair[0].1;
+            let inside = match rule {
+                FillRule::EvenOdd => winding % 2 != 0,
+                FillRule::NonZero => winding != 0,
+            };
+            // The pixels with their centers between the crossings.
+            let first = (pair[0].0 + 127) >> 8;
+            let last = ((pair[1].0 + 127) >> 8) - 1;
+            if inside && first <= last {
+                span(canvas, first, last, y, color);
+            }
+        }
+    }
+}
+
+/// How the ends of thick lines are drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    /// the line ends square at its end points
+    Butt,
+    /// the line ends square, half its thickness past its end points
+    Square,
+    /// the line ends in a half circle around its end points
+    Round,
+}
+
+/// The color, thickness, caps and dashes of a line.
+#[derive(Clone, Copy, Debug)]
+pub struct LineStyle<'a> {
+    /// the color of the line
+    pub color: u16,
+    /// the thickness in pixels
+    pub thickness: u16,
+    /// how the ends of the line, and of each dash, are drawn
+    pub cap: LineCap,
+    /// the lengths in pixels of the dashes and the gaps between them, in turn
+    pub dashes: &'a [u16],
+}
+
+impl<'a> LineStyle<'a> {
+    /// A solid line one pixel thick.
+    pub const fn new(color: u16) -> LineStyle<'a> {
+        LineStyle {
+            color,
+            thickness: 1,
+            cap: LineCap::Butt,
+            dashes: &[],
+        }
+    }
+
+    pub const fn with_thickness(self, thickness: u16) -> LineStyle<'a> {
+        LineStyle { thickness, ..self }
+    }
+
+    pub const fn with_cap(self, cap: LineCap) -> LineStyle<'a> {
+        LineStyle { cap, ..self }
+    }
+
+    /// Dashes the line, the lengths of the dashes and gaps in turn starting
+    /// with a dash, e.g. `&[6, 3]`.
+    pub const fn with_dashes(self, dashes: &'a [u16]) -> LineStyle<'a> {
+        LineStyle { dashes, ..self }
+    }
+}
+
+/// Where a dashed line is in its pattern, kept from one segment to the next.
+struct Dashes<'a> {
+    pattern: &'a [u16],
+    index: usize,
+    /// what's left of the current dash or gap, with 8 fractional bits
+    left: i32,
+}
+
+impl<'a> Dashes<'a> {
+    fn new(pattern: &'a [u16]) -> Dashes<'a> {
+        // A pattern without any length would never move on.
+        let pattern = if pattern.iter().all(|&length| length == 0) {
+            &[]
+        } else {
+            pattern
+        };
+        Dashes {
+            pattern,
+            index: 0,
+            left: pattern.first().map_or(0, |&length| (length as i32) << 8),
+        }
+    }
+
+    /// Calls `draw` with the start and end of each dash along `length`,
+    /// with 8 fractional bits.
+    fn walk(&mut self, length: i32, mut draw: impl FnMut(i32, i32)) {
+        if self.pattern.is_empty() {
+            return draw(0, length);
+        }
+        let mut position = 0;
+        while position < length {
+            let step = self.left.min(length - position);
+            if self.index.is_multiple_of(2) && step > 0 {
+                draw(position, position + step);
+            }
+            position += step;
+            self.left -= step;
+            if self.left == 0 {
+                self.index = (self.index + 1) % self.pattern.len();
+                self.left = (self.pattern[self.index] as i32) << 8;
+            }
+        }
+    }
+}
+
+/// Draws a line in a style, like `draw_line` when it is solid and one pixel
+/// thick.
+pub fn draw_styled_line<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    style: &LineStyle,
+) {
+    draw_polyline(canvas, &[(x0, y0), (x1, y1)], style);
+}
+
+/// Draws lines joining the points in turn in a style. Thick lines are joined
+/// round, and the dashes carry on around the corners.
+pub fn draw_polyline<C: Canvas + ?Sized>(canvas: &mut C, points: &[(i32, i32)], style: &LineStyle) {
+    if let [(x, y)] = points {
+        return stroke(canvas, (*x << 8, *y << 8), (*x << 8, *y << 8), style);
+    }
+    let mut stroker = Stroker::new(style);
+    for &(x, y) in points {
+        stroker.line_to(canvas, (x << 8, y << 8));
+    }
+}
+
+/// Draws lines joining points in turn in a style, a point at a time.
+pub(crate) struct Stroker<'s, 'a> {
+    style: &'s LineStyle<'a>,
+    dashes: Dashes<'a>,
+    /// the last point, with 8 fractional bits, and whether a line ends there
+    last: Option<((i32, i32), bool)>,
+}
+
+impl<'s, 'a> Stroker<'s, 'a> {
+    pub(crate) fn new(style: &'s LineStyle<'a>) -> Stroker<'s, 'a> {
+        Stroker {
+            style,
+            dashes: Dashes::new(style.dashes),
+            last: None,
+        }
+    }
+
+    /// Starts new lines, and the dashes over, from a point with 8 fractional bits.
+    pub(crate) fn move_to(&mut self, point: (i32, i32)) {
+        self.dashes = Dashes::new(self.style.dashes);
+        self.last = Some((point, false));
+    }
+
+    /// Draws a line from the last point to a point with 8 fractional bits,
+    /// or starts from it if there is none.
+    pub(crate) fn line_to<C: Canvas + ?Sized>(&mut self, canvas: &mut C, point: (i32, i32)) {
+        let Some((from, joined)) = self.last else {
+            return self.move_to(point);
+        };
+        let style = self.style;
+        let dashed = !self.dashes.pattern.is_empty();
+        if joined && style.thickness > 2 && style.cap != LineCap::Round && !dashed {
+            let r = (style.thickness as i32 - 1) / 2;
+            let round = |v: i32| (v + 128) >> 8;
+            fill_circle(canvas, round(from.0), round(from.1), r, style.color);
+        }
+
+        let (dx, dy) = ((point.0 - from.0) as i64, (point.1 - from.1) as i64);
+        let length = (dx * dx + dy * dy).isqrt() as i32;
+        let at = |position: i32| match length {
+            0 => from,
+            _ => (
+                from.0 + (dx * position as i64 / length as i64) as i32,
+                from.1 + (dy * position as i64 / length as i64) as i32,
+            ),
+        };
+        self.dashes.walk(length, |start, end| {
+            // One pixel dashes end a pixel short, to be as long as the pattern.
+            let end = if dashed && style.thickness <= 1 {
+                (end - 256).max(start)
+            } else {
+                end
+            };
+            stroke(canvas, at(start), at(end), style);
+        });
+        self.last = Some((point, true));
+    }
+}
+
+/// Draws a line between pixel centers with 8 fractional bits in a style.
+fn stroke<C: Canvas + ?Sized>(canvas: &mut C, a: (i32, i32), b: (i32, i32), style: &LineStyle) {
+    let color = style.color;
+    if style.thickness <= 1 {
+        let round = |v: i32| (v + 128) >> 8;
+        return draw_line(
+            canvas,
+            round(a.0),
+            round(a.1),
+            round(b.0),
+            round(b.1),
+            color,
+        );
+    }
+    let (dx, dy) = ((b.0 - a.0) as i64, (b.1 - a.1) as i64);
+    let length = (dx * dx + dy * dy).isqrt();
+    let half = style.thickness as i64 * 128;
+    if length == 0 {
+        match style.cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                let (x, y) = (a.0 - half as i32 + 128, a.1 - half as i32 + 128);
+                let t = style.thickness as i32;
+                fill_rect(canvas, (x + 127) >> 8, (y + 127) >> 8, t, t, color);
+            }
+            LineCap::Round => {
+                let r = (style.thickness as i32 - 1) / 2;
+                fill_circle(canvas, (a.0 + 128) >> 8, (a.1 + 128) >> 8, r, color);
+            }
+        }
+        return;
+    }
+
+    // Half the thickness across the line, and along it for square caps.
+    let (nx, ny) = ((-dy * half / length) as i32, (dx * half / length) as i32);
+    let (ex, ey) = match style.cap {
+        LineCap::Square => ((dx * half / length) as i32, (dy * half / length) as i32),
+        _ => (0, 0),
+    };
+    // The centers of the pixels are at a half.
+    let (ax, ay) = (a.0 + 128 - ex, a.1 + 128 - ey);
+    let (bx, by) = (b.0 + 128 + ex, b.1 + 128 + ey);
+    let corners = [
+        (ax + nx, ay + ny),
+        (bx + nx, by + ny),
+        (bx - nx, by - ny),
+        (ax - nx, ay - ny),
+    ];
+    fill_fixed_polygon(canvas, 4, |i| corners[i], color);
+    if style.cap == LineCap::Round {
+        let r = (style.thickness as i32 - 1) / 2;
+        for (x, y) in [a, b] {
+            fill_circle(canvas, (x + 128) >> 8, (y + 128) >> 8, r, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::Framebuffer;
+    use crate::testing::{assert_golden, buffer};
+
+    const WHITE: u16 = 0xffff;
+
+    #[test]
+    fn thick_lines_are_as_thick_as_their_style() {
+        let mut pixels = buffer(48, 24);
+        let mut frame = Framebuffer::new(&mut pixels, 48, 24);
+        for (i, thickness) in [1, 2, 3, 5].into_iter().enumerate() {
+            let style = LineStyle::new(WHITE).with_thickness(thickness);
+            let y = 2 + i as i32 * 6;
+            draw_styled_line(&mut frame, 2, y, 12, y, &style);
+            let x = 18 + i as i32 * 7;
+            draw_styled_line(&mut frame, x, 3, x + 4, 20, &style);
+        }
+        assert_golden("styled_line_thickness", &frame);
+    }
+
+    #[test]
+    fn thick_lines_end_in_their_caps() {
+        let mut pixels = buffer(24, 24);
+        let mut frame = Framebuffer::new(&mut pixels, 24, 24);
+        for (i, cap) in [LineCap::Butt, LineCap::Square, LineCap::Round]
+            .into_iter()
+            .enumerate()
+        {
+            let style = LineStyle::new(WHITE).with_thickness(5).with_cap(cap);
+            let y = 4 + i as i32 * 8;
+            draw_styled_line(&mut frame, 6, y, 17, y, &style);
+        }
+        assert_golden("styled_line_caps", &frame);
+    }
+
+    #[test]
+    fn dashes_carry_on_around_corners() {
+        let mut pixels = buffer(32, 24);
+        let mut frame = Framebuffer::new(&mut pixels, 32, 24);
+        let style = LineStyle::new(WHITE).with_dashes(&[4, 2]);
+        draw_styled_line(&mut frame, 1, 1, 30, 1, &style);
+        let style = LineStyle::new(WHITE).with_dashes(&[3, 1, 1, 1]);
+        draw_styled_line(&mut frame, 1, 4, 30, 10, &style);
+        let style = LineStyle::new(WHITE).with_thickness(3).with_dashes(&[5, 3]);
+        draw_polyline(&mut frame, &[(3, 14), (15, 14), (15, 21), (28, 21)], &style);
+        assert_golden("styled_line_dashes", &frame);
+    }
+
+    #[test]
+    fn a_solid_thin_styled_line_is_a_line() {
+        let mut expected = buffer(16, 16);
+        let mut frame = Framebuffer::new(&mut expected, 16, 16);
+        draw_line(&mut frame, 1, 2, 14, 11, WHITE);
+        let mut pixels = buffer(16, 16);
+        let mut frame = Framebuffer::new(&mut pixels, 16, 16);
+        draw_styled_line(&mut frame, 1, 2, 14, 11, &LineStyle::new(WHITE));
+        assert_eq!(pixels, expected);
+    }
+
+    #[test]
+    fn circles_have_their_radius_down_to_a_dot() {
+        let mut pixels = buffer(56, 32);
+        let mut frame = Framebuffer::new(&mut pixels, 56, 32);
+        for (i, r) in [0, 1, 3, 6].into_iter().enumerate() {
+            let cx = 7 + i as i32 * 14;
+            draw_circle(&mut frame, cx, 7, r, WHITE);
+            fill_circle(&mut frame, cx, 23, r, WHITE);
+        }
+        assert_golden("shapes_circles", &frame);
+    }
+
+    #[test]
+    fn ellipses_flatten_down_to_a_line() {
+        let mut pixels = buffer(64, 32);
+        let mut frame = Framebuffer::new(&mut pixels, 64, 32);
+        for (i, (rx, ry)) in [(0, 4), (5, 0), (7, 4), (3, 6)].into_iter().enumerate() {
+            let cx = 8 + i as i32 * 16;
+            draw_ellipse(&mut frame, cx, 7, rx, ry, WHITE);
+            fill_ellipse(&mut frame, cx, 23, rx, ry, WHITE);
+        }
+        assert_golden("shapes_ellipses", &frame);
+    }
+
+    #[test]
+    fn arcs_and_pies_turn_counterclockwise_between_their_angles() {
+        let mut pixels = buffer(56, 48);
+        let mut frame = Framebuffer::new(&mut pixels, 56, 48);
+        // A quarter, three quarters, across 0° and an empty radius.
+        let arcs = [(0, 90, 6), (45, 315, 6), (300, 60, 6), (0, 180, 0)];
+        for (i, (start, end, r)) in arcs.into_iter().enumerate() {
+            let cx = 7 + i as i32 * 14;
+            draw_arc(&mut frame, cx, 7, r, start, end, WHITE);
+            draw_pie(&mut frame, cx, 23, r, start, end, WHITE);
+            fill_pie(&mut frame, cx, 39, r, start, end, WHITE);
+        }
+        assert_golden("shapes_arcs_and_pies", &frame);
+    }
+
+    #[test]
+    fn rounded_rects_round_at_most_half_their_size() {
+        let mut pixels = buffer(48, 30);
+        let mut frame = Framebuffer::new(&mut pixels, 48, 30);
+        // Square corners, rounded, rounded to half the width and a radius
+        // past half the whole rectangle.
+        for (i, r) in [0, 2, 5, 20].into_iter().enumerate() {
+            let x = 1 + i as i32 * 12;
+            draw_rounded_rect(&mut frame, x, 1, 10, 12, r, WHITE);
+            fill_rounded_rect(&mut frame, x, 16, 10, 12, r, WHITE);
+        }
+        assert_golden("shapes_rounded_rects", &frame);
+    }
+
+    #[test]
+    fn triangles_and_polygons_are_filled_by_the_even_odd_rule() {
+        let mut pixels = buffer(48, 32);
+        let mut frame = Framebuffer::new(&mut pixels, 48, 32);
+        let triangle = [(1, 1), (12, 4), (4, 13)];
+        draw_triangle(&mut frame, triangle, WHITE);
+        fill_triangle(&mut frame, triangle.map(|(x, y)| (x, y + 16)), WHITE);
+        // A flat triangle fills nothing.
+        fill_triangle(&mut frame, [(14, 20), (20, 20), (26, 20)], WHITE);
+        let star = [(36, 1), (40, 13), (30, 5), (42, 5), (32, 13)];
+        draw_polygon(&mut frame, &star, WHITE);
+        fill_polygon(&mut frame, &star.map(|(x, y)| (x, y + 16)), WHITE);
+        assert_golden("shapes_polygons", &frame);
+    }
+}
@@ -1,4 +1,3 @@
-use core::mem;
 use core::ops::BitOr;
 
 use embedded_hal::spi::SpiBus;
@@ -11,7 +10,13 @@ use rp_pico::hal::spi::{
 };
 use rp_pico::{self as bsp, hal};
 
-use crate::font::Font;
+use crate::canvas::{Canvas, Rect, Scroll, Viewport};
+use crate::color::blend;
+use crate::font::{Font, Glyph};
+use crate::geometry::LinePoints;
+use crate::hershey::HersheyFont;
+use crate::shapes;
+use crate::text::{self, Span, TextCanvas, TextRotation};
 use bsp::pac;
 use cortex_m::delay::Delay;
 use embedded_hal::digital::OutputPin;
@@ -85,14 +90,6 @@ impl BitOr for ColorMode {
     }
 }
 
-fn abs(x: i16) -> i16 {
-    if x < 0 {
-        -x
-    } else {
-        x
-    }
-}
-
 /// OptionalOutputPin is used to implement some optional output pins.
 pub trait OptionalOutputPin {
     /// Set the output pin to the specified value.
@@ -148,9 +145,15 @@ where
     height: u16,
     /// screen buffer size
     buf_size: u16,
+    /// the origin and clip of drawing
+    viewport: Viewport,
+    /// the pixels on their way to the display, shared by every drawing so
+    /// none of them puts its own on the stack
+    buffer: [u8; BUFFER_SIZE],
 }
 
-const BUFFER_SIZE: u16 = 4096;
+/// The bytes of the buffer pixels are written through, 256 pixels.
+const BUFFER_SIZE: usize = 512;
 
 #[allow(dead_code)]
 impl<
@@ -187,6 +190,8 @@ impl<
             height: 240,
             width: 240,
             buf_size: 57600, // 240 x 240
+            viewport: Viewport::new(240, 240),
+            buffer: [0; BUFFER_SIZE],
         };
 
         i.hard_reset(delay);
@@ -244,6 +249,24 @@ impl<
         self.dc_pin.set_low().unwrap();
     }
 
+    /// Write pixels to the window, the color of each from its index, through
+    /// the buffer a chunk at a time.
+    fn send_pixels(&mut self, count: usize, mut color: impl FnMut(usize) -> u16) {
+        let mut start = 0;
+        while start < count {
+            let len = (count - start).min(BUFFER_SIZE / 2);
+            for (i, bytes) in self.buffer[..len * 2].chunks_exact_mut(2).enumerate() {
+                bytes.copy_from_slice(&color(start + i).to_be_bytes());
+            }
+            self.cs_pin.set(false);
+            self.dc_pin.set_high().unwrap();
+            self.spi.write(&self.buffer[..len * 2]).unwrap();
+            self.cs_pin.set(true);
+            self.dc_pin.set_low().unwrap();
+            start += len;
+        }
+    }
+
     /// Reset by sending a software reset command.
     /// It will be called automatically when created.
     /// It is usually called after `hard_reset`.
@@ -291,9 +314,29 @@ impl<
         self.send_data(&[rotation as u8]);
     }
 
+    /// Returns the width of the display in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the height of the display in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the origin and clip rectangle drawing goes through.
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// Returns the viewport to push and pop origins and clip rectangles.
+    pub fn viewport_mut(&mut self) -> &mut Viewport {
+        &mut self.viewport
+    }
+
     /// Select columns.
     fn set_columns(&mut self, start: u16, end: u16) {
-        assert!(start <= end && end <= self.width);
+        assert!(start <= end && end < self.width);
         self.send_command(Command::Caset);
         self.send_data(&[
             (start >> 8) as u8,
@@ -305,7 +348,7 @@ impl<
 
     /// Select rows.
     fn set_rows(&mut self, start: u16, end: u16) {
-        assert!(start <= end && end <= self.height);
+        assert!(start <= end && end < self.height);
         self.send_command(Command::Raset);
         self.send_data(&[
             (start >> 8) as u8,
@@ -322,20 +365,29 @@ impl<
         self.send_command(Command::Ramwr);
     }
 
+    /// Select the window of a rectangle inside the screen.
+    fn set_window_rect(&mut self, rect: Rect) {
+        let (x, y) = (rect.x as u16, rect.y as u16);
+        let (right, bottom) = (rect.right() as u16, rect.bottom() as u16);
+        self.set_window(x, y, right - 1, bottom - 1);
+    }
+
     /// Draw a vertical line.
-    pub fn draw_vertical_line(&mut self, x: u16, y: u16, length: u16, color: u16) {
+    pub fn draw_vertical_line(&mut self, x: i32, y: i32, length: u16, color: u16) {
         self.draw_solid_rect(x, y, 1, length, color);
     }
 
     /// Draw a horizontal line.
-    pub fn draw_horizontal_line(&mut self, x: u16, y: u16, length: u16, color: u16) {
+    pub fn draw_horizontal_line(&mut self, x: i32, y: i32, length: u16, color: u16) {
         self.draw_solid_rect(x, y, length, 1, color);
     }
 
-    /// Draw a single pixel.**Not recommended**.
-    pub fn pixel(&mut self, x: u16, y: u16, color: u16) {
-        self.set_window(x, y, x, y);
-        self.send_data(&[(color >> 8) as u8, (color & 0xff) as u8]);
+    /// Draw a single pixel, if it's inside the clip.**Not recommended**.
+    pub fn pixel(&mut self, x: i32, y: i32, color: u16) {
+        if let Some((x, y)) = self.viewport.clip_point(x, y) {
+            self.set_window(x, y, x, y);
+            self.send_data(&[(color >> 8) as u8, (color & 0xff) as u8]);
+        }
     }
 
     /// Draw the color buffer into an area.
@@ -344,109 +396,107 @@ impl<
     pub fn push_buffer(&mut self, bitmap: &[u16]) {
         assert_eq!(bitmap.len(), self.width as usize * self.height as usize);
         self.set_window(0, 0, self.width - 1, self.height - 1);
-        let chunks = bitmap.len() as u16 / BUFFER_SIZE;
-        let rest = bitmap.len() as u16 % BUFFER_SIZE;
-        let mut buf: [u8; (BUFFER_SIZE * 2) as usize] = [0x0; (BUFFER_SIZE * 2) as usize];
-
-        let mut index = 0;
-        for _ in 0..chunks {
-            for i in 0..BUFFER_SIZE {
-                buf[i as usize * 2] = (bitmap[index] >> 8) as u8;
-                buf[i as usize * 2 + 1] = (bitmap[index] & 0xff) as u8;
-                index += 1;
-            }
-            self.send_data(&buf);
-        }
-        if rest > 0 {
-            for i in 0..rest {
-                buf[i as usize * 2] = (bitmap[index] >> 8) as u8;
-                buf[i as usize * 2 + 1] = (bitmap[index] & 0xff) as u8;
-                index += 1;
-            }
-            self.send_data(&buf);
-        }
+        self.send_pixels(bitmap.len(), |i| bitmap[i]);
     }
 
-    /// Draw the raw color buffer into an area.
+    /// Draw the raw color buffer into an area, clipped.
     ///
     /// The `buf` is a color array of `u8` which encoded with big-endian.
-    pub fn draw_color_buf_raw(&mut self, buffer: &[u8], x: u16, y: u16, width: u16, height: u16) {
+    pub fn draw_color_buf_raw(&mut self, buffer: &[u8], x: i32, y: i32, width: u16, height: u16) {
         // TODO:
         // Remove width and height
         assert_eq!(buffer.len(), width as usize * height as usize * 2);
-        self.set_window(x, y, x + width - 1, y + height - 1);
-        self.send_data(buffer);
+        let Some(rect) = self.viewport.clip_rect(x, y, width as i32, height as i32) else {
+            return;
+        };
+        self.set_window_rect(rect);
+        let (origin_x, origin_y) = self.viewport.origin();
+        let (left, top) = (
+            (rect.x - origin_x - x) as usize,
+            (rect.y - origin_y - y) as usize,
+        );
+        if rect.width == width as i32 {
+            let start = top * width as usize * 2;
+            self.send_data(&buffer[start..start + rect.height as usize * width as usize * 2]);
+            return;
+        }
+        for row in top..top + rect.height as usize {
+            let start = (row * width as usize + left) * 2;
+            self.send_data(&buffer[start..start + rect.width as usize * 2]);
+        }
     }
 
-    /// Draw a solid rectangle.
-    pub fn draw_solid_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16) {
-        self.set_window(x, y, x + width - 1, y + height - 1);
-        let pixel: [u8; 2] = [(color >> 8) as u8, (color & 0xff) as u8];
-        let chunks = (width * height) / BUFFER_SIZE;
-        let rest = (width * height) % BUFFER_SIZE;
-
-        let buf: &mut [u8] = &mut [0u8; BUFFER_SIZE as usize * 2];
-        for i in 0..BUFFER_SIZE {
-            buf[i as usize * 2] = pixel[0];
-            buf[i as usize * 2 + 1] = pixel[1];
+    /// Draw a solid rectangle, clipped.
+    pub fn draw_solid_rect(&mut self, x: i32, y: i32, width: u16, height: u16, color: u16) {
+        if let Some(rect) = self.viewport.clip_rect(x, y, width as i32, height as i32) {
+            self.fill_area(rect, color);
         }
+    }
 
-        for _ in 0..chunks {
-            self.send_data(buf);
-        }
-        if rest > 0 {
-            self.send_data(&buf[0..2 * rest as usize]);
-        }
+    /// Fill a rectangle inside the screen.
+    fn fill_area(&mut self, rect: Rect, color: u16) {
+        self.set_window_rect(rect);
+        self.send_pixels(rect.width as usize * rect.height as usize, |_| color);
     }
 
-    /// Fill the screen with a color.
+    /// Fill the screen, or the clip rectangle if one is pushed, with a color.
     pub fn fill(&mut self, color: u16) {
-        self.draw_solid_rect(0, 0, self.width, self.height, color);
+        let clip = self.viewport.clip();
+        if !clip.is_empty() {
+            self.fill_area(clip, color);
+        }
     }
 
     /// Draw a hollow rectangle.
-    pub fn draw_hollow_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16) {
+    pub fn draw_hollow_rect(&mut self, x: i32, y: i32, width: u16, height: u16, color: u16) {
         self.draw_horizontal_line(x, y, width, color);
-        self.draw_horizontal_line(x, y + height - 1, width, color);
+        self.draw_horizontal_line(x, y + height as i32 - 1, width, color);
         self.draw_vertical_line(x, y, height, color);
-        self.draw_vertical_line(x + width - 1, y, height, color);
+        self.draw_vertical_line(x + width as i32 - 1, y, height, color);
     }
 
-    /// Draw a line from (x0, y0) to (x1, y1).
-    pub fn line(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) {
-        let mut x0 = x0;
-        let mut y0 = y0;
-        let mut x1 = x1;
-        let mut y1 = y1;
+    /// Draw a white line from (x0, y0) to (x1, y1).
+    pub fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32) {
+        self.draw_line(x0, y0, x1, y1, 0xffff);
+    }
 
-        let steep = abs(y1 as i16 - y0 as i16) > abs(x1 as i16 - x0 as i16);
-        if steep {
-            mem::swap(&mut x0, &mut y0);
-            mem::swap(&mut x1, &mut y1);
-        }
-        if x0 > x1 {
-            mem::swap(&mut x0, &mut x1);
-            mem::swap(&mut y0, &mut y1);
+    /// Draw a line from (x0, y0) to (x1, y1) in a color, clipped.
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u16) {
+        shapes::draw_line(self, x0, y0, x1, y1, color);
+    }
+
+    /// Draw a line with a square pen `thickness` pixels wide, clipped.
+    fn draw_pen_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, thickness: u16, color: u16) {
+        if thickness <= 1 {
+            return self.draw_line(x0, y0, x1, y1, color);
         }
-        let dx: i16 = x1 as i16 - x0 as i16;
-        let dy: i16 = y1 as i16 - y0 as i16;
-        let mut derror: i16 = (dx / 2) as i16;
-        let ystep: i16 = if y0 < y1 { 1 } else { -1 };
-        let mut y: i16 = y0 as i16;
-        for x in x0..=x1 {
-            if steep {
-                self.pixel(y as u16, x as u16, 0xffff);
-            } else {
-                self.pixel(x as u16, y as u16, 0xffff);
-            }
-            derror -= dy;
-            if derror < 0 {
-                y += ystep;
-                derror += dx;
-            }
+        let half = thickness as i32 / 2;
+        for (x, y) in LinePoints::new(x0, y0, x1, y1) {
+            self.draw_solid_rect(x - half, y - half, thickness, thickness, color);
         }
     }
 
+    /// Draw text with a vector font, with capitals `size` pixels high, turned
+    /// `angle` degrees counterclockwise and strokes `thickness` pixels wide.
+    /// `x` and `y` are the pen position on the baseline, which may be off screen.
+    /// Returns the pen position after the text.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_vector_text(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &HersheyFont,
+        size: u16,
+        angle: i32,
+        thickness: u16,
+        color: u16,
+    ) -> (i32, i32) {
+        font.strokes(text, x, y, size, angle, |x0, y0, x1, y1| {
+            self.draw_pen_line(x0, y0, x1, y1, thickness, color)
+        })
+    }
+
     /// Set Vertical Scrolling Definition.
     ///
     /// To scroll a 135x240 display these values should be 40, 240, 40.
@@ -486,73 +536,188 @@ impl<
     /// `x` and `y` are the top left corner of the text.
     /// Returns the bottom right corner of the text box.
     ///
+    /// Each character fills a box from the pen position to the next one, as high
+    /// as a line, with the glyph placed at its offsets and kerning applied.
+    /// Anti-aliased glyphs are blended over `background_color`.
+    ///
     /// When meeting a newline character or reach the end of screen, the next line will be drawn.
     ///
     /// When the distance to the bottom of the screen is less than the font height, it will stop drawing.
     pub fn draw_text(
         &mut self,
-        x: u16,
-        y: u16,
+        x: i32,
+        y: i32,
         text: &str,
         font: &dyn Font,
         font_color: u16,
         background_color: u16,
-    ) -> (u16, u16) {
-        let start_x = x;
-        let mut end_x = x;
-        let height = font.get_height() as u16;
-        let mut x = x;
-        let mut y = y;
-
-        let render_buffer = &mut [0u8; BUFFER_SIZE as usize * 2];
-
-        for c in text.chars() {
-            if c == '\n' {
-                if x > end_x {
-                    end_x = x;
-                }
-                x = start_x;
-                y += height;
-                if y + height > self.height as u16 {
-                    return (end_x, y);
-                } else {
-                    continue;
-                }
-            }
-            if let Some((buf, w)) = font.get_char(c) {
-                if x + w as u16 > self.width {
-                    if x > end_x {
-                        end_x = x;
-                    }
-                    x = start_x;
-                    y += height;
-                    if y + height > self.height as u16 {
-                        return (end_x, y);
-                    }
-                }
-                self.set_window(x, y, x + w as u16 - 1, y + height - 1);
+    ) -> (i32, i32) {
+        self.draw_rich_text(x, y, &[Span::new(text, font, font_color)], background_color)
+    }
 
-                let mut buf_index: usize = 0;
-                for i in (0 as usize)..(w as usize * height as usize) {
-                    if buf_index == (BUFFER_SIZE * 2) as usize {
-                        buf_index = 0;
-                        self.send_data(render_buffer);
-                    }
-                    if buf[i >> 3] & (0x80 >> (i & 7)) != 0 {
-                        render_buffer[buf_index] = (font_color >> 8) as u8;
-                        render_buffer[buf_index + 1] = (font_color & 0xff) as u8;
-                    } else {
-                        render_buffer[buf_index] = (background_color >> 8) as u8;
-                        render_buffer[buf_index + 1] = (background_color & 0xff) as u8;
+    /// Draw spans of text one after another, like `draw_text`.
+    ///
+    /// Lines are as high as the tallest font of the spans, with the glyphs
+    /// at the top.
+    pub fn draw_rich_text(
+        &mut self,
+        x: i32,
+        y: i32,
+        spans: &[Span],
+        background_color: u16,
+    ) -> (i32, i32) {
+        text::draw_spans(self, x, y, spans, Some(background_color))
+    }
+
+    /// Draw text scaled `scale` times and turned, one window per character.
+    /// `x` and `y` are the top left corner of the turned text box.
+    /// Returns the bottom right corner of the text box.
+    ///
+    /// Newline characters start a new line under the previous one, in the
+    /// direction of the text. Characters are clipped like everything else.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_text_transformed(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &dyn Font,
+        scale: u8,
+        rotation: TextRotation,
+        font_color: u16,
+        background_color: u16,
+    ) -> (i32, i32) {
+        text::transformed_glyphs(x, y, text, font, scale, rotation, |glyph| {
+            let cell = glyph.rect;
+            let Some(rect) = self
+                .viewport
+                .clip_rect(cell.x, cell.y, cell.width, cell.height)
+            else {
+                return;
+            };
+            self.set_window_rect(rect);
+            let (origin_x, origin_y) = self.viewport.origin();
+            let (left, top) = (rect.x - origin_x - cell.x, rect.y - origin_y - cell.y);
+
+            let count = rect.width as usize * rect.height as usize;
+            self.send_pixels(count, |index| {
+                let index = index as i32;
+                let (i, j) = (left + index % rect.width, top + index / rect.width);
+                blend(font_color, background_color, glyph.coverage(i, j))
+            });
+        })
+    }
+}
+
+impl<
+        K: OptionalOutputPin,
+        L: PinId,
+        M: OptionalOutputPin,
+        N: OptionalOutputPin,
+        S: SpiDevice,
+        P: ValidSpiPinout<S>,
+    > TextCanvas for ST7789Display<'_, K, L, M, N, S, P>
+{
+    fn size(&self) -> (u16, u16) {
+        self.viewport.local_size()
+    }
+
+    /// Without a background color the pixels at least half covered are drawn
+    /// one by one, as the display can't be read back to blend over.
+    fn draw_glyph(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: &Glyph,
+        height: u16,
+        color: u16,
+        background: Option<u16>,
+    ) {
+        let w = glyph.advance as i32;
+        let Some(rect) = self.viewport.clip_rect(x, y, w, height as i32) else {
+            return;
+        };
+        let Some(background) = background else {
+            for py in 0..height as i32 {
+                for px in 0..w {
+                    if glyph.cell_pixel(px, py) {
+                        self.pixel(x + px, y + py, color);
                     }
-                    buf_index += 2;
                 }
-                if buf_index != 0 {
-                    self.send_data(&render_buffer[0..buf_index]);
-                }
-                x += w as u16;
             }
+            return;
+        };
+
+        self.set_window_rect(rect);
+        let (origin_x, origin_y) = self.viewport.origin();
+        let (left, top) = (rect.x - origin_x - x, rect.y - origin_y - y);
+        let count = rect.width as usize * rect.height as usize;
+        self.send_pixels(count, |index| {
+            let index = index as i32;
+            let (px, py) = (left + index % rect.width, top + index / rect.width);
+            blend(color, background, glyph.cell_coverage(px, py))
+        });
+    }
+}
+
+impl<
+        K: OptionalOutputPin,
+        L: PinId,
+        M: OptionalOutputPin,
+        N: OptionalOutputPin,
+        S: SpiDevice,
+        P: ValidSpiPinout<S>,
+    > Canvas for ST7789Display<'_, K, L, M, N, S, P>
+{
+    fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    fn origin(&self) -> (i32, i32) {
+        self.viewport.origin()
+    }
+
+    fn clip(&self) -> Rect {
+        self.viewport.clip()
+    }
+
+    fn fill_span(&mut self, x: u16, y: u16, width: u16, color: u16) {
+        self.fill_rect(x, y, width, 1, color);
+    }
+    fn draw_span(&mut self, x: u16, y: u16, colors: &[u16]) {
+        self.draw_block(x, y, colors.len() as u16, colors);
+    }
+
+    /// Draws the block through one window.
+    fn draw_block(&mut self, x: u16, y: u16, width: u16, colors: &[u16]) {
+        if colors.is_empty() {
+            return;
         }
-        (end_x, y + height as u16)
+        let height = (colors.len() / width as usize) as u16;
+        self.set_window(x, y, x + width - 1, y + height - 1);
+        self.send_pixels(colors.len(), |i| colors[i]);
+    }
+
+    fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, color: u16) {
+        let rect = Rect::new(x as i32, y as i32, width as i32, height as i32);
+        self.fill_area(rect, color);
+    }
+}
+
+impl<
+        K: OptionalOutputPin,
+        L: PinId,
+        M: OptionalOutputPin,
+        N: OptionalOutputPin,
+        S: SpiDevice,
+        P: ValidSpiPinout<S>,
+    > Scroll for ST7789Display<'_, K, L, M, N, S, P>
+{
+    fn set_scroll_area(&mut self, top: u16, lines: u16, bottom: u16) {
+        self.vscrdef(top, lines, bottom);
+    }
+
+    fn set_scroll_start(&mut self, line: u16) {
+        self.vscsad(line);
     }
 }
@@ -0,0 +1,608 @@
+use core::fmt;
+
+use crate::canvas::{Canvas, Scroll};
+use crate::font::Font;
+use crate::shapes;
+use crate::text::{self, Span, TextCanvas};
+
+/// Number of lines in the ST7789 frame memory.
+const FRAME_MEMORY_LINES: u16 = 320;
+
+/// Width of a tab stop in columns.
+const TAB_WIDTH: u16 = 8;
+
+/// Maximum number of numeric parameters kept for a CSI sequence.
+const MAX_PARAMS: usize = 4;
+
+/// The 16 ANSI colours in RGB565, normal colours first, then bright ones.
+pub const ANSI_PALETTE: [u16; 16] = [
+    0x0000, 0xA800, 0x0540, 0xAAA0, 0x0015, 0xA815, 0x0555, 0xAD55, // normal
+    0x52AA, 0xFAAA, 0x57EA, 0xFFEA, 0x52BF, 0xFABF, 0x57FF, 0xFFFF, // bright
+];
+
+/// State of the escape sequence parser.
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    /// Printing characters.
+    Ground,
+    /// Got `ESC`, waiting for `[`.
+    Escape,
+    /// Inside a `ESC [` control sequence.
+    Csi,
+}
+
+/// A text terminal drawing directly on the display.
+///
+/// New lines scroll the screen by moving the vertical scroll start address,
+/// so only the freshly exposed line is redrawn. Hardware scrolling follows the
+/// frame memory rows, so the terminal is meant to be used in `Portrait` rotation.
+///
+/// `\n` moves to the start of the next line, `\r`, `\t` and backspace move the
+/// cursor, and the following `ESC [` sequences are understood:
+///
+/// - `A`, `B`, `C`, `D`: cursor up, down, forward, back
+/// - `H`, `f`: cursor position, `G`: cursor column
+/// - `J`, `K`: erase in display, erase in line
+/// - `s`, `u`: save and restore the cursor
+/// - `m`: reset, bold, reverse and the 8/16 foreground and background colours
+pub struct Terminal<'d, D: Canvas + TextCanvas + Scroll> {
+    /// the display to draw on
+    display: &'d mut D,
+    /// the font of the text
+    font: &'d dyn Font,
+    /// the width of a character cell in pixels
+    cell_width: u16,
+    /// the height of a character cell in pixels
+    cell_height: u16,
+    /// the number of columns
+    columns: u16,
+    /// the number of rows
+    rows: u16,
+    /// the cursor column
+    column: u16,
+    /// the cursor row
+    row: u16,
+    /// the saved cursor position
+    saved: (u16, u16),
+    /// the frame memory row currently shown at the top of the screen
+    top: u16,
+    /// the default font color
+    default_fg: u16,
+    /// the default background color
+    default_bg: u16,
+    /// the palette index of the font color, `None` for the default
+    fg: Option<u8>,
+    /// the palette index of the background color, `None` for the default
+    bg: Option<u8>,
+    /// bold text uses the bright palette colors
+    bold: bool,
+    /// swap font and background colors
+    reverse: bool,
+    /// the escape sequence parser state
+    state: State,
+    /// the parameters of the current control sequence
+    params: [u16; MAX_PARAMS],
+    /// the number of parameters of the current control sequence
+    n_params: usize,
+}
+
+impl<'d, D: Canvas + TextCanvas + Scroll> Terminal<'d, D> {
+    /// Creates a terminal covering the whole display and clears it.
+    ///
+    /// The font is expected to be monospaced, the width of the space
+    /// character is used as the cell width.
+    pub fn new(
+        display: &'d mut D,
+        font: &'d dyn Font,
+        font_color: u16,
+        background_color: u16,
+    ) -> Self {
+        let cell_width = (font.get_width(' ').unwrap_or(8) as u16).max(1);
+        let cell_height = (font.get_height() as u16).max(1);
+        let (width, height) = Canvas::size(display);
+        // At least a cell, cut off, on a canvas smaller than one.
+        let columns = (width / cell_width).max(1);
+        let rows = (height / cell_height).max(1);
+
+        // Only scroll whole rows, the remaining lines stay in the bottom fixed area.
+        let scroll_lines = rows.saturating_mul(cell_height).min(FRAME_MEMORY_LINES);
+        display.set_scroll_area(0, scroll_lines, FRAME_MEMORY_LINES - scroll_lines);
+
+        let mut terminal = Self {
+            display,
+            font,
+            cell_width,
+            cell_height,
+            columns,
+            rows,
+            column: 0,
+            row: 0,
+            saved: (0, 0),
+            top: 0,
+            default_fg: font_color,
+            default_bg: background_color,
+            fg: None,
+            bg: None,
+            bold: false,
+            reverse: false,
+            state: State::Ground,
+            params: [0; MAX_PARAMS],
+            n_params: 0,
+        };
+        terminal.clear();
+        terminal
+    }
+
+    /// Returns the number of columns.
+    pub fn columns(&self) -> u16 {
+        self.columns
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    /// Returns the cursor position as `(column, row)`.
+    pub fn cursor(&self) -> (u16, u16) {
+        (self.column, self.row)
+    }
+
+    /// Moves the cursor, clamping it to the screen.
+    pub fn set_cursor(&mut self, column: u16, row: u16) {
+        self.column = column.min(self.columns - 1);
+        self.row = row.min(self.rows - 1);
+    }
+
+    /// Sets the default colors used after a reset (`ESC [ 0 m`).
+    pub fn set_default_colors(&mut self, font_color: u16, background_color: u16) {
+        self.default_fg = font_color;
+        self.default_bg = background_color;
+    }
+
+    /// Clears the screen, resets the scrolling and moves the cursor home.
+    pub fn clear(&mut self) {
+        self.top = 0;
+        self.display.set_scroll_start(0);
+        let bg = self.background_color();
+        let (width, height) = Canvas::size(self.display);
+        shapes::fill_rect(self.display, 0, 0, width as i32, height as i32, bg);
+        self.column = 0;
+        self.row = 0;
+    }
+
+    /// Writes a single character, interpreting control characters and escape sequences.
+    pub fn put_char(&mut self, c: char) {
+        match self.state {
+            State::Ground => self.put_ground(c),
+            State::Escape => {
+                if c == '[' {
+                    self.params = [0; MAX_PARAMS];
+                    self.n_params = 0;
+                    self.state = State::Csi;
+                } else {
+                    self.state = State::Ground;
+                }
+            }
+            State::Csi => self.put_csi(c),
+        }
+    }
+
+    fn put_ground(&mut self, c: char) {
+        match c {
+            '\n' => self.new_line(),
+            '\r' => self.column = 0,
+            '\t' => {
+                let next = (self.column / TAB_WIDTH + 1) * TAB_WIDTH;
+                self.column = next.min(self.columns - 1);
+            }
+            '\x08' => self.column = self.column.saturating_sub(1),
+            '\x1b' => self.state = State::Escape,
+            c if c.is_control() => {}
+            c => {
+                if self.column >= self.columns {
+                    self.new_line();
+                }
+                self.draw_char(c);
+                self.column += 1;
+            }
+        }
+    }
+
+    fn put_csi(&mut self, c: char) {
+        if let Some(digit) = c.to_digit(10) {
+            if self.n_params == 0 {
+                self.n_params = 1;
+            }
+            let param = &mut self.params[self.n_params - 1];
+            *param = param.saturating_mul(10).saturating_add(digit as u16);
+            return;
+        }
+        match c {
+            ';' => {
+                if self.n_params == 0 {
+                    self.n_params = 1;
+                }
+                if self.n_params < MAX_PARAMS {
+                    self.n_params += 1;
+                }
+                return;
+            }
+            // Private mode markers and intermediate bytes are ignored.
+            '\x20'..='\x3f' => return,
+            _ => {}
+        }
+
+        self.state = State::Ground;
+        let count = self.param(0, 1);
+        match c {
+            'A' => self.row = self.row.saturating_sub(count),
+            'B' => self.row = self.row.saturating_add(count).min(self.rows - 1),
+            'C' => self.column = self.column.saturating_add(count).min(self.columns - 1),
+            'D' => self.column = self.column.min(self.columns - 1).saturating_sub(count),
+            'G' => self.set_cursor(count - 1, self.row),
+            'H' | 'f' => self.set_cursor(self.param(1, 1) - 1, count - 1),
+            'J' => self.erase_in_display(self.param(0, 0)),
+            'K' => self.erase_in_line(self.param(0, 0)),
+            's' => self.saved = (self.column, self.row),
+            'u' => (self.column, self.row) = self.saved,
+            'm' => self.select_graphic_rendition(),
+            _ => {}
+        }
+    }
+
+    /// Returns the `index`th parameter, or `default` when it is missing or zero.
+    fn param(&self, index: usize, default: u16) -> u16 {
+        if index < self.n_params && self.params[index] != 0 {
+            self.params[index]
+        } else {
+            default
+        }
+    }
+
+    fn select_graphic_rendition(&mut self) {
+        let n_params = self.n_params.max(1);
+        for i in 0..n_params {
+            match self.params[i] {
+                0 => {
+                    self.fg = None;
+                    self.bg = None;
+                    self.bold = false;
+                    self.reverse = false;
+                }
+                1 => self.bold = true,
+                7 => self.reverse = true,
+                22 => self.bold = false,
+                27 => self.reverse = false,
+                p @ 30..=37 => self.fg = Some((p - 30) as u8),
+                39 => self.fg = None,
+                p @ 40..=47 => self.bg = Some((p - 40) as u8),
+                49 => self.bg = None,
+                p @ 90..=97 => self.fg = Some((p - 90 + 8) as u8),
+                p @ 100..=107 => self.bg = Some((p - 100 + 8) as u8),
+                _ => {}
+            }
+        }
+    }
+
+    fn font_color(&self) -> u16 {
+        match self.fg {
+            Some(i) if self.bold && i < 8 => ANSI_PALETTE[i as usize + 8],
+            Some(i) => ANSI_PALETTE[i as usize],
+            None => self.default_fg,
+        }
+    }
+
+    fn background_color(&self) -> u16 {
+        match self.bg {
+            Some(i) => ANSI_PALETTE[i as usize],
+            None => self.default_bg,
+        }
+    }
+
+    /// Returns the font and background colors with reverse video applied.
+    fn colors(&self) -> (u16, u16) {
+        if self.reverse {
+            (self.background_color(), self.font_color())
+        } else {
+            (self.font_color(), self.background_color())
+        }
+    }
+
+    /// Returns the frame memory line of the top of a row.
+    fn row_to_line(&self, row: u16) -> u16 {
+        ((self.top + row) % self.rows) * self.cell_height
+    }
+
+    fn draw_char(&mut self, c: char) {
+        let x = (self.column * self.cell_width) as i32;
+        let y = self.row_to_line(self.row) as i32;
+        let (fg, bg) = self.colors();
+        if self.font.get_char(c).is_some() {
+            let mut buf = [0u8; 4];
+            let span = Span::new(c.encode_utf8(&mut buf), self.font, fg);
+            text::draw_spans(self.display, x, y, &[span], Some(bg));
+        } else {
+            let (width, height) = (self.cell_width as i32, self.cell_height as i32);
+            shapes::fill_rect(self.display, x, y, width, height, bg);
+        }
+    }
+
+    fn new_line(&mut self) {
+        self.column = 0;
+        if self.row + 1 < self.rows {
+            self.row += 1;
+        } else {
+            self.scroll();
+        }
+    }
+
+    /// Scrolls up by one row and clears the exposed bottom row.
+    fn scroll(&mut self) {
+        self.top = (self.top + 1) % self.rows;
+        self.erase_columns(self.rows - 1, 0, self.columns);
+        self.display.set_scroll_start(self.top * self.cell_height);
+    }
+
+    /// Clears the cells `start..end` of a row.
+    fn erase_columns(&mut self, row: u16, start: u16, end: u16) {
+        let end = end.min(self.columns);
+        if start >= end {
+            return;
+        }
+        let y = self.row_to_line(row);
+        let bg = self.background_color();
+        shapes::fill_rect(
+            self.display,
+            (start * self.cell_width) as i32,
+            y as i32,
+            ((end - start) * self.cell_width) as i32,
+            self.cell_height as i32,
+            bg,
+        );
+    }
+
+    fn erase_in_line(&mut self, mode: u16) {
+        match mode {
+            0 => self.erase_columns(self.row, self.column, self.columns),
+            1 => self.erase_columns(self.row, 0, self.column + 1),
+            _ => self.erase_columns(self.row, 0, self.columns),
+        }
+    }
+
+    fn erase_in_display(&mut self, mode: u16) {
+        match mode {
+            0 => {
+                self.erase_in_line(0);
+                for row in self.row + 1..self.rows {
+                    self.erase_columns(row, 0, self.columns);
+                }
+            }
+            1 => {
+                for row in 0..self.row {
+                    self.erase_columns(row, 0, self.columns);
+                }
+                self.erase_in_line(1);
+            }
+            _ => {
+                for row in 0..self.rows {
+                    self.erase_columns(row, 0, self.columns);
+                }
+            }
+        }
+    }
+}
+
+impl<D: Canvas + TextCanvas + Scroll> fmt::Write for Terminal<'_, D> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            self.put_char(c);
+        }
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.put_char(c);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::String;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::canvas::Rect;
+    use crate::font::Glyph;
+    use crate::framebuffer::Framebuffer;
+    use crate::testing::{buffer, read_cell, read_line, CODE_FONT};
+
+    const COLUMNS: u16 = 10;
+    const ROWS: u16 = 4;
+    const FG: u16 = 0xffff;
+    const BG: u16 = 0x0001;
+
+    /// A framebuffer as the frame memory of a display scrolled in hardware.
+    struct Screen<'a> {
+        frame: Framebuffer<'a>,
+        start: u16,
+    }
+
+    impl Canvas for Screen<'_> {
+        fn size(&self) -> (u16, u16) {
+            Canvas::size(&self.frame)
+        }
+
+        fn origin(&self) -> (i32, i32) {
+            self.frame.origin()
+        }
+
+        fn clip(&self) -> Rect {
+            self.frame.clip()
+        }
+
+        fn fill_span(&mut self, x: u16, y: u16, width: u16, color: u16) {
+            self.frame.fill_span(x, y, width, color);
+        }
+
+        fn draw_span(&mut self, x: u16, y: u16, colors: &[u16]) {
+            self.frame.draw_span(x, y, colors);
+        }
+    }
+
+    impl TextCanvas for Screen<'_> {
+        fn size(&self) -> (u16, u16) {
+            TextCanvas::size(&self.frame)
+        }
+
+        fn draw_glyph(
+            &mut self,
+            x: i32,
+            y: i32,
+            glyph: &Glyph,
+            height: u16,
+            color: u16,
+            background: Option<u16>,
+        ) {
+            self.frame
+                .draw_glyph(x, y, glyph, height, color, background);
+        }
+    }
+
+    impl Scroll for Screen<'_> {
+        fn set_scroll_area(&mut self, top: u16, lines: u16, _bottom: u16) {
+            assert_eq!((top, lines), (0, ROWS * 2));
+        }
+
+        fn set_scroll_start(&mut self, line: u16) {
+            self.start = line;
+        }
+    }
+
+    /// Returns the rows shown on the screen from the top, without the spaces
+    /// after the text.
+    fn rows(screen: &Screen) -> Vec<String> {
+        (0..ROWS)
+            .map(|row| {
+                let y = (screen.start + row * 2) % (ROWS * 2);
+                let line = read_line(&screen.frame, 0, y as i32, COLUMNS);
+                line.trim_end().into()
+            })
+            .collect()
+    }
+
+    /// Returns the character shown in a cell and its colors.
+    fn cell(screen: &Screen, column: u16, row: u16) -> (char, u16, u16) {
+        let y = (screen.start + row * 2) % (ROWS * 2);
+        read_cell(&screen.frame, column as i32 * 8, y as i32)
+    }
+
+    /// Writes `input` to a terminal on a blank screen, then checks the screen
+    /// and where the cursor ended.
+    fn write(input: &str, check: impl FnOnce(&Screen, (u16, u16))) {
+        let mut buffer = buffer(COLUMNS * 8, ROWS * 2);
+        let mut screen = Screen {
+            frame: Framebuffer::new(&mut buffer, COLUMNS * 8, ROWS * 2),
+            start: 0,
+        };
+        let mut terminal = Terminal::new(&mut screen, &CODE_FONT, FG, BG);
+        fmt::Write::write_str(&mut terminal, input).unwrap();
+        let cursor = terminal.cursor();
+        check(&screen, cursor);
+    }
+
+    #[test]
+    fn prints_and_wraps_lines() {
+        write("hi\r\nthere\n0123456789abc", |screen, cursor| {
+            assert_eq!(rows(screen), ["hi", "there", "0123456789", "abc"]);
+            assert_eq!(cursor, (3, 3));
+        });
+    }
+
+    #[test]
+    fn scrolls_by_moving_the_start_line() {
+        write("1\n2\n3\n4\n5\n6", |screen, cursor| {
+            assert_eq!(rows(screen), ["3", "4", "5", "6"]);
+            assert_eq!(screen.start, 4);
+            assert_eq!(cursor, (1, 3));
+        });
+    }
+
+    #[test]
+    fn tabs_and_backspaces() {
+        write("a\tb\x08\x08c\t\t\td", |screen, _| {
+            assert_eq!(rows(screen), ["a      cbd", "", "", ""]);
+        });
+    }
+
+    #[test]
+    fn moves_the_cursor() {
+        let input = concat!(
+            "\x1b[2;3Hx",
+            "\x1b[65535B\x1b[65535Dy",
+            "\x1b[65535A\x1b[65535Cz",
+            "\x1b[5Gw",
+            "\x1b[s\x1b[3;2Hv\x1b[uu",
+        );
+        write(input, |screen, cursor| {
+            assert_eq!(rows(screen), ["    wu   z", "  x", " v", "y"]);
+            assert_eq!(cursor, (6, 0));
+        });
+    }
+
+    #[test]
+    fn erases_in_lines() {
+        write(
+            "abcdef\x1b[3D\x1b[K\nghij\x1b[2D\x1b[1K",
+            |screen, cursor| {
+                assert_eq!(rows(screen), ["abc", "   j", "", ""]);
+                assert_eq!(cursor, (2, 1));
+            },
+        );
+    }
+
+    #[test]
+    fn erases_in_the_display() {
+        write("one\ntwo\nsix\x1b[2;2H\x1b[J", |screen, _| {
+            assert_eq!(rows(screen), ["one", "t", "", ""]);
+        });
+        write("one\ntwo\nsix\x1b[2;2H\x1b[1J", |screen, _| {
+            assert_eq!(rows(screen), ["", "  o", "six", ""]);
+        });
+        write("one\ntwo\x1b[2J", |screen, cursor| {
+            assert_eq!(rows(screen), ["", "", "", ""]);
+            assert_eq!(cursor, (3, 1));
+        });
+    }
+
+    #[test]
+    fn selects_colors() {
+        write(
+            "a\x1b[31mb\x1b[1mc\x1b[7md\x1b[0me\x1b[94;42mf",
+            |screen, _| {
+                assert_eq!(cell(screen, 0, 0), ('a', FG, BG));
+                assert_eq!(cell(screen, 1, 0), ('b', ANSI_PALETTE[1], BG));
+                assert_eq!(cell(screen, 2, 0), ('c', ANSI_PALETTE[9], BG));
+                assert_eq!(cell(screen, 3, 0), ('d', BG, ANSI_PALETTE[9]));
+                assert_eq!(cell(screen, 4, 0), ('e', FG, BG));
+                assert_eq!(cell(screen, 5, 0), ('f', ANSI_PALETTE[12], ANSI_PALETTE[2]));
+            },
+        );
+    }
+
+    #[test]
+    fn a_cell_wider_than_the_canvas_is_one_column() {
+        let mut buffer = buffer(4, ROWS * 2);
+        let mut screen = Screen {
+            frame: Framebuffer::new(&mut buffer, 4, ROWS * 2),
+            start: 0,
+        };
+        let mut terminal = Terminal::new(&mut screen, &CODE_FONT, FG, BG);
+        assert_eq!((terminal.columns(), terminal.rows()), (1, ROWS));
+        fmt::Write::write_str(&mut terminal, "ab\x1b[5C\tc\x1b[9;9H\x1b[K").unwrap();
+        assert_eq!(terminal.cursor(), (0, ROWS - 1));
+    }
+}
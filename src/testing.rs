@@ -0,0 +1,111 @@
+//! Helpers shared by the host tests.
+extern crate std;
+
+use std::fmt::Write;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::font::ROMFont;
+use crate::framebuffer::Framebuffer;
+
+/// The characters the golden images name their colors with, in order.
+const PALETTE_NAMES: &str =
+    ".#abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+-*/=%&@$?!<>()[]{}^~;:,'`|_\"\\";
+
+/// The glyphs of `CODE_FONT`: the top row of each is its character code and
+/// the bottom row is empty.
+static CODE_FONT_DATA: [u8; 190] = {
+    let mut data = [0; 190];
+    let mut i = 0;
+    while i < 95 {
+        data[i * 2] = 0x20 + i as u8;
+        i += 1;
+    }
+    data
+};
+
+/// An 8x2 font of the printable ASCII characters whose glyphs spell their
+/// character codes, so text drawn with it can be read back from the pixels.
+pub static CODE_FONT: ROMFont = ROMFont::new(&CODE_FONT_DATA, 2, 8, ' '..'\x7f');
+
+/// Returns a zeroed buffer for a framebuffer of `width` by `height` pixels.
+pub fn buffer(width: u16, height: u16) -> Vec<u8> {
+    std::vec![0; width as usize * height as usize * 2]
+}
+
+/// Returns the character drawn with `CODE_FONT` in the cell at (x, y) and its
+/// font and background colors. Empty cells read as spaces.
+pub fn read_cell(framebuffer: &Framebuffer, x: i32, y: i32) -> (char, u16, u16) {
+    let pixel = |x, y| framebuffer.get_pixel(x, y).unwrap();
+    let background = pixel(x, y + 1);
+    let (mut code, mut color) = (0u8, background);
+    for i in 0..8 {
+        if pixel(x + i, y) != background {
+            code |= 0x80 >> i;
+            color = pixel(x + i, y);
+        }
+    }
+    let c = if code == 0 { ' ' } else { code as char };
+    (c, color, background)
+}
+
+/// Returns the characters drawn with `CODE_FONT` in `columns` cells from
+/// (x, y).
+pub fn read_line(framebuffer: &Framebuffer, x: i32, y: i32, columns: u16) -> String {
+    (0..columns as i32)
+        .map(|column| read_cell(framebuffer, x + column * 8, y).0)
+        .collect()
+}
+
+/// Returns the pixels of a framebuffer as text: the colors named in the order
+/// they're first met, one `name color` per line, then a blank line and a
+/// line of names per row.
+pub fn picture(framebuffer: &Framebuffer) -> String {
+    let mut colors: Vec<u16> = Vec::new();
+    let mut rows = String::new();
+    for y in 0..framebuffer.height() as i32 {
+        for x in 0..framebuffer.width() as i32 {
+            let color = framebuffer.get_pixel(x, y).unwrap();
+            let index = match colors.iter().position(|&c| c == color) {
+                Some(index) => index,
+                None => {
+                    colors.push(color);
+                    colors.len() - 1
+                }
+            };
+            let name = PALETTE_NAMES.chars().nth(index).expect("too many colors");
+            rows.push(name);
+        }
+        rows.push('\n');
+    }
+    let mut text = String::new();
+    for (color, name) in colors.iter().zip(PALETTE_NAMES.chars()) {
+        writeln!(text, "{name} {color:04x}").unwrap();
+    }
+    text.push('\n');
+    text + &rows
+}
+
+/// Checks the pixels of a framebuffer against `tests/golden/<name>.txt`, in
+/// the format of `picture`. With `UPDATE_GOLDEN` set the file is written
+/// instead.
+pub fn assert_golden(name: &str, framebuffer: &Framebuffer) {
+    let path = std::format!("{}/tests/golden/{name}.txt", env!("CARGO_MANIFEST_DIR"));
+    let actual = picture(framebuffer);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("no golden image {path}, run with UPDATE_GOLDEN=1"));
+    assert!(
+        actual == expected,
+        "{name} differs from its golden image:\n{actual}"
+    );
+}
+
+/// Returns the bytes of `tests/fixtures/<name>`.
+pub fn fixture(name: &str) -> Vec<u8> {
+    let path = std::format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read(&path).unwrap_or_else(|_| panic!("no fixture {path}"))
+}
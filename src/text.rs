@@ -0,0 +1,840 @@
+use crate::canvas::Rect;
+use crate::font::{Font, Glyph};
+
+/// Something text can be drawn on, the display or a framebuffer.
+pub trait TextCanvas {
+    /// Returns the width and height text fits in, right of and below the
+    /// origin of the canvas.
+    fn size(&self) -> (u16, u16);
+
+    /// Draws a glyph in its cell, from `x` to the next pen position and
+    /// `height` high from `y`, clipped.
+    ///
+    /// With a background color the whole cell is drawn, otherwise only the
+    /// glyph is.
+    fn draw_glyph(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: &Glyph,
+        height: u16,
+        color: u16,
+        background: Option<u16>,
+    );
+}
+
+/// A run of text drawn with one font and color, to mix fonts in a label.
+///
+/// ```ignore
+/// display.draw_rich_text(0, 0, &[
+///     Span::new("Score ", &fonts::VGA2_BOLD_16X16, WHITE),
+///     Span::new("1200", &fonts::VGA2_8X16, YELLOW),
+/// ], BLACK);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Span<'a> {
+    /// the text of the run
+    pub text: &'a str,
+    /// the font of the run
+    pub font: &'a dyn Font,
+    /// the color of the text
+    pub color: u16,
+}
+
+impl<'a> Span<'a> {
+    pub const fn new(text: &'a str, font: &'a dyn Font, color: u16) -> Span<'a> {
+        Span { text, font, color }
+    }
+}
+
+/// Returns the height of a line of spans, that of their tallest font.
+pub fn line_height(spans: &[Span]) -> u16 {
+    spans
+        .iter()
+        .map(|span| span.font.get_height() as u16)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the width and height of spans, like `Font::measure_text`.
+pub fn measure_spans(spans: &[Span]) -> (u16, u16) {
+    let height = line_height(spans);
+    let mut width: u16 = 0;
+    let mut max_width: u16 = 0;
+    let mut lines: u16 = 1;
+    for span in spans {
+        let mut prev: Option<char> = None;
+        for c in span.text.chars() {
+            if c == '\n' {
+                max_width = max_width.max(width);
+                width = 0;
+                lines = lines.saturating_add(1);
+                prev = None;
+            } else if let Some(w) = span.font.get_width(c) {
+                if let Some(p) = prev {
+                    width = width.saturating_add_signed(span.font.get_kerning(p, c) as i16);
+                }
+                width = width.saturating_add(w as u16);
+                prev = Some(c);
+            }
+        }
+    }
+    (max_width.max(width), lines.saturating_mul(height))
+}
+
+/// Draws spans of text one after another from `x` and `y`, breaking lines at
+/// newline characters and at the right of the canvas.
+/// Returns the bottom right corner of the text box.
+///
+/// Lines are as high as the tallest font of the spans, with the glyphs at the
+/// top. It stops drawing at the first line that doesn't fit the canvas.
+pub fn draw_spans<C: TextCanvas + ?Sized>(
+    canvas: &mut C,
+    x: i32,
+    y: i32,
+    spans: &[Span],
+    background: Option<u16>,
+) -> (i32, i32) {
+    let (width, bottom) = canvas.size();
+    let (width, bottom) = (width as i32, bottom as i32);
+    let start_x = x;
+    let mut end_x = x;
+    let height = line_height(spans) as i32;
+    let mut x = x;
+    let mut y = y;
+
+    for span in spans {
+        let font = span.font;
+        let mut prev: Option<char> = None;
+        for c in span.text.chars() {
+            if c == '\n' {
+                if x > end_x {
+                    end_x = x;
+                }
+                x = start_x;
+                y += height;
+                prev = None;
+                if y + height > bottom {
+                    return (end_x, y);
+                } else {
+                    continue;
+                }
+            }
+            if let Some(glyph) = font.get_glyph(c) {
+                if let Some(p) = prev {
+                    x = (x + font.get_kerning(p, c) as i32).max(start_x);
+                }
+                prev = Some(c);
+                let w = glyph.advance as i32;
+                if w == 0 {
+                    continue;
+                }
+                if x + w > width {
+                    if x > end_x {
+                        end_x = x;
+                    }
+                    x = start_x;
+                    y += height;
+                    if y + height > bottom {
+                        return (end_x, y);
+                    }
+                }
+                canvas.draw_glyph(x, y, &glyph, height as u16, span.color, background);
+                x += w;
+            }
+        }
+    }
+    if x > end_x {
+        end_x = x;
+    }
+    (end_x, y + height)
+}
+
+/// A glyph of text laid out by `transformed_glyphs`, scaled and turned.
+pub(crate) struct TransformedGlyph<'f> {
+    /// the cell of the glyph on the screen
+    pub rect: Rect,
+    glyph: Glyph<'f>,
+    rotation: TextRotation,
+    /// the scale, at least 1
+    scale: i32,
+    /// the scaled advance and line height, the cell before it is turned
+    advance: i32,
+    height: i32,
+}
+
+impl TransformedGlyph<'_> {
+    /// Returns the coverage of the glyph at (i, j) in its cell on the screen.
+    pub fn coverage(&self, i: i32, j: i32) -> u8 {
+        let (a, h) = (self.advance, self.height);
+        // Back from the screen to the unscaled cell of the glyph.
+        let (u, v) = match self.rotation {
+            TextRotation::None => (i, j),
+            TextRotation::Cw90 => (j, h - 1 - i),
+            TextRotation::Cw180 => (a - 1 - i, h - 1 - j),
+            TextRotation::Cw270 => (a - 1 - j, i),
+        };
+        self.glyph.cell_coverage(u / self.scale, v / self.scale)
+    }
+}
+
+/// Lays text out scaled `scale` times and turned, from the top left corner
+/// `x` and `y` of the turned text box, calling `draw` with each glyph.
+/// Returns the bottom right corner of the text box.
+///
+/// Newline characters start a new line under the previous one, in the
+/// direction of the text.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transformed_glyphs<'f>(
+    x: i32,
+    y: i32,
+    text: &str,
+    font: &'f dyn Font,
+    scale: u8,
+    rotation: TextRotation,
+    mut draw: impl FnMut(&TransformedGlyph<'f>),
+) -> (i32, i32) {
+    let s = scale.max(1) as i32;
+    let (text_width, text_height) = font.measure_text(text);
+    let (box_width, box_height) = (text_width as i32 * s, text_height as i32 * s);
+    let h = font.get_height() as i32 * s;
+    let mut u: i32 = 0;
+    let mut v: i32 = 0;
+    let mut prev: Option<char> = None;
+
+    for c in text.chars() {
+        if c == '\n' {
+            u = 0;
+            v += h;
+            prev = None;
+            continue;
+        }
+        let Some(glyph) = font.get_glyph(c) else {
+            continue;
+        };
+        if let Some(p) = prev {
+            u = (u + font.get_kerning(p, c) as i32 * s).max(0);
+        }
+        prev = Some(c);
+        let a = glyph.advance as i32 * s;
+        if a == 0 {
+            continue;
+        }
+
+        // The cell of the character on the screen, and its size.
+        let rect = match rotation {
+            TextRotation::None => Rect::new(x + u, y + v, a, h),
+            TextRotation::Cw90 => Rect::new(x + box_height - v - h, y + u, h, a),
+            TextRotation::Cw180 => Rect::new(x + box_width - u - a, y + box_height - v - h, a, h),
+            TextRotation::Cw270 => Rect::new(x + v, y + box_width - u - a, h, a),
+        };
+        u += a;
+        draw(&TransformedGlyph {
+            rect,
+            glyph,
+            rotation,
+            scale: s,
+            advance: a,
+            height: h,
+        });
+    }
+    match rotation {
+        TextRotation::None | TextRotation::Cw180 => (x + box_width, y + box_height),
+        TextRotation::Cw90 | TextRotation::Cw270 => (x + box_height, y + box_width),
+    }
+}
+
+/// The direction text runs in, independent of the panel `Rotation`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TextRotation {
+    /// left to right
+    None,
+    /// top to bottom, turned a quarter clockwise
+    Cw90,
+    /// right to left, upside down
+    Cw180,
+    /// bottom to top, like the label of a vertical axis
+    Cw270,
+}
+
+/// The horizontal alignment of the lines of a `Layout`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+    /// widen the spaces so lines fill the width, except the last of a paragraph
+    Justify,
+}
+
+/// The vertical alignment of the lines of a `Layout`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// A rectangle in pixels.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct Bounds {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Bounds {
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Bounds {
+        Bounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// A line of a `Layout`, where and how it is drawn.
+#[derive(Clone, Copy)]
+pub struct Line<'t> {
+    /// the text of the line, without the spaces or newline it was broken at
+    pub text: &'t str,
+    /// the left of the line
+    pub x: u16,
+    /// the top of the line
+    pub y: u16,
+    /// the width of the line as drawn, with the ellipsis and justification
+    pub width: u16,
+    /// the height of the line
+    pub height: u16,
+    /// the pixels spread over the spaces of the line to justify it
+    pub extra_space: u16,
+    /// the number of spaces in the line
+    pub spaces: u16,
+    /// whether the text is cut after the line, ending it with an ellipsis
+    pub ellipsis: bool,
+}
+
+impl Line<'_> {
+    /// Returns the pixels added to a space of the line, counting from 0.
+    pub fn space_extra(&self, index: u16) -> u16 {
+        if self.spaces == 0 {
+            return 0;
+        }
+        self.extra_space / self.spaces + (index < self.extra_space % self.spaces) as u16
+    }
+}
+
+/// Lays text out in a rectangle, wrapping lines between words.
+///
+/// Words wider than the rectangle are broken where they overflow. When the
+/// text doesn't fit the rectangle or `max_lines`, the last line ends with "…",
+/// or "..." with fonts without it.
+///
+/// ```ignore
+/// let layout = Layout::new(&fonts::VGAPROP_16, Bounds::new(10, 10, 220, 60))
+///     .with_align(Align::Center)
+///     .with_max_lines(3);
+/// layout.draw(&mut display, "A long message...", WHITE, Some(BLACK));
+/// ```
+#[derive(Clone, Copy)]
+pub struct Layout<'a> {
+    /// the font of the text
+    font: &'a dyn Font,
+    /// the rectangle of the text
+    bounds: Bounds,
+    /// the horizontal alignment of the lines
+    align: Align,
+    /// the vertical alignment of the lines in the rectangle
+    vertical_align: VerticalAlign,
+    /// the pixels between two lines
+    line_spacing: u16,
+    /// the most lines drawn, 0 for as many as fit
+    max_lines: u16,
+}
+
+impl<'a> Layout<'a> {
+    /// Creates a layout of left aligned lines from the top of `bounds`.
+    pub const fn new(font: &'a dyn Font, bounds: Bounds) -> Layout<'a> {
+        Layout {
+            font,
+            bounds,
+            align: Align::Left,
+            vertical_align: VerticalAlign::Top,
+            line_spacing: 0,
+            max_lines: 0,
+        }
+    }
+
+    pub const fn with_align(self, align: Align) -> Layout<'a> {
+        Layout { align, ..self }
+    }
+
+    pub const fn with_vertical_align(self, vertical_align: VerticalAlign) -> Layout<'a> {
+        Layout {
+            vertical_align,
+            ..self
+        }
+    }
+
+    /// Returns the layout with pixels added between lines.
+    pub const fn with_line_spacing(self, line_spacing: u16) -> Layout<'a> {
+        Layout {
+            line_spacing,
+            ..self
+        }
+    }
+
+    /// Returns the layout with at most `max_lines` lines, 0 for as many as fit.
+    pub const fn with_max_lines(self, max_lines: u16) -> Layout<'a> {
+        Layout { max_lines, ..self }
+    }
+
+    /// Returns the lines of a text as they are drawn.
+    pub fn lines<'t>(&self, text: &'t str) -> Lines<'a, 't> {
+        let height = self.font.get_height() as u16;
+        let pitch = height + self.line_spacing;
+        let mut limit = (self.bounds.height + self.line_spacing)
+            .checked_div(pitch)
+            .unwrap_or(0);
+        if self.max_lines != 0 {
+            limit = limit.min(self.max_lines);
+        }
+
+        // Count the lines up to one past the limit, to know whether to cut the text.
+        let total = self.breaks(text).take(limit as usize + 1).count() as u16;
+        let shown = total.min(limit);
+        let block = (shown * pitch).saturating_sub(self.line_spacing);
+        let free = self.bounds.height.saturating_sub(block);
+        let top = self.bounds.y
+            + match self.vertical_align {
+                VerticalAlign::Top => 0,
+                VerticalAlign::Middle => free / 2,
+                VerticalAlign::Bottom => free,
+            };
+
+        Lines {
+            layout: *self,
+            breaks: self.breaks(text),
+            index: 0,
+            shown,
+            cut: total > limit,
+            top,
+            pitch,
+        }
+    }
+
+    /// Returns the rectangle covered by the lines of a text as they are drawn.
+    pub fn measure(&self, text: &str) -> Bounds {
+        let mut covered: Option<Bounds> = None;
+        for line in self.lines(text) {
+            covered = Some(match covered {
+                None => Bounds::new(line.x, line.y, line.width, line.height),
+                Some(b) => {
+                    let left = b.x.min(line.x);
+                    let right = (b.x + b.width).max(line.x + line.width);
+                    Bounds::new(left, b.y, right - left, line.y + line.height - b.y)
+                }
+            });
+        }
+        covered.unwrap_or(Bounds::new(self.bounds.x, self.bounds.y, 0, 0))
+    }
+
+    /// Draws the lines of a text, returning the rectangle they cover.
+    ///
+    /// With a background color, the cells of the glyphs are drawn, not the
+    /// whole rectangle.
+    pub fn draw<C: TextCanvas + ?Sized>(
+        &self,
+        canvas: &mut C,
+        text: &str,
+        color: u16,
+        background: Option<u16>,
+    ) -> Bounds {
+        let font = self.font;
+        for line in self.lines(text) {
+            let mut x = line.x;
+            let mut prev: Option<char> = None;
+            let mut space = 0;
+            let ellipsis = if line.ellipsis { ellipsis(font) } else { "" };
+            for c in line.text.chars().chain(ellipsis.chars()) {
+                if let Some(glyph) = font.get_glyph(c) {
+                    if let Some(p) = prev {
+                        x = x.saturating_add_signed(font.get_kerning(p, c) as i16);
+                    }
+                    prev = Some(c);
+                    if glyph.advance != 0 {
+                        let (x, y) = (x as i32, line.y as i32);
+                        canvas.draw_glyph(x, y, &glyph, line.height, color, background);
+                    }
+                    x += glyph.advance as u16;
+                }
+                if c == ' ' {
+                    x += line.space_extra(space);
+                    space += 1;
+                }
+            }
+        }
+        self.measure(text)
+    }
+
+    fn breaks<'t>(&self, text: &'t str) -> Breaks<'a, 't> {
+        Breaks {
+            font: self.font,
+            width: self.bounds.width,
+            rest: Some(text),
+        }
+    }
+}
+
+/// The lines of a text laid out by `Layout::lines`.
+pub struct Lines<'a, 't> {
+    layout: Layout<'a>,
+    breaks: Breaks<'a, 't>,
+    /// the index of the next line
+    index: u16,
+    /// the number of lines drawn
+    shown: u16,
+    /// whether the text goes on after the last line drawn
+    cut: bool,
+    /// the top of the first line
+    top: u16,
+    /// the pixels from the top of a line to the next
+    pitch: u16,
+}
+
+impl<'t> Iterator for Lines<'_, 't> {
+    type Item = Line<'t>;
+
+    fn next(&mut self) -> Option<Line<'t>> {
+        if self.index == self.shown {
+            return None;
+        }
+        let layout = &self.layout;
+        let font = layout.font;
+        let bounds = layout.bounds;
+        let (mut text, end_of_paragraph) = self.breaks.next()?;
+        let ellipsis = self.cut && self.index + 1 == self.shown;
+        // The text and the ellipsis are one run, kerned between them as drawn.
+        let end = if ellipsis { self::ellipsis(font) } else { "" };
+        let line_width = |text: &str| text_width(font, text.chars().chain(end.chars()));
+        let mut width = line_width(text);
+        if ellipsis {
+            // Drop characters until the ellipsis fits after them.
+            while width > bounds.width && !text.is_empty() {
+                let mut chars = text.chars();
+                chars.next_back();
+                text = chars.as_str();
+                width = line_width(text);
+            }
+            text = text.trim_end_matches(' ');
+            width = line_width(text);
+        }
+        let spaces = text.chars().filter(|&c| c == ' ').count() as u16;
+        let extra_space =
+            if layout.align == Align::Justify && !end_of_paragraph && !ellipsis && spaces > 0 {
+                bounds.width.saturating_sub(width)
+            } else {
+                0
+            };
+        let free = bounds.width.saturating_sub(width + extra_space);
+        let x = bounds.x
+            + match layout.align {
+                Align::Left | Align::Justify => 0,
+                Align::Center => free / 2,
+                Align::Right => free,
+            };
+        let line = Line {
+            text,
+            x,
+            y: self.top + self.index * self.pitch,
+            width: width + extra_space,
+            height: font.get_height() as u16,
+            extra_space,
+            spaces,
+            ellipsis,
+        };
+        self.index += 1;
+        Some(line)
+    }
+}
+
+/// Breaks text into lines no wider than `width`, with whether each line ends
+/// a paragraph.
+struct Breaks<'a, 't> {
+    font: &'a dyn Font,
+    width: u16,
+    /// the text left, None after the last line
+    rest: Option<&'t str>,
+}
+
+impl<'t> Iterator for Breaks<'_, 't> {
+    type Item = (&'t str, bool);
+
+    fn next(&mut self) -> Option<(&'t str, bool)> {
+        let text = self.rest?;
+        let mut width: u16 = 0;
+        let mut prev: Option<char> = None;
+        // The end of the line and the start of the next one at the last space.
+        let mut last_break: Option<(usize, usize)> = None;
+        for (i, c) in text.char_indices() {
+            if c == '\n' {
+                self.rest = Some(&text[i + 1..]);
+                return Some((&text[..i], true));
+            }
+            let Some(advance) = self.font.get_width(c) else {
+                continue;
+            };
+            let kerning = match prev {
+                Some(p) => self.font.get_kerning(p, c) as i16,
+                None => 0,
+            };
+            prev = Some(c);
+            let next = width.saturating_add_signed(kerning) + advance as u16;
+            if c == ' ' {
+                // Spaces can overflow, they are dropped at the break.
+                last_break = Some((i, i + 1));
+            } else if next > self.width && i > 0 {
+                let (line, rest) = match last_break {
+                    Some((end, start)) => (&text[..end], &text[start..]),
+                    None => (&text[..i], &text[i..]),
+                };
+                self.rest = Some(rest.trim_start_matches(' '));
+                return Some((line.trim_end_matches(' '), false));
+            }
+            width = next;
+        }
+        self.rest = None;
+        Some((text, true))
+    }
+}
+
+/// Returns the width of a run of characters in a font, kerned as
+/// `Layout::draw` draws them.
+fn text_width(font: &dyn Font, text: impl Iterator<Item = char>) -> u16 {
+    let mut width: u16 = 0;
+    let mut prev: Option<char> = None;
+    for c in text {
+        if let Some(w) = font.get_width(c) {
+            if let Some(p) = prev {
+                width = width.saturating_add_signed(font.get_kerning(p, c) as i16);
+            }
+            width = width.saturating_add(w as u16);
+            prev = Some(c);
+        }
+    }
+    width
+}
+
+/// Returns the ellipsis of a font, "…" when it has it.
+fn ellipsis(font: &dyn Font) -> &'static str {
+    if font.get_width('…').is_some() {
+        "…"
+    } else {
+        "..."
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::canvas::Canvas;
+    use crate::color::blend;
+    use crate::framebuffer::Framebuffer;
+    use crate::testing::{assert_golden, buffer, read_cell, CODE_FONT};
+
+    /// Returns the text, left, top and width of the lines of a layout.
+    fn placed<'t>(layout: &Layout, text: &'t str) -> Vec<(&'t str, u16, u16, u16)> {
+        layout
+            .lines(text)
+            .map(|line| (line.text, line.x, line.y, line.width))
+            .collect()
+    }
+
+    /// `CODE_FONT` with the dots after a 'c' pulled back under it.
+    struct Kerned;
+
+    impl Font for Kerned {
+        fn get_char(&self, c: char) -> Option<(&[u8], u8)> {
+            CODE_FONT.get_char(c)
+        }
+
+        fn get_width(&self, c: char) -> Option<u8> {
+            CODE_FONT.get_width(c)
+        }
+
+        fn get_height(&self) -> u8 {
+            CODE_FONT.get_height()
+        }
+
+        fn get_kerning(&self, left: char, right: char) -> i8 {
+            if (left, right) == ('c', '.') {
+                -8
+            } else {
+                0
+            }
+        }
+    }
+
+    #[test]
+    fn a_cut_line_is_measured_with_its_ellipsis_kerned_after_it() {
+        let layout = Layout::new(&Kerned, Bounds::new(0, 0, 40, 2)).with_max_lines(1);
+        let line = layout.lines("abcd efgh").next().unwrap();
+        assert_eq!(line.text, "abc");
+        assert!(line.ellipsis);
+        assert_eq!(line.width, 40);
+        assert_eq!(layout.measure("abcd efgh").width, 40);
+    }
+
+    #[test]
+    fn an_ellipsis_wider_than_the_line_leaves_no_text() {
+        let layout = Layout::new(&CODE_FONT, Bounds::new(0, 0, 16, 2)).with_max_lines(1);
+        let line = layout.lines("abcd efgh").next().unwrap();
+        assert_eq!((line.text, line.ellipsis, line.width), ("", true, 24));
+    }
+
+    #[test]
+    fn spans_too_big_to_measure_saturate() {
+        let text = "a".repeat(10_000);
+        let spans = [Span::new(&text, &CODE_FONT, 0)];
+        assert_eq!(measure_spans(&spans).0, u16::MAX);
+        let text = "\n".repeat(40_000);
+        let spans = [Span::new(&text, &CODE_FONT, 0)];
+        assert_eq!(measure_spans(&spans), (0, u16::MAX));
+    }
+
+    #[test]
+    fn lines_wrap_between_words_at_the_width() {
+        let layout = Layout::new(&CODE_FONT, Bounds::new(0, 0, 56, 20));
+        let lines: Vec<_> = layout
+            .lines("ab cd efgh ij")
+            .map(|line| line.text)
+            .collect();
+        assert_eq!(lines, ["ab cd", "efgh ij"]);
+        let lines: Vec<_> = layout.lines("ab\n\ncd").map(|line| line.text).collect();
+        assert_eq!(lines, ["ab", "", "cd"]);
+    }
+
+    #[test]
+    fn a_word_wider_than_the_line_is_broken_where_it_overflows() {
+        let layout = Layout::new(&CODE_FONT, Bounds::new(0, 0, 32, 20));
+        let lines: Vec<_> = layout.lines("abcdefghij k").map(|line| line.text).collect();
+        assert_eq!(lines, ["abcd", "efgh", "ij k"]);
+    }
+
+    #[test]
+    fn lines_are_placed_by_their_alignment() {
+        let layout = Layout::new(&CODE_FONT, Bounds::new(10, 0, 80, 20));
+        let text = "ab cd\nefg";
+        assert_eq!(
+            placed(&layout, text),
+            [("ab cd", 10, 0, 40), ("efg", 10, 2, 24)]
+        );
+        let layout = layout.with_align(Align::Center);
+        assert_eq!(
+            placed(&layout, text),
+            [("ab cd", 30, 0, 40), ("efg", 38, 2, 24)]
+        );
+        let layout = layout.with_align(Align::Right);
+        assert_eq!(
+            placed(&layout, text),
+            [("ab cd", 50, 0, 40), ("efg", 66, 2, 24)]
+        );
+    }
+
+    #[test]
+    fn justified_lines_spread_the_space_left_over_their_spaces() {
+        let layout = Layout::new(&CODE_FONT, Bounds::new(0, 0, 83, 4)).with_align(Align::Justify);
+        let text = "ab cd ef gh ij";
+        // The last line of the paragraph stays left aligned.
+        assert_eq!(
+            placed(&layout, text),
+            [("ab cd ef", 0, 0, 83), ("gh ij", 0, 2, 40)]
+        );
+        let line = layout.lines(text).next().unwrap();
+        assert_eq!((line.extra_space, line.spaces), (19, 2));
+        assert_eq!((line.space_extra(0), line.space_extra(1)), (10, 9));
+
+        let mut buf = buffer(83, 4);
+        let mut fb = Framebuffer::new(&mut buf, 83, 4);
+        layout.draw(&mut fb, text, 0xffff, None);
+        let cells: Vec<_> = [0, 8, 34, 42, 67, 75]
+            .iter()
+            .map(|&x| read_cell(&fb, x, 0).0)
+            .collect();
+        assert_eq!(cells, ['a', 'b', 'c', 'd', 'e', 'f']);
+    }
+
+    #[test]
+    fn lines_are_spaced_and_aligned_vertically() {
+        let layout = Layout::new(&CODE_FONT, Bounds::new(0, 5, 80, 20)).with_line_spacing(3);
+        let tops =
+            |layout: Layout| -> Vec<u16> { layout.lines("a\nb").map(|line| line.y).collect() };
+        assert_eq!(tops(layout), [5, 10]);
+        assert_eq!(
+            tops(layout.with_vertical_align(VerticalAlign::Middle)),
+            [11, 16]
+        );
+        assert_eq!(
+            tops(layout.with_vertical_align(VerticalAlign::Bottom)),
+            [18, 23]
+        );
+        let middle = layout.with_vertical_align(VerticalAlign::Middle);
+        assert!(middle.measure("a\nb") == Bounds::new(0, 11, 8, 7));
+        // 4 lines fit, the last one cut with an ellipsis.
+        let lines: Vec<_> = layout.lines("a\nb\nc\nd\ne").collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            (lines[3].text, lines[3].ellipsis, lines[3].y),
+            ("d", true, 20)
+        );
+    }
+
+    /// Checks "Ab\nc" turned every way at `scale`, drawn as the display does:
+    /// upright and upside down on the left, a quarter turn each way on the
+    /// right.
+    fn assert_transformed(scale: u8) {
+        let s = scale as i32;
+        let (width, height) = (32 * scale as u16, 16 * scale as u16);
+        let mut pixels = buffer(width, height);
+        let mut frame = Framebuffer::new(&mut pixels, width, height);
+        let shots = [
+            (0, 0, TextRotation::None, (16, 4)),
+            (0, 6, TextRotation::Cw180, (16, 10)),
+            (20, 0, TextRotation::Cw90, (24, 16)),
+            (26, 0, TextRotation::Cw270, (30, 16)),
+        ];
+        for (x, y, rotation, end) in shots {
+            let end = (end.0 * s, end.1 * s);
+            let corner = transformed_glyphs(
+                x * s,
+                y * s,
+                "Ab\nc",
+                &CODE_FONT,
+                scale,
+                rotation,
+                |glyph| {
+                    let cell = glyph.rect;
+                    for j in 0..cell.height {
+                        for i in 0..cell.width {
+                            let color = blend(0xffff, 0x1082, glyph.coverage(i, j));
+                            frame.fill_span((cell.x + i) as u16, (cell.y + j) as u16, 1, color);
+                        }
+                    }
+                },
+            );
+            assert_eq!(corner, end);
+        }
+        assert_golden(&std::format!("text_transformed_scale_{scale}"), &frame);
+    }
+
+    #[test]
+    fn transformed_text_turns_around_its_box() {
+        assert_transformed(1);
+        assert_transformed(2);
+    }
+}
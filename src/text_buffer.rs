@@ -0,0 +1,585 @@
+use core::ops::BitOr;
+
+use crate::canvas::{Canvas, Rect};
+use crate::font::Font;
+
+/// Size of the pixel buffer used while rendering a cell, in bytes.
+const RENDER_BUFFER_SIZE: usize = 512;
+
+/// Display attributes of a character cell.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct Attributes(u8);
+
+impl Attributes {
+    pub const NONE: Attributes = Attributes(0x00);
+    /// The character is hidden every other blink phase.
+    pub const BLINK: Attributes = Attributes(0x01);
+    /// The last pixel row of the cell is drawn with the font color.
+    pub const UNDERLINE: Attributes = Attributes(0x02);
+    /// The font and background colors are swapped.
+    pub const REVERSE: Attributes = Attributes(0x04);
+    /// The character is drawn twice as wide, covering the next cell too.
+    pub const DOUBLE_WIDTH: Attributes = Attributes(0x08);
+    /// The character is drawn twice as high, covering the cell below too.
+    pub const DOUBLE_HEIGHT: Attributes = Attributes(0x10);
+    /// The cell is covered by a double-width or double-height neighbour.
+    const COVERED: Attributes = Attributes(0x80);
+
+    /// Returns whether all the attributes in `other` are set.
+    pub const fn contains(self, other: Attributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the attributes without the ones in `other`.
+    pub const fn without(self, other: Attributes) -> Attributes {
+        Attributes(self.0 & !other.0)
+    }
+}
+
+impl BitOr for Attributes {
+    type Output = Attributes;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Attributes(self.0 | rhs.0)
+    }
+}
+
+/// A character cell of a text buffer.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    /// the character
+    pub ch: char,
+    /// the font color
+    pub fg: u16,
+    /// the background color
+    pub bg: u16,
+    /// the display attributes
+    pub attrs: Attributes,
+}
+
+impl Cell {
+    /// Creates a cell without attributes.
+    pub const fn new(ch: char, fg: u16, bg: u16) -> Cell {
+        Cell {
+            ch,
+            fg,
+            bg,
+            attrs: Attributes::NONE,
+        }
+    }
+
+    /// Returns the cell with the given attributes.
+    pub const fn with_attrs(self, attrs: Attributes) -> Cell {
+        Cell {
+            ch: self.ch,
+            fg: self.fg,
+            bg: self.bg,
+            attrs,
+        }
+    }
+}
+
+/// A text-mode screen of `COLS` x `ROWS` character cells.
+///
+/// Cells are drawn with a monospaced font, e.g. a 30x15 buffer with `VGA1_8X16`
+/// covers a 240x240 display. Only the cells changed since the last `render`
+/// are sent to the display.
+pub struct TextBuffer<'f, const COLS: usize, const ROWS: usize> {
+    /// the font of the text
+    font: &'f dyn Font,
+    /// the width of a cell in pixels
+    cell_width: u16,
+    /// the height of a cell in pixels
+    cell_height: u16,
+    /// the top left corner on the display
+    origin: (u16, u16),
+    /// the cells
+    cells: [[Cell; COLS]; ROWS],
+    /// the cells to draw on the next render
+    dirty: [[bool; COLS]; ROWS],
+    /// whether blinking characters are currently shown
+    blink_on: bool,
+}
+
+impl<'f, const COLS: usize, const ROWS: usize> TextBuffer<'f, COLS, ROWS> {
+    /// Creates a buffer filled with spaces.
+    ///
+    /// The width of the space character is used as the cell width.
+    pub fn new(font: &'f dyn Font, font_color: u16, background_color: u16) -> Self {
+        Self {
+            font,
+            cell_width: font.get_width(' ').unwrap_or(8) as u16,
+            cell_height: font.get_height() as u16,
+            origin: (0, 0),
+            cells: [[Cell::new(' ', font_color, background_color); COLS]; ROWS],
+            dirty: [[true; COLS]; ROWS],
+            blink_on: true,
+        }
+    }
+
+    /// Returns the number of columns.
+    pub const fn columns(&self) -> usize {
+        COLS
+    }
+
+    /// Returns the number of rows.
+    pub const fn rows(&self) -> usize {
+        ROWS
+    }
+
+    /// Returns the size of a cell in pixels as `(width, height)`.
+    pub fn cell_size(&self) -> (u16, u16) {
+        (self.cell_width, self.cell_height)
+    }
+
+    /// Moves the buffer on the display. Everything is drawn again on the next render.
+    pub fn set_origin(&mut self, x: u16, y: u16) {
+        self.origin = (x, y);
+        self.invalidate();
+    }
+
+    /// Returns the cell at a position.
+    pub fn get(&self, col: usize, row: usize) -> Option<&Cell> {
+        self.cells.get(row).and_then(|r| r.get(col))
+    }
+
+    /// Sets the cell at a position. Positions outside the buffer are ignored.
+    ///
+    /// Double-width and double-height cells cover their neighbours, the
+    /// attributes are dropped when there is no room for them. Writing over a
+    /// covered cell shrinks the character covering it back to one cell.
+    pub fn set(&mut self, col: usize, row: usize, cell: Cell) {
+        if col >= COLS || row >= ROWS {
+            return;
+        }
+        let mut cell = cell;
+        if col + 1 >= COLS {
+            cell.attrs = cell.attrs.without(Attributes::DOUBLE_WIDTH);
+        }
+        if row + 1 >= ROWS {
+            cell.attrs = cell.attrs.without(Attributes::DOUBLE_HEIGHT);
+        }
+
+        // Shrink the double-size characters drawn over the cells back to one cell.
+        let (w, h) = Self::span(&cell);
+        for r in row..row + h {
+            for c in col..col + w {
+                self.release(c, r);
+            }
+        }
+
+        self.update(col, row, cell);
+        for r in row..row + h {
+            for c in col..col + w {
+                if (c, r) != (col, row) {
+                    let covered = Cell::new(' ', cell.fg, cell.bg).with_attrs(Attributes::COVERED);
+                    self.update(c, r, covered);
+                }
+            }
+        }
+    }
+
+    /// Writes a string starting at a position, without wrapping.
+    /// Returns the column after the last character.
+    pub fn print(
+        &mut self,
+        col: usize,
+        row: usize,
+        text: &str,
+        font_color: u16,
+        background_color: u16,
+        attrs: Attributes,
+    ) -> usize {
+        let mut col = col;
+        let step = if attrs.contains(Attributes::DOUBLE_WIDTH) {
+            2
+        } else {
+            1
+        };
+        for c in text.chars() {
+            if col >= COLS {
+                break;
+            }
+            self.set(
+                col,
+                row,
+                Cell::new(c, font_color, background_color).with_attrs(attrs),
+            );
+            col += step;
+        }
+        col.min(COLS)
+    }
+
+    /// Fills a rectangle of cells.
+    pub fn fill(&mut self, col: usize, row: usize, width: usize, height: usize, cell: Cell) {
+        for r in row..(row + height).min(ROWS) {
+            for c in col..(col + width).min(COLS) {
+                self.set(c, r, cell);
+            }
+        }
+    }
+
+    /// Fills the whole buffer with spaces.
+    pub fn clear(&mut self, font_color: u16, background_color: u16) {
+        self.fill(
+            0,
+            0,
+            COLS,
+            ROWS,
+            Cell::new(' ', font_color, background_color),
+        );
+    }
+
+    /// Marks every cell to be drawn on the next render.
+    pub fn invalidate(&mut self) {
+        self.dirty = [[true; COLS]; ROWS];
+    }
+
+    /// Switches the blink phase, marking the blinking cells to be drawn.
+    pub fn toggle_blink(&mut self) {
+        self.blink_on = !self.blink_on;
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                if self.cells[row][col].attrs.contains(Attributes::BLINK) {
+                    self.dirty[row][col] = true;
+                }
+            }
+        }
+    }
+
+    /// Draws the changed cells on the display or a framebuffer, from the
+    /// origin of the buffer in local coordinates, clipped.
+    pub fn render<C: Canvas + ?Sized>(&mut self, canvas: &mut C) {
+        let buf = &mut [0u16; RENDER_BUFFER_SIZE / 2];
+        let (origin_x, origin_y) = canvas.origin();
+        let clip = canvas.clip();
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                if !self.dirty[row][col] {
+                    continue;
+                }
+                self.dirty[row][col] = false;
+                let cell = self.cells[row][col];
+                if cell.attrs.contains(Attributes::COVERED) {
+                    continue;
+                }
+
+                let (sx, sy) = Self::span(&cell);
+                let block_width = self.cell_width * sx as u16;
+                let block_height = self.cell_height * sy as u16;
+                let x = self.origin.0 + col as u16 * self.cell_width;
+                let y = self.origin.1 + row as u16 * self.cell_height;
+                let (fg, bg) = if cell.attrs.contains(Attributes::REVERSE) {
+                    (cell.bg, cell.fg)
+                } else {
+                    (cell.fg, cell.bg)
+                };
+                let hidden = !self.blink_on && cell.attrs.contains(Attributes::BLINK);
+                let glyph = if hidden {
+                    None
+                } else {
+                    self.font.get_glyph(cell.ch)
+                };
+                let underline = cell.attrs.contains(Attributes::UNDERLINE);
+
+                // Send the block in bands of as many pixel rows as fit in the buffer.
+                let band_rows = (buf.len() / block_width as usize).max(1) as u16;
+                let mut band_y = 0;
+                while band_y < block_height {
+                    let rows = band_rows.min(block_height - band_y);
+                    let mut index = 0;
+                    for py in band_y..band_y + rows {
+                        for px in 0..block_width {
+                            let lit = match glyph {
+                                Some(glyph) => glyph
+                                    .cell_pixel((px / sx as u16) as i32, (py / sy as u16) as i32),
+                                None => false,
+                            };
+                            let lit = lit || (underline && !hidden && py == block_height - 1);
+                            buf[index] = if lit { fg } else { bg };
+                            index += 1;
+                        }
+                    }
+                    let band = Rect::new(
+                        x as i32 + origin_x,
+                        (y + band_y) as i32 + origin_y,
+                        block_width as i32,
+                        rows as i32,
+                    );
+                    draw_band(canvas, band, &clip, &buf[..index]);
+                    band_y += rows;
+                }
+            }
+        }
+    }
+
+    /// Shrinks the double-size character drawn over or from a cell back to
+    /// one cell, uncovering the cells around it.
+    fn release(&mut self, col: usize, row: usize) {
+        let Some((col, row)) = self.anchor(col, row) else {
+            return;
+        };
+        let cell = self.cells[row][col];
+        let (w, h) = Self::span(&cell);
+        let size = Attributes::DOUBLE_WIDTH | Attributes::DOUBLE_HEIGHT;
+        self.update(col, row, cell.with_attrs(cell.attrs.without(size)));
+        for r in row..row + h {
+            for c in col..col + w {
+                if (c, r) != (col, row) {
+                    self.update(c, r, Cell::new(' ', cell.fg, cell.bg));
+                }
+            }
+        }
+    }
+
+    /// Returns the cell a character covering a cell is drawn from, the cell
+    /// itself when it isn't covered.
+    fn anchor(&self, col: usize, row: usize) -> Option<(usize, usize)> {
+        if !self.cells[row][col].attrs.contains(Attributes::COVERED) {
+            return Some((col, row));
+        }
+        let candidates = [(1, 0), (0, 1), (1, 1)];
+        candidates.into_iter().find_map(|(dc, dr)| {
+            let (c, r) = (col.checked_sub(dc)?, row.checked_sub(dr)?);
+            let cell = &self.cells[r][c];
+            let (w, h) = Self::span(cell);
+            let covers = !cell.attrs.contains(Attributes::COVERED) && col < c + w && row < r + h;
+            covers.then_some((c, r))
+        })
+    }
+
+    /// Returns the number of cells covered by a cell as `(columns, rows)`.
+    fn span(cell: &Cell) -> (usize, usize) {
+        let w = if cell.attrs.contains(Attributes::DOUBLE_WIDTH) {
+            2
+        } else {
+            1
+        };
+        let h = if cell.attrs.contains(Attributes::DOUBLE_HEIGHT) {
+            2
+        } else {
+            1
+        };
+        (w, h)
+    }
+
+    /// Stores a cell, marking it dirty when it changed.
+    fn update(&mut self, col: usize, row: usize, cell: Cell) {
+        if self.cells[row][col] != cell {
+            self.cells[row][col] = cell;
+            self.dirty[row][col] = true;
+        }
+    }
+}
+
+/// Draws a band of a cell, in canvas coordinates, through one block when
+/// it's all inside the clip and a span a row otherwise.
+fn draw_band<C: Canvas + ?Sized>(canvas: &mut C, band: Rect, clip: &Rect, colors: &[u16]) {
+    let visible = band.intersect(clip);
+    if visible.is_empty() {
+        return;
+    }
+    if visible == band {
+        return canvas.draw_block(band.x as u16, band.y as u16, band.width as u16, colors);
+    }
+    let (left, right) = (
+        (visible.x - band.x) as usize,
+        (visible.right() - band.x) as usize,
+    );
+    for y in visible.y..visible.bottom() {
+        let start = (y - band.y) as usize * band.width as usize;
+        let row = &colors[start + left..start + right];
+        canvas.draw_span(visible.x as u16, y as u16, row);
+    }
+}
+
+/// A grid of character cells that can be drawn on, such as a `TextBuffer`.
+pub trait CellGrid {
+    /// Returns the size of the grid as `(columns, rows)`.
+    fn size(&self) -> (usize, usize);
+
+    /// Sets the cell at a position. Positions outside the grid are ignored.
+    fn set_cell(&mut self, col: usize, row: usize, cell: Cell);
+}
+
+impl<'f, const COLS: usize, const ROWS: usize> CellGrid for TextBuffer<'f, COLS, ROWS> {
+    fn size(&self) -> (usize, usize) {
+        (COLS, ROWS)
+    }
+
+    fn set_cell(&mut self, col: usize, row: usize, cell: Cell) {
+        self.set(col, row, cell);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::String;
+
+    use super::*;
+    use crate::framebuffer::Framebuffer;
+    use crate::testing::{buffer, read_cell, read_line, CODE_FONT};
+
+    const FG: u16 = 0xffff;
+    const BG: u16 = 0x0001;
+    /// the color of pixels the buffer didn't draw
+    const UNDRAWN: u16 = 0xf800;
+
+    type Buffer = TextBuffer<'static, 4, 3>;
+
+    /// Renders a buffer on a framebuffer filled with `UNDRAWN`, then checks
+    /// the framebuffer.
+    fn render(text: &mut Buffer, check: impl FnOnce(&Framebuffer)) {
+        let mut buffer = buffer(32, 6);
+        let mut framebuffer = Framebuffer::new(&mut buffer, 32, 6);
+        framebuffer.fill(UNDRAWN);
+        text.render(&mut framebuffer);
+        check(&framebuffer);
+    }
+
+    /// Returns the rows of characters drawn, with `!` for undrawn cells.
+    fn rows(framebuffer: &Framebuffer) -> [String; 3] {
+        [0, 1, 2].map(|row| {
+            (0..4)
+                .map(|col| match read_cell(framebuffer, col * 8, row * 2) {
+                    (_, _, UNDRAWN) => '!',
+                    (c, _, _) => c,
+                })
+                .collect()
+        })
+    }
+
+    fn is_covered(text: &Buffer, col: usize, row: usize) -> bool {
+        text.get(col, row)
+            .unwrap()
+            .attrs
+            .contains(Attributes::COVERED)
+    }
+
+    #[test]
+    fn renders_only_changed_cells() {
+        let mut text = Buffer::new(&CODE_FONT, FG, BG);
+        text.print(0, 0, "abcd", FG, BG, Attributes::NONE);
+        render(&mut text, |framebuffer| {
+            assert_eq!(rows(framebuffer), ["abcd", "    ", "    "]);
+        });
+        text.print(1, 0, "bX", FG, BG, Attributes::NONE);
+        text.set(3, 2, Cell::new('Y', BG, FG));
+        render(&mut text, |framebuffer| {
+            assert_eq!(rows(framebuffer), ["!!X!", "!!!!", "!!!Y"]);
+            assert_eq!(read_cell(framebuffer, 24, 4), ('Y', BG, FG));
+        });
+        render(&mut text, |framebuffer| {
+            assert_eq!(rows(framebuffer), ["!!!!", "!!!!", "!!!!"]);
+        });
+        text.invalidate();
+        render(&mut text, |framebuffer| {
+            assert_eq!(rows(framebuffer), ["abXd", "    ", "   Y"]);
+        });
+    }
+
+    #[test]
+    fn draws_attributes() {
+        let mut text = Buffer::new(&CODE_FONT, FG, BG);
+        text.print(0, 0, "r", FG, BG, Attributes::REVERSE);
+        text.print(1, 0, "b", FG, BG, Attributes::BLINK);
+        text.print(2, 0, "u", FG, BG, Attributes::UNDERLINE);
+        render(&mut text, |framebuffer| {
+            assert_eq!(read_cell(framebuffer, 0, 0), ('r', BG, FG));
+            assert_eq!(read_line(framebuffer, 0, 0, 2), "rb");
+            // The underline is the bottom row of the cell.
+            assert_eq!(framebuffer.get_pixel(16, 1), Some(FG));
+        });
+        text.toggle_blink();
+        render(&mut text, |framebuffer| {
+            assert_eq!(rows(framebuffer)[0], "! !!");
+        });
+    }
+
+    #[test]
+    fn double_size_cells_cover_their_neighbours() {
+        let mut text = Buffer::new(&CODE_FONT, FG, BG);
+        let double = Attributes::DOUBLE_WIDTH | Attributes::DOUBLE_HEIGHT;
+        text.print(1, 1, "A", FG, BG, double);
+        assert!(is_covered(&text, 2, 1));
+        assert!(is_covered(&text, 1, 2));
+        assert!(is_covered(&text, 2, 2));
+        render(&mut text, |framebuffer| {
+            // 'A' is 0x41, its second and last pixels set, each drawn 2x2.
+            for y in 2..6 {
+                let row: String = (8..24)
+                    .map(|x| match framebuffer.get_pixel(x, y) {
+                        Some(FG) => '#',
+                        _ => '.',
+                    })
+                    .collect();
+                let expected = if y < 4 {
+                    "..##..........##"
+                } else {
+                    "................"
+                };
+                assert_eq!(row, expected);
+            }
+        });
+
+        // Without room the attributes are dropped.
+        text.print(3, 2, "B", FG, BG, double);
+        assert!(text.get(3, 2).unwrap().attrs == Attributes::NONE);
+    }
+
+    #[test]
+    fn overwriting_a_covered_cell_shrinks_the_character_over_it() {
+        let mut text = Buffer::new(&CODE_FONT, FG, BG);
+        let double = Attributes::DOUBLE_WIDTH | Attributes::DOUBLE_HEIGHT;
+        text.print(0, 0, "A", FG, BG, double);
+        render(&mut text, |_| {});
+
+        text.set(1, 1, Cell::new('B', FG, BG));
+        assert!(text.get(0, 0).unwrap().attrs == Attributes::NONE);
+        assert!(!is_covered(&text, 1, 0));
+        assert!(!is_covered(&text, 0, 1));
+        render(&mut text, |framebuffer| {
+            assert_eq!(rows(framebuffer), ["A !!", " B!!", "!!!!"]);
+        });
+        text.invalidate();
+        render(&mut text, |framebuffer| {
+            assert_eq!(rows(framebuffer), ["A   ", " B  ", "    "]);
+        });
+    }
+
+    #[test]
+    fn double_size_cells_over_double_size_cells() {
+        let mut text = Buffer::new(&CODE_FONT, FG, BG);
+        text.print(1, 0, "A", FG, BG, Attributes::DOUBLE_WIDTH);
+        text.print(0, 0, "B", FG, BG, Attributes::DOUBLE_WIDTH);
+        assert!(is_covered(&text, 1, 0));
+        assert!(!is_covered(&text, 2, 0));
+        text.invalidate();
+        render(&mut text, |framebuffer| {
+            assert_eq!(read_line(framebuffer, 16, 0, 2), "  ");
+        });
+
+        // A double-size character written over its own covered cell.
+        text.print(0, 0, "C", FG, BG, Attributes::DOUBLE_WIDTH);
+        assert!(is_covered(&text, 1, 0));
+        text.print(1, 0, "D", FG, BG, Attributes::DOUBLE_WIDTH);
+        assert!(text.get(0, 0).unwrap().attrs == Attributes::NONE);
+        assert!(is_covered(&text, 2, 0));
+    }
+
+    #[test]
+    fn renders_clipped() {
+        let mut text = Buffer::new(&CODE_FONT, FG, BG);
+        text.print(0, 0, "abcd", FG, BG, Attributes::NONE);
+        let mut buffer = buffer(32, 6);
+        let mut framebuffer = Framebuffer::new(&mut buffer, 32, 6);
+        framebuffer.fill(UNDRAWN);
+        framebuffer.viewport_mut().push_clip(4, 0, 16, 6);
+        text.render(&mut framebuffer);
+        framebuffer.viewport_mut().pop();
+        assert_eq!(framebuffer.get_pixel(3, 0), Some(UNDRAWN));
+        assert_eq!(read_cell(&framebuffer, 8, 0), ('b', FG, BG));
+        assert_eq!(framebuffer.get_pixel(20, 1), Some(UNDRAWN));
+    }
+}
@@ -0,0 +1,241 @@
+use core::fmt;
+
+use crate::font::Font;
+use crate::text::{Bounds, TextCanvas};
+
+/// A pen drawing text as it is written, on the display or a framebuffer.
+///
+/// ```ignore
+/// let mut cursor = TextCursor::new(&mut display, &fonts::VGA2_8X16, WHITE, Some(BLACK));
+/// write!(cursor, "Mines: {} Time: {:03}", n, t).unwrap();
+/// ```
+///
+/// Lines wrap at the right of the bounds, the whole canvas by default, and
+/// text past their bottom is dropped. With the `ufmt` feature it also
+/// implements `ufmt::uWrite`.
+pub struct TextCursor<'c, 'f, C: TextCanvas + ?Sized> {
+    /// the display or framebuffer
+    canvas: &'c mut C,
+    /// the font of the text
+    font: &'f dyn Font,
+    /// the color of the text
+    color: u16,
+    /// the color behind the text, None to draw only the glyphs
+    background: Option<u16>,
+    /// the rectangle the text is drawn in
+    bounds: Bounds,
+    /// the pen position
+    x: u16,
+    y: u16,
+    /// the last character drawn on the line, for kerning
+    prev: Option<char>,
+}
+
+impl<'c, 'f, C: TextCanvas + ?Sized> TextCursor<'c, 'f, C> {
+    /// Creates a cursor at the top left of the canvas.
+    pub fn new(
+        canvas: &'c mut C,
+        font: &'f dyn Font,
+        color: u16,
+        background: Option<u16>,
+    ) -> TextCursor<'c, 'f, C> {
+        let (width, height) = canvas.size();
+        TextCursor {
+            canvas,
+            font,
+            color,
+            background,
+            bounds: Bounds::new(0, 0, width, height),
+            x: 0,
+            y: 0,
+            prev: None,
+        }
+    }
+
+    /// Returns the cursor drawing in `bounds`, at their top left.
+    pub fn with_bounds(self, bounds: Bounds) -> TextCursor<'c, 'f, C> {
+        TextCursor {
+            bounds,
+            x: bounds.x,
+            y: bounds.y,
+            prev: None,
+            ..self
+        }
+    }
+
+    /// Returns the pen position.
+    pub fn position(&self) -> (u16, u16) {
+        (self.x, self.y)
+    }
+
+    /// Moves the pen, `y` being the top of the line.
+    pub fn set_position(&mut self, x: u16, y: u16) {
+        self.x = x;
+        self.y = y;
+        self.prev = None;
+    }
+
+    /// Moves the pen back to the top left of the bounds.
+    pub fn home(&mut self) {
+        self.set_position(self.bounds.x, self.bounds.y);
+    }
+
+    pub fn set_font(&mut self, font: &'f dyn Font) {
+        self.font = font;
+        self.prev = None;
+    }
+
+    pub fn set_colors(&mut self, color: u16, background: Option<u16>) {
+        self.color = color;
+        self.background = background;
+    }
+
+    /// Returns the canvas, to draw on it between writes.
+    pub fn canvas(&mut self) -> &mut C {
+        self.canvas
+    }
+
+    /// Moves the pen to the start of the next line.
+    pub fn new_line(&mut self) {
+        self.x = self.bounds.x;
+        self.y = self.y.saturating_add(self.font.get_height() as u16);
+        self.prev = None;
+    }
+
+    /// Draws a character at the pen and advances it.
+    pub fn put_char(&mut self, c: char) {
+        match c {
+            '\n' => return self.new_line(),
+            '\r' => {
+                self.x = self.bounds.x;
+                self.prev = None;
+                return;
+            }
+            _ => {}
+        }
+        let font = self.font;
+        let Some(glyph) = font.get_glyph(c) else {
+            return;
+        };
+        if let Some(p) = self.prev {
+            let kerning = font.get_kerning(p, c) as i16;
+            self.x = self.x.saturating_add_signed(kerning).max(self.bounds.x);
+        }
+        self.prev = Some(c);
+        let w = glyph.advance as u16;
+        if w == 0 {
+            return;
+        }
+        let right = self.bounds.x.saturating_add(self.bounds.width);
+        if self.x.saturating_add(w) > right {
+            self.new_line();
+        }
+        let height = font.get_height() as u16;
+        let bottom = self.bounds.y.saturating_add(self.bounds.height);
+        if self.y.saturating_add(height) > bottom {
+            return;
+        }
+        let (x, y) = (self.x as i32, self.y as i32);
+        self.canvas
+            .draw_glyph(x, y, &glyph, height, self.color, self.background);
+        self.x = self.x.saturating_add(w);
+    }
+}
+
+impl<C: TextCanvas + ?Sized> fmt::Write for TextCursor<'_, '_, C> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            self.put_char(c);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<C: TextCanvas + ?Sized> ufmt_write::uWrite for TextCursor<'_, '_, C> {
+    type Error = core::convert::Infallible;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        for c in s.chars() {
+            self.put_char(c);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use super::*;
+    use crate::framebuffer::Framebuffer;
+    use crate::testing::{buffer, read_line, CODE_FONT};
+
+    #[test]
+    fn a_pen_at_the_end_of_the_coordinates_wraps_instead_of_overflowing() {
+        let mut pixels = buffer(32, 4);
+        let mut frame = Framebuffer::new(&mut pixels, 32, 4);
+        let mut cursor = TextCursor::new(&mut frame, &CODE_FONT, 0xffff, Some(0))
+            .with_bounds(Bounds::new(0, 0, u16::MAX - 2, u16::MAX));
+        cursor.set_position(u16::MAX - 4, 0);
+        cursor.put_char('a');
+        cursor.put_char('b');
+        assert_eq!(cursor.position(), (16, 2));
+        assert_eq!(read_line(&frame, 0, 2, 4), "ab  ");
+    }
+
+    #[test]
+    fn formatted_text_is_drawn_from_the_pen() {
+        let mut pixels = buffer(96, 2);
+        let mut frame = Framebuffer::new(&mut pixels, 96, 2);
+        let mut cursor = TextCursor::new(&mut frame, &CODE_FONT, 0xffff, None);
+        write!(cursor, "n={} {:03}", 4, 7).unwrap();
+        assert_eq!(cursor.position(), (56, 0));
+        assert_eq!(read_line(&frame, 0, 0, 12), "n=4 007     ");
+    }
+
+    #[test]
+    fn text_wraps_at_the_right_of_the_bounds() {
+        let mut pixels = buffer(48, 6);
+        let mut frame = Framebuffer::new(&mut pixels, 48, 6);
+        let mut cursor = TextCursor::new(&mut frame, &CODE_FONT, 0xffff, None)
+            .with_bounds(Bounds::new(8, 0, 32, 4));
+        cursor.write_str("abcdefghijk").unwrap();
+        // Text past the bottom is dropped.
+        assert_eq!(cursor.position(), (8, 4));
+        assert_eq!(read_line(&frame, 0, 0, 6), " abcd ");
+        assert_eq!(read_line(&frame, 0, 2, 6), " efgh ");
+        assert_eq!(read_line(&frame, 0, 4, 6), "      ");
+    }
+
+    #[test]
+    fn a_new_line_goes_back_to_the_left_of_the_bounds() {
+        let mut pixels = buffer(48, 6);
+        let mut frame = Framebuffer::new(&mut pixels, 48, 6);
+        let mut cursor = TextCursor::new(&mut frame, &CODE_FONT, 0xffff, None)
+            .with_bounds(Bounds::new(8, 0, 40, 6));
+        cursor.write_str("ab").unwrap();
+        cursor.new_line();
+        assert_eq!(cursor.position(), (8, 2));
+        cursor.write_str("c\nd\re").unwrap();
+        assert_eq!(cursor.position(), (16, 4));
+        assert_eq!(read_line(&frame, 0, 0, 6), " ab   ");
+        assert_eq!(read_line(&frame, 0, 2, 6), " c    ");
+        assert_eq!(read_line(&frame, 0, 4, 6), " e    ");
+    }
+
+    /// `uwrite!` of the `ufmt` crate writes through these.
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn text_is_written_through_u_write() {
+        use ufmt_write::uWrite;
+
+        let mut pixels = buffer(48, 2);
+        let mut frame = Framebuffer::new(&mut pixels, 48, 2);
+        let mut cursor = TextCursor::new(&mut frame, &CODE_FONT, 0xffff, None);
+        uWrite::write_str(&mut cursor, "n=").unwrap();
+        uWrite::write_char(&mut cursor, '4').unwrap();
+        assert_eq!(cursor.position(), (24, 0));
+        assert_eq!(read_line(&frame, 0, 0, 6), "n=4   ");
+    }
+}
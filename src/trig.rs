@@ -0,0 +1,29 @@
+/// The fixed-point one of `sin_cos`, 14 fractional bits.
+pub const ONE: i32 = 1 << 14;
+
+/// sin(0°) to sin(90°), with 14 fractional bits.
+#[rustfmt::skip]
+const SIN: [i16; 91] = [
+    0, 286, 572, 857, 1143, 1428, 1713, 1997, 2280, 2563,
+    2845, 3126, 3406, 3686, 3964, 4240, 4516, 4790, 5063, 5334,
+    5604, 5872, 6138, 6402, 6664, 6924, 7182, 7438, 7692, 7943,
+    8192, 8438, 8682, 8923, 9162, 9397, 9630, 9860, 10087, 10311,
+    10531, 10749, 10963, 11174, 11381, 11585, 11786, 11982, 12176, 12365,
+    12551, 12733, 12911, 13085, 13255, 13421, 13583, 13741, 13894, 14044,
+    14189, 14330, 14466, 14598, 14726, 14849, 14968, 15082, 15191, 15296,
+    15396, 15491, 15582, 15668, 15749, 15826, 15897, 15964, 16026, 16083,
+    16135, 16182, 16225, 16262, 16294, 16322, 16344, 16362, 16374, 16382,
+    16384,
+];
+
+/// Returns the sine and cosine of an angle in degrees, with 14 fractional bits.
+pub fn sin_cos(degrees: i32) -> (i32, i32) {
+    let d = degrees.rem_euclid(360);
+    let sin = |d: i32| match d {
+        0..=90 => SIN[d as usize] as i32,
+        91..=180 => SIN[(180 - d) as usize] as i32,
+        181..=270 => -(SIN[(d - 180) as usize] as i32),
+        _ => -(SIN[(360 - d) as usize] as i32),
+    };
+    (sin(d), sin((d + 90) % 360))
+}
@@ -0,0 +1,902 @@
+use crate::text_buffer::{Cell, CellGrid};
+
+/// Box drawing and block characters, found in the `vga2_*` fonts.
+mod glyph {
+    pub const SINGLE: [char; 6] = ['┌', '┐', '└', '┘', '─', '│'];
+    pub const DOUBLE: [char; 6] = ['╔', '╗', '╚', '╝', '═', '║'];
+    pub const LIGHT_SHADE: char = '░';
+    pub const FULL_BLOCK: char = '█';
+    pub const LEFT_HALF_BLOCK: char = '▌';
+    pub const HORIZONTAL: char = '─';
+}
+
+/// An input event, e.g. from buttons or a keyboard.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Move the focus to the next widget.
+    Next,
+    /// Move the focus to the previous widget.
+    Previous,
+    /// Activate the focused item.
+    Select,
+    /// Leave or dismiss.
+    Cancel,
+}
+
+/// The outcome of an event sent to a widget.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Response {
+    /// The widget did not use the event.
+    Ignored,
+    /// The widget used the event, its value did not change.
+    Handled,
+    /// The value of the widget changed.
+    Changed,
+    /// An item was activated, e.g. a menu entry or a dialog button.
+    Activated(usize),
+    /// The widget was dismissed.
+    Cancelled,
+}
+
+/// Colors used to draw widgets.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    /// the text color
+    pub fg: u16,
+    /// the background color
+    pub bg: u16,
+    /// the frame color
+    pub frame: u16,
+    /// the text color of the highlight bar
+    pub highlight_fg: u16,
+    /// the background color of the highlight bar
+    pub highlight_bg: u16,
+}
+
+impl Theme {
+    /// White on blue, with a cyan highlight bar.
+    pub const CLASSIC: Theme = Theme {
+        fg: 0xFFFF,
+        bg: 0x0015,
+        frame: 0xAD55,
+        highlight_fg: 0x0000,
+        highlight_bg: 0x0555,
+    };
+}
+
+/// A rectangle of cells.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rect {
+    pub col: usize,
+    pub row: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub const fn new(col: usize, row: usize, width: usize, height: usize) -> Rect {
+        Rect {
+            col,
+            row,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the rectangle inside a one cell frame.
+    pub const fn inner(&self) -> Rect {
+        Rect {
+            col: self.col + 1,
+            row: self.row + 1,
+            width: self.width.saturating_sub(2),
+            height: self.height.saturating_sub(2),
+        }
+    }
+}
+
+/// A text-mode widget.
+pub trait Widget {
+    /// Draws the widget on the grid.
+    fn draw(&self, grid: &mut dyn CellGrid, theme: &Theme, focused: bool);
+
+    /// Handles an input event.
+    fn handle(&mut self, _event: Event) -> Response {
+        Response::Ignored
+    }
+
+    /// Returns whether the widget can take the focus.
+    fn focusable(&self) -> bool {
+        true
+    }
+}
+
+/// Fills a rectangle with one cell.
+pub fn fill(grid: &mut dyn CellGrid, rect: Rect, cell: Cell) {
+    for row in rect.row..rect.row + rect.height {
+        for col in rect.col..rect.col + rect.width {
+            grid.set_cell(col, row, cell);
+        }
+    }
+}
+
+/// Writes a string on one row, cut at `max_width` cells.
+/// Returns the number of cells written.
+pub fn print(
+    grid: &mut dyn CellGrid,
+    col: usize,
+    row: usize,
+    text: &str,
+    max_width: usize,
+    fg: u16,
+    bg: u16,
+) -> usize {
+    let mut n = 0;
+    for c in text.chars().take(max_width) {
+        grid.set_cell(col + n, row, Cell::new(c, fg, bg));
+        n += 1;
+    }
+    n
+}
+
+/// Draws a frame around the edge of a rectangle, with a single or double line.
+pub fn draw_frame(grid: &mut dyn CellGrid, rect: Rect, double: bool, fg: u16, bg: u16) {
+    if rect.width < 2 || rect.height < 2 {
+        return;
+    }
+    let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] =
+        if double { glyph::DOUBLE } else { glyph::SINGLE };
+    let right = rect.col + rect.width - 1;
+    let bottom = rect.row + rect.height - 1;
+    grid.set_cell(rect.col, rect.row, Cell::new(top_left, fg, bg));
+    grid.set_cell(right, rect.row, Cell::new(top_right, fg, bg));
+    grid.set_cell(rect.col, bottom, Cell::new(bottom_left, fg, bg));
+    grid.set_cell(right, bottom, Cell::new(bottom_right, fg, bg));
+    for col in rect.col + 1..right {
+        grid.set_cell(col, rect.row, Cell::new(horizontal, fg, bg));
+        grid.set_cell(col, bottom, Cell::new(horizontal, fg, bg));
+    }
+    for row in rect.row + 1..bottom {
+        grid.set_cell(rect.col, row, Cell::new(vertical, fg, bg));
+        grid.set_cell(right, row, Cell::new(vertical, fg, bg));
+    }
+}
+
+/// Draws a framed box with a centered title, and clears its inside.
+fn draw_box(grid: &mut dyn CellGrid, rect: Rect, title: &str, double: bool, theme: &Theme) {
+    fill(grid, rect.inner(), Cell::new(' ', theme.fg, theme.bg));
+    draw_frame(grid, rect, double, theme.frame, theme.bg);
+    let len = title.chars().count().min(rect.width.saturating_sub(4));
+    if len > 0 {
+        let col = rect.col + (rect.width - len) / 2;
+        print(grid, col, rect.row, title, len, theme.fg, theme.bg);
+    }
+}
+
+/// A framed window with a title.
+pub struct Window<'a> {
+    pub rect: Rect,
+    pub title: &'a str,
+    /// draw the frame with a double line
+    pub double: bool,
+}
+
+impl<'a> Window<'a> {
+    pub const fn new(rect: Rect, title: &'a str) -> Self {
+        Self {
+            rect,
+            title,
+            double: false,
+        }
+    }
+}
+
+impl<'a> Widget for Window<'a> {
+    fn draw(&self, grid: &mut dyn CellGrid, theme: &Theme, _focused: bool) {
+        draw_box(grid, self.rect, self.title, self.double, theme);
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+}
+
+/// A vertical menu with a highlight bar.
+///
+/// `Up` and `Down` move the highlight bar, `Select` activates the highlighted item.
+pub struct Menu<'a> {
+    pub col: usize,
+    pub row: usize,
+    pub width: usize,
+    pub items: &'a [&'a str],
+    pub selected: usize,
+}
+
+impl<'a> Menu<'a> {
+    pub const fn new(col: usize, row: usize, width: usize, items: &'a [&'a str]) -> Self {
+        Self {
+            col,
+            row,
+            width,
+            items,
+            selected: 0,
+        }
+    }
+}
+
+impl<'a> Widget for Menu<'a> {
+    fn draw(&self, grid: &mut dyn CellGrid, theme: &Theme, focused: bool) {
+        for (i, item) in self.items.iter().enumerate() {
+            let (fg, bg) = if i == self.selected && focused {
+                (theme.highlight_fg, theme.highlight_bg)
+            } else {
+                (theme.fg, theme.bg)
+            };
+            let row = self.row + i;
+            fill(
+                grid,
+                Rect::new(self.col, row, self.width, 1),
+                Cell::new(' ', fg, bg),
+            );
+            print(
+                grid,
+                self.col + 1,
+                row,
+                item,
+                self.width.saturating_sub(2),
+                fg,
+                bg,
+            );
+        }
+    }
+
+    fn handle(&mut self, event: Event) -> Response {
+        match event {
+            Event::Up if self.selected > 0 => {
+                self.selected -= 1;
+                Response::Changed
+            }
+            Event::Down if self.selected + 1 < self.items.len() => {
+                self.selected += 1;
+                Response::Changed
+            }
+            Event::Select if !self.items.is_empty() => Response::Activated(self.selected),
+            _ => Response::Ignored,
+        }
+    }
+}
+
+/// A checkbox with a label, toggled by `Select`.
+pub struct Checkbox<'a> {
+    pub col: usize,
+    pub row: usize,
+    pub label: &'a str,
+    pub checked: bool,
+}
+
+impl<'a> Checkbox<'a> {
+    pub const fn new(col: usize, row: usize, label: &'a str, checked: bool) -> Self {
+        Self {
+            col,
+            row,
+            label,
+            checked,
+        }
+    }
+}
+
+impl<'a> Widget for Checkbox<'a> {
+    fn draw(&self, grid: &mut dyn CellGrid, theme: &Theme, focused: bool) {
+        let (fg, bg) = if focused {
+            (theme.highlight_fg, theme.highlight_bg)
+        } else {
+            (theme.fg, theme.bg)
+        };
+        let mark = if self.checked { "[X] " } else { "[ ] " };
+        let n = print(grid, self.col, self.row, mark, 4, fg, bg);
+        print(grid, self.col + n, self.row, self.label, usize::MAX, fg, bg);
+    }
+
+    fn handle(&mut self, event: Event) -> Response {
+        match event {
+            Event::Select => {
+                self.checked = !self.checked;
+                Response::Changed
+            }
+            _ => Response::Ignored,
+        }
+    }
+}
+
+/// A horizontal slider, adjusted by `Left` and `Right`.
+pub struct Slider {
+    pub col: usize,
+    pub row: usize,
+    pub width: usize,
+    pub value: i32,
+    pub min: i32,
+    pub max: i32,
+    pub step: i32,
+}
+
+impl Slider {
+    pub const fn new(col: usize, row: usize, width: usize, min: i32, max: i32) -> Self {
+        Self {
+            col,
+            row,
+            width,
+            value: min,
+            min,
+            max,
+            step: 1,
+        }
+    }
+}
+
+impl Widget for Slider {
+    fn draw(&self, grid: &mut dyn CellGrid, theme: &Theme, focused: bool) {
+        if self.width == 0 {
+            return;
+        }
+        let knob_fg = if focused {
+            theme.highlight_bg
+        } else {
+            theme.fg
+        };
+        // In i64, and at the left end should min be above max.
+        let (min, max) = (self.min as i64, self.max as i64);
+        let range = (max - min).max(1);
+        let offset = ((self.value as i64).max(min).min(max) - min).max(0);
+        let knob = (offset * (self.width as i64 - 1) / range) as usize;
+        for i in 0..self.width {
+            let cell = if i == knob {
+                Cell::new(glyph::FULL_BLOCK, knob_fg, theme.bg)
+            } else {
+                Cell::new(glyph::HORIZONTAL, theme.frame, theme.bg)
+            };
+            grid.set_cell(self.col + i, self.row, cell);
+        }
+    }
+
+    fn handle(&mut self, event: Event) -> Response {
+        let value = match event {
+            Event::Left => self.value.saturating_sub(self.step).max(self.min),
+            Event::Right => self.value.saturating_add(self.step).min(self.max),
+            _ => return Response::Ignored,
+        };
+        if value == self.value {
+            Response::Handled
+        } else {
+            self.value = value;
+            Response::Changed
+        }
+    }
+}
+
+/// A horizontal progress bar with half-cell resolution.
+pub struct ProgressBar {
+    pub col: usize,
+    pub row: usize,
+    pub width: usize,
+    /// the progress in percent
+    pub percent: u8,
+}
+
+impl ProgressBar {
+    pub const fn new(col: usize, row: usize, width: usize) -> Self {
+        Self {
+            col,
+            row,
+            width,
+            percent: 0,
+        }
+    }
+}
+
+impl Widget for ProgressBar {
+    fn draw(&self, grid: &mut dyn CellGrid, theme: &Theme, _focused: bool) {
+        let halves = self.percent.min(100) as usize * self.width * 2 / 100;
+        for i in 0..self.width {
+            let c = if i * 2 + 2 <= halves {
+                glyph::FULL_BLOCK
+            } else if i * 2 + 1 == halves {
+                glyph::LEFT_HALF_BLOCK
+            } else {
+                glyph::LIGHT_SHADE
+            };
+            grid.set_cell(self.col + i, self.row, Cell::new(c, theme.fg, theme.bg));
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+}
+
+/// A scrolling list with a highlight bar and a scroll bar.
+///
+/// `Up` and `Down` move the highlight bar, `Select` activates the highlighted item.
+pub struct ListView<'a> {
+    pub rect: Rect,
+    pub items: &'a [&'a str],
+    pub selected: usize,
+    /// the index of the first visible item
+    pub scroll: usize,
+}
+
+impl<'a> ListView<'a> {
+    pub const fn new(rect: Rect, items: &'a [&'a str]) -> Self {
+        Self {
+            rect,
+            items,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    /// Scrolls so that the selected item is visible.
+    fn follow_selection(&mut self) {
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + self.rect.height {
+            self.scroll = self.selected + 1 - self.rect.height;
+        }
+    }
+}
+
+impl<'a> Widget for ListView<'a> {
+    fn draw(&self, grid: &mut dyn CellGrid, theme: &Theme, focused: bool) {
+        let text_width = self.rect.width.saturating_sub(1);
+        for i in 0..self.rect.height {
+            let index = self.scroll + i;
+            let (fg, bg) = if index == self.selected && focused {
+                (theme.highlight_fg, theme.highlight_bg)
+            } else {
+                (theme.fg, theme.bg)
+            };
+            let row = self.rect.row + i;
+            fill(
+                grid,
+                Rect::new(self.rect.col, row, text_width, 1),
+                Cell::new(' ', fg, bg),
+            );
+            if let Some(item) = self.items.get(index) {
+                print(grid, self.rect.col, row, item, text_width, fg, bg);
+            }
+        }
+
+        // The scroll bar is only drawn when the items don't fit.
+        let scroll_col = self.rect.col + text_width;
+        let len = self.items.len();
+        for i in 0..self.rect.height {
+            let c = if len <= self.rect.height {
+                ' '
+            } else {
+                let thumb = self.scroll * (self.rect.height - 1) / (len - self.rect.height);
+                if i == thumb {
+                    glyph::FULL_BLOCK
+                } else {
+                    glyph::LIGHT_SHADE
+                }
+            };
+            grid.set_cell(
+                scroll_col,
+                self.rect.row + i,
+                Cell::new(c, theme.frame, theme.bg),
+            );
+        }
+    }
+
+    fn handle(&mut self, event: Event) -> Response {
+        let selected = match event {
+            Event::Up if self.selected > 0 => self.selected - 1,
+            Event::Down if self.selected + 1 < self.items.len() => self.selected + 1,
+            Event::Select if !self.items.is_empty() => return Response::Activated(self.selected),
+            _ => return Response::Ignored,
+        };
+        self.selected = selected;
+        self.follow_selection();
+        Response::Changed
+    }
+}
+
+/// A modal dialog with a message and a row of buttons.
+///
+/// While shown, all events should be sent to the dialog. `Left` and `Right`
+/// choose a button, `Select` activates it and `Cancel` dismisses the dialog.
+pub struct Dialog<'a> {
+    pub rect: Rect,
+    pub title: &'a str,
+    /// the message, lines are separated by `\n`
+    pub message: &'a str,
+    pub buttons: &'a [&'a str],
+    pub selected: usize,
+}
+
+impl<'a> Dialog<'a> {
+    pub const fn new(rect: Rect, title: &'a str, message: &'a str, buttons: &'a [&'a str]) -> Self {
+        Self {
+            rect,
+            title,
+            message,
+            buttons,
+            selected: 0,
+        }
+    }
+}
+
+impl<'a> Widget for Dialog<'a> {
+    fn draw(&self, grid: &mut dyn CellGrid, theme: &Theme, _focused: bool) {
+        draw_box(grid, self.rect, self.title, true, theme);
+        let inner = self.rect.inner();
+        let text_width = inner.width.saturating_sub(2);
+        for (i, line) in self
+            .message
+            .lines()
+            .take(inner.height.saturating_sub(2))
+            .enumerate()
+        {
+            print(
+                grid,
+                inner.col + 1,
+                inner.row + i,
+                line,
+                text_width,
+                theme.fg,
+                theme.bg,
+            );
+        }
+
+        // Buttons are centered on the last row inside the frame.
+        let total: usize = self.buttons.iter().map(|b| b.chars().count() + 4).sum();
+        let mut col = inner.col + inner.width.saturating_sub(total) / 2;
+        let row = inner.row + inner.height.saturating_sub(1);
+        for (i, button) in self.buttons.iter().enumerate() {
+            let (fg, bg) = if i == self.selected {
+                (theme.highlight_fg, theme.highlight_bg)
+            } else {
+                (theme.fg, theme.bg)
+            };
+            col += print(grid, col, row, "[ ", 2, fg, bg);
+            col += print(grid, col, row, button, usize::MAX, fg, bg);
+            col += print(grid, col, row, " ]", 2, fg, bg);
+        }
+    }
+
+    fn handle(&mut self, event: Event) -> Response {
+        match event {
+            Event::Left | Event::Previous if self.selected > 0 => {
+                self.selected -= 1;
+                Response::Changed
+            }
+            Event::Right | Event::Next if self.selected + 1 < self.buttons.len() => {
+                self.selected += 1;
+                Response::Changed
+            }
+            Event::Select if !self.buttons.is_empty() => Response::Activated(self.selected),
+            Event::Cancel => Response::Cancelled,
+            _ => Response::Handled,
+        }
+    }
+}
+
+/// Keeps track of the focused widget of a screen.
+///
+/// Events go to the focused widget first. `Next` and `Previous`, and `Down`
+/// and `Up` when the widget ignores them, move the focus between the
+/// focusable widgets.
+pub struct Focus {
+    /// the index of the focused widget
+    index: usize,
+}
+
+impl Focus {
+    pub const fn new() -> Self {
+        Self { index: 0 }
+    }
+
+    /// Returns the index of the focused widget.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Sends an event to the widgets.
+    ///
+    /// Returns the index of the widget that used the event and its response,
+    /// with `Handled` as response when the focus moved.
+    pub fn handle(&mut self, widgets: &mut [&mut dyn Widget], event: Event) -> (usize, Response) {
+        self.settle(widgets);
+        if let Some(widget) = widgets.get_mut(self.index).filter(|w| w.focusable()) {
+            let response = widget.handle(event);
+            if response != Response::Ignored {
+                return (self.index, response);
+            }
+        }
+        let moved = match event {
+            Event::Next | Event::Down => self.step(widgets, true),
+            Event::Previous | Event::Up => self.step(widgets, false),
+            _ => false,
+        };
+        let response = if moved {
+            Response::Handled
+        } else {
+            Response::Ignored
+        };
+        (self.index, response)
+    }
+
+    /// Draws all the widgets, in order, the one the focus settles on focused.
+    pub fn draw(&self, widgets: &[&mut dyn Widget], grid: &mut dyn CellGrid, theme: &Theme) {
+        let focused = self.focused(widgets);
+        for (i, widget) in widgets.iter().enumerate() {
+            widget.draw(grid, theme, focused == Some(i));
+        }
+    }
+
+    /// Moves the focus to the first focusable widget if needed.
+    fn settle(&mut self, widgets: &[&mut dyn Widget]) {
+        if let Some(index) = self.focused(widgets) {
+            self.index = index;
+        }
+    }
+
+    /// Returns the focused widget, or the first focusable one when it can't
+    /// take the focus, None when none can.
+    fn focused(&self, widgets: &[&mut dyn Widget]) -> Option<usize> {
+        if widgets.get(self.index).is_some_and(|w| w.focusable()) {
+            return Some(self.index);
+        }
+        widgets.iter().position(|w| w.focusable())
+    }
+
+    /// Moves the focus to the next or previous focusable widget, wrapping around.
+    fn step(&mut self, widgets: &[&mut dyn Widget], forward: bool) -> bool {
+        let n = widgets.len();
+        for offset in 1..n {
+            let index = if forward {
+                (self.index + offset) % n
+            } else {
+                (self.index + n - offset) % n
+            };
+            if widgets[index].focusable() {
+                self.index = index;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Default for Focus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::cell::Cell as Flag;
+    use std::string::String;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::testing::CODE_FONT;
+    use crate::text_buffer::TextBuffer;
+
+    const THEME: Theme = Theme::CLASSIC;
+
+    type Screen = TextBuffer<'static, 16, 8>;
+
+    fn screen() -> Screen {
+        Screen::new(&CODE_FONT, THEME.fg, THEME.bg)
+    }
+
+    /// Returns the characters of a row of cells.
+    fn row(screen: &Screen, row: usize) -> String {
+        (0..16)
+            .map(|col| screen.get(col, row).unwrap().ch)
+            .collect()
+    }
+
+    fn is_highlighted(screen: &Screen, col: usize, row: usize) -> bool {
+        screen.get(col, row).unwrap().bg == THEME.highlight_bg
+    }
+
+    /// Sends events to a widget and returns its responses.
+    fn send(widget: &mut dyn Widget, events: &[Event]) -> Vec<Response> {
+        events.iter().map(|&event| widget.handle(event)).collect()
+    }
+
+    /// A widget remembering whether it was last drawn focused.
+    struct Probe {
+        focusable: bool,
+        focused: Flag<bool>,
+    }
+
+    impl Probe {
+        fn new(focusable: bool) -> Probe {
+            Probe {
+                focusable,
+                focused: Flag::new(false),
+            }
+        }
+    }
+
+    impl Widget for Probe {
+        fn draw(&self, _grid: &mut dyn CellGrid, _theme: &Theme, focused: bool) {
+            self.focused.set(focused);
+        }
+
+        fn focusable(&self) -> bool {
+            self.focusable
+        }
+    }
+
+    #[test]
+    fn menu_moves_the_highlight_bar() {
+        use Event::*;
+        use Response::*;
+        let mut menu = Menu::new(0, 0, 8, &["Play", "Scores", "Quit"]);
+        let responses = send(&mut menu, &[Up, Down, Down, Down, Up, Select]);
+        assert_eq!(
+            responses,
+            [Ignored, Changed, Changed, Ignored, Changed, Activated(1)]
+        );
+
+        let mut screen = screen();
+        menu.draw(&mut screen, &THEME, true);
+        assert_eq!(row(&screen, 1), " Scores         ");
+        assert!(is_highlighted(&screen, 7, 1));
+        assert!(!is_highlighted(&screen, 0, 0));
+        menu.draw(&mut screen, &THEME, false);
+        assert!(!is_highlighted(&screen, 0, 1));
+    }
+
+    #[test]
+    fn list_view_scrolls_to_the_selection() {
+        let items = ["one", "two", "three", "four", "five", "six"];
+        let mut list = ListView::new(Rect::new(0, 0, 8, 3), &items);
+        send(&mut list, &[Event::Down; 4]);
+        assert_eq!((list.selected, list.scroll), (4, 2));
+
+        let mut screen = screen();
+        list.draw(&mut screen, &THEME, true);
+        assert_eq!(row(&screen, 0), "three  ░        ");
+        assert_eq!(row(&screen, 1), "four   █        ");
+        assert_eq!(row(&screen, 2), "five   ░        ");
+        assert!(is_highlighted(&screen, 0, 2));
+
+        send(&mut list, &[Event::Up; 4]);
+        assert_eq!((list.selected, list.scroll), (0, 0));
+        assert_eq!(list.handle(Event::Up), Response::Ignored);
+    }
+
+    #[test]
+    fn slider_and_checkbox_change_their_values() {
+        use Response::*;
+        let mut slider = Slider::new(0, 0, 5, 0, 8);
+        slider.step = 3;
+        let responses = send(
+            &mut slider,
+            &[Event::Left, Event::Right, Event::Right, Event::Right],
+        );
+        assert_eq!(responses, [Handled, Changed, Changed, Changed]);
+        assert_eq!(slider.value, 8);
+        assert_eq!(slider.handle(Event::Right), Handled);
+
+        let mut screen = screen();
+        slider.draw(&mut screen, &THEME, false);
+        assert_eq!(row(&screen, 0), "────█           ");
+
+        // A minimum above the maximum or extreme values don't panic.
+        let slider = Slider::new(0, 2, 5, 8, 0);
+        slider.draw(&mut screen, &THEME, false);
+        assert_eq!(row(&screen, 2), "█────           ");
+        let mut slider = Slider::new(0, 2, 5, i32::MIN, i32::MAX);
+        slider.value = i32::MAX;
+        slider.step = i32::MAX;
+        assert_eq!(slider.handle(Event::Right), Handled);
+        slider.draw(&mut screen, &THEME, false);
+        assert_eq!(row(&screen, 2), "────█           ");
+
+        let mut checkbox = Checkbox::new(0, 1, "Sound", false);
+        assert_eq!(
+            send(&mut checkbox, &[Event::Select, Event::Up]),
+            [Changed, Ignored]
+        );
+        checkbox.draw(&mut screen, &THEME, true);
+        assert_eq!(row(&screen, 1), "[X] Sound       ");
+        assert!(is_highlighted(&screen, 0, 1));
+    }
+
+    #[test]
+    fn dialog_chooses_a_button() {
+        use Event::*;
+        use Response::*;
+        let mut dialog = Dialog::new(Rect::new(0, 0, 16, 5), "Quit", "Sure?", &["Yes", "No"]);
+        let responses = send(&mut dialog, &[Left, Right, Right, Down, Select, Cancel]);
+        assert_eq!(
+            responses,
+            [Handled, Changed, Handled, Handled, Activated(1), Cancelled]
+        );
+
+        let mut screen = screen();
+        dialog.draw(&mut screen, &THEME, true);
+        assert_eq!(row(&screen, 0), "╔═════Quit═════╗");
+        assert_eq!(row(&screen, 1), "║ Sure?        ║");
+        assert_eq!(row(&screen, 3), "║[ Yes ][ No ] ║");
+        assert!(is_highlighted(&screen, 8, 3));
+        assert!(!is_highlighted(&screen, 2, 3));
+    }
+
+    #[test]
+    fn focus_moves_between_focusable_widgets() {
+        use Event::*;
+        use Response::*;
+        let mut window = Window::new(Rect::new(0, 0, 16, 8), "Setup");
+        let mut menu = Menu::new(1, 1, 8, &["Easy", "Hard"]);
+        let mut progress = ProgressBar::new(1, 4, 8);
+        let mut checkbox = Checkbox::new(1, 5, "Sound", false);
+        let mut slider = Slider::new(1, 6, 8, 0, 4);
+        let mut focus = Focus::new();
+        {
+            let mut widgets: [&mut dyn Widget; 5] = [
+                &mut window,
+                &mut menu,
+                &mut progress,
+                &mut checkbox,
+                &mut slider,
+            ];
+            let script = [
+                (Down, (1, Changed)),
+                (Down, (3, Handled)),
+                (Select, (3, Changed)),
+                (Next, (4, Handled)),
+                (Right, (4, Changed)),
+                (Right, (4, Changed)),
+                (Next, (1, Handled)),
+                (Previous, (4, Handled)),
+                (Up, (3, Handled)),
+                (Up, (1, Handled)),
+                (Select, (1, Activated(1))),
+            ];
+            for (event, expected) in script {
+                assert_eq!(focus.handle(&mut widgets, event), expected, "{event:?}");
+            }
+
+            let mut screen = screen();
+            focus.draw(&widgets, &mut screen, &THEME);
+            assert_eq!(row(&screen, 0), "┌────Setup─────┐");
+            assert_eq!(row(&screen, 2), "│ Hard         │");
+            assert!(is_highlighted(&screen, 1, 2));
+            assert!(!is_highlighted(&screen, 1, 5));
+        }
+        assert_eq!(menu.selected, 1);
+        assert!(checkbox.checked);
+        assert_eq!(slider.value, 2);
+    }
+
+    #[test]
+    fn focus_draws_only_focusable_widgets_focused() {
+        let (mut first, mut second) = (Probe::new(false), Probe::new(true));
+        let widgets: [&mut dyn Widget; 2] = [&mut first, &mut second];
+        Focus::new().draw(&widgets, &mut screen(), &THEME);
+        assert!(!first.focused.get());
+        assert!(second.focused.get());
+
+        let mut nothing = Probe::new(false);
+        let mut widgets: [&mut dyn Widget; 1] = [&mut nothing];
+        let mut focus = Focus::new();
+        assert_eq!(
+            focus.handle(&mut widgets, Event::Next),
+            (0, Response::Ignored)
+        );
+        focus.draw(&widgets, &mut screen(), &THEME);
+        assert!(!nothing.focused.get());
+    }
+}
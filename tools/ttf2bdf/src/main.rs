@@ -0,0 +1,175 @@
+//! Renders a TrueType or OpenType font at a pixel size into a BDF font,
+//! with 1, 2 or 4 bits of coverage per pixel, ready for `assets/fonts`.
+//!
+//! ```sh
+//! cargo run --release -- DejaVuSans.ttf 16 4 20-7e > ../../assets/fonts/dejavu-sans-16-aa4.bdf
+//! ```
+//!
+//! Grayscale output uses the BDF 2.3 bits per pixel field of `SIZE`. The
+//! copyright notice of the font is kept in `COMMENT` lines; its license goes
+//! next to the BDF, as `assets/fonts/DEJAVU-LICENSE` does for DejaVu Sans.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::process;
+
+use fontdue::{Font, FontSettings};
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: ttf2bdf <font.ttf> <pixel size> <bits per pixel: 1, 2 or 4> <first-last, in hex>"
+    );
+    process::exit(1);
+}
+
+/// Returns the copyright notice of a font, name 0 of its `name` table, or
+/// nothing if it has none.
+fn copyright(data: &[u8]) -> String {
+    let u16_at = |i: usize| {
+        data.get(i..i + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+    };
+    let u32_at = |i: usize| {
+        data.get(i..i + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+    };
+    let find = || -> Option<String> {
+        let tables = u16_at(4)?;
+        let table = (0..tables)
+            .map(|i| 12 + i * 16)
+            .find(|&entry| data.get(entry..entry + 4) == Some(b"name"))?;
+        let table = u32_at(table + 8)?;
+        let (count, strings) = (u16_at(table + 2)?, table + u16_at(table + 4)?);
+        let mut mac = None;
+        for record in (0..count).map(|i| table + 6 + i * 12) {
+            let (platform, encoding, name) =
+                (u16_at(record)?, u16_at(record + 2)?, u16_at(record + 6)?);
+            let (length, offset) = (u16_at(record + 8)?, u16_at(record + 10)?);
+            let bytes = data.get(strings + offset..strings + offset + length)?;
+            match (platform, encoding, name) {
+                // Windows Unicode, UTF-16 big endian.
+                (3, 1, 0) => {
+                    let units: Vec<u16> = bytes
+                        .chunks(2)
+                        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                        .collect();
+                    return Some(String::from_utf16_lossy(&units));
+                }
+                // Macintosh Roman, ASCII for what we need.
+                (1, 0, 0) => mac = Some(bytes.iter().map(|&b| b as char).collect()),
+                _ => {}
+            }
+        }
+        mac
+    };
+    find().unwrap_or_default()
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 5 {
+        usage();
+    }
+    let path = &args[1];
+    let size: f32 = args[2].parse().unwrap_or_else(|_| usage());
+    let bpp: u32 = args[3].parse().unwrap_or_else(|_| usage());
+    if ![1, 2, 4].contains(&bpp) {
+        usage();
+    }
+    let (first, last) = args[4].split_once('-').unwrap_or_else(|| usage());
+    let first = u32::from_str_radix(first, 16).unwrap_or_else(|_| usage());
+    let last = u32::from_str_radix(last, 16).unwrap_or_else(|_| usage());
+
+    let data = fs::read(path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(1);
+    });
+    let copyright = copyright(&data);
+    let font = Font::from_bytes(data, FontSettings::default()).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(1);
+    });
+    let line = font.horizontal_line_metrics(size).unwrap_or_else(|| {
+        eprintln!("{}: no horizontal metrics", path);
+        process::exit(1);
+    });
+    let ascent = line.ascent.ceil() as i32;
+    let descent = (-line.descent).ceil() as i32;
+    let max_level = (1 << bpp) - 1;
+
+    let mut chars = String::new();
+    let mut count = 0;
+    let mut max_width = 0;
+    for c in (first..=last).filter_map(char::from_u32) {
+        if font.lookup_glyph_index(c) == 0 && c != ' ' {
+            continue;
+        }
+        let (metrics, coverage) = font.rasterize(c, size);
+        let advance = metrics.advance_width.round() as i32;
+        max_width = max_width.max(advance);
+        count += 1;
+
+        writeln!(chars, "STARTCHAR U+{:04X}", c as u32).unwrap();
+        writeln!(chars, "ENCODING {}", c as u32).unwrap();
+        writeln!(chars, "SWIDTH {} 0", advance * 1000 / size as i32).unwrap();
+        writeln!(chars, "DWIDTH {} 0", advance).unwrap();
+        writeln!(
+            chars,
+            "BBX {} {} {} {}",
+            metrics.width, metrics.height, metrics.xmin, metrics.ymin
+        )
+        .unwrap();
+        chars.push_str("BITMAP\n");
+        for row in coverage.chunks(metrics.width.max(1)).take(metrics.height) {
+            // Pack the quantized coverage of the row, padded to whole bytes.
+            let mut bits: Vec<u8> = Vec::new();
+            for (i, &v) in row.iter().enumerate() {
+                let level = (v as u32 * max_level + 127) / 255;
+                let bit = i as u32 * bpp;
+                if bit % 8 == 0 {
+                    bits.push(0);
+                }
+                *bits.last_mut().unwrap() |= (level << (8 - bpp - bit % 8)) as u8;
+            }
+            for b in bits {
+                write!(chars, "{:02X}", b).unwrap();
+            }
+            chars.push('\n');
+        }
+        chars.push_str("ENDCHAR\n");
+    }
+
+    let name = font.name().unwrap_or("unknown");
+    println!("STARTFONT 2.3");
+    println!(
+        "COMMENT Rendered from {} at {} pixels by tools/ttf2bdf.",
+        name, size
+    );
+    for line in copyright
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        println!("COMMENT {}", line);
+    }
+    println!(
+        "FONT -misc-{}-medium-r-normal--{}-0-75-75-p-0-iso10646-1",
+        name.replace(' ', ""),
+        size
+    );
+    println!("SIZE {} 75 75 {}", size, bpp);
+    println!(
+        "FONTBOUNDINGBOX {} {} 0 {}",
+        max_width,
+        ascent + descent,
+        -descent
+    );
+    println!("STARTPROPERTIES 2");
+    println!("FONT_ASCENT {}", ascent);
+    println!("FONT_DESCENT {}", descent);
+    println!("ENDPROPERTIES");
+    println!("CHARS {}", count);
+    print!("{}", chars);
+    println!("ENDFONT");
+}